@@ -0,0 +1,115 @@
+//! Compares checking every Tower against every Enemy with checking only the
+//! Enemies the spatial hash finds near each Tower, and times whole game 
+//! ticks with as many Enemies and Towers. Run with 
+//! `cargo bench --bench collision`.
+
+extern crate rust_game;
+
+use rust_game::ecs::{World, EntityId, systems};
+use rust_game::entity::enemy::spawn_enemy;
+use rust_game::entity::towers::tower::spawn_tower;
+use rust_game::game::GameController;
+use rust_game::levels::map::MapIdx;
+use rust_game::levels::spatial_hash::SpatialHash;
+use rust_game::math::Point2;
+use rust_game::math::random::{create_seed, from_seed, next_u32, RNG};
+use rust_game::game::consts::{map_idx_to_point2, WINDOW_WIDTH};
+use rust_game::traits::draw::check_collision;
+use std::time::{Duration, Instant};
+
+const ENEMIES: usize = 1000;
+const TOWERS: usize = 100;
+const ITERATIONS: u32 = 100;
+const DT: f64 = 1.0 / 120.0;
+
+fn random_point(rng: &mut RNG) -> Point2 {
+    Point2 {
+        x: (next_u32(rng) % WINDOW_WIDTH as u32) as f64,
+        y: (next_u32(rng) % WINDOW_WIDTH as u32) as f64,
+    }
+}
+
+/// Returns, for each Tower, the Enemies within range and the Enemies its 
+/// bullet touches, checking every Enemy.
+fn brute_force(world: &World) -> Vec<(Vec<EntityId>, Vec<EntityId>)> {
+    let enemies = world.enemies.ids();
+    world.towers.iter().map(|(_, tower)| {
+        let center = tower.base_shape.center_point();
+        let in_range = enemies.iter().copied().filter(|&id| {
+            let dir = world.shapes[id].center_point() - center;
+            dir.x.abs() + dir.y.abs() < tower.range
+        }).collect();
+        let hit = enemies.iter().copied().filter(|&id| check_collision(tower.bullet.shape, world.shapes[id])).collect();
+        (in_range, hit)
+    }).collect()
+}
+
+/// Returns the same as brute_force(), but only checks the Enemies found by 
+/// the spatial hash. The spatial hash is rebuilt from scratch each call, 
+/// rather than only indexing the Enemies which moved as the game does.
+fn spatial_hash(grid: &mut SpatialHash, world: &World) -> Vec<(Vec<EntityId>, Vec<EntityId>)> {
+    grid.clear();
+    systems::index(world, &world.enemies, grid);
+    world.towers.iter().map(|(_, tower)| {
+        let center = tower.base_shape.center_point();
+        let in_range = grid.query_radius(center, tower.range).into_iter().filter_map(|i| world.enemies.id_at(i)).filter(|&id| {
+            let dir = world.shapes[id].center_point() - center;
+            dir.x.abs() + dir.y.abs() < tower.range
+        }).collect();
+        let hit = grid.query_shape(&tower.bullet.shape).into_iter().filter_map(|i| world.enemies.id_at(i))
+            .filter(|&id| check_collision(tower.bullet.shape, world.shapes[id])).collect();
+        (in_range, hit)
+    }).collect()
+}
+
+/// Returns a game with the Enemies and Towers spread over the floor of its 
+/// Level. Each Enemy heads for another Tile of floor.
+fn crowded_game(rng: &mut RNG) -> GameController {
+    let mut controller = GameController::new(create_seed(true)).expect("Couldn't create the game!");
+    let model = &mut controller.model;
+    let mut floor: Vec<MapIdx> = model.level.map.iter()
+        .filter(|(_, tile)| !tile.variant.is_solid())
+        .map(|(idx, _)| *idx)
+        .collect();
+    floor.sort_by_key(|idx| (idx.y, idx.x));
+    let random_floor = |rng: &mut RNG| map_idx_to_point2(floor[next_u32(rng) as usize % floor.len()]);
+    for _ in 0..ENEMIES {
+        let enemy = spawn_enemy(&mut model.world, random_floor(rng));
+        model.world.enemies[enemy].path = vec![random_floor(rng)];
+    }
+    for _ in 0..TOWERS {
+        spawn_tower(&mut model.world, random_floor(rng));
+    }
+    controller
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let mut rng = from_seed([3; 32]);
+    let mut world = World::new();
+    for _ in 0..ENEMIES {
+        spawn_enemy(&mut world, random_point(&mut rng));
+    }
+    for _ in 0..TOWERS {
+        spawn_tower(&mut world, random_point(&mut rng));
+    }
+    let mut grid = SpatialHash::new();
+
+    assert!(brute_force(&world) == spatial_hash(&mut grid, &world));
+
+    let brute = time(|| { brute_force(&world); });
+    let hashed = time(|| { spatial_hash(&mut grid, &world); });
+    let mut controller = crowded_game(&mut rng);
+    let ticked = time(|| { controller.tick(DT); });
+    println!("{} enemies, {} towers", ENEMIES, TOWERS);
+    println!("brute force:  {:?} per tick", brute);
+    println!("spatial hash: {:?} per tick", hashed);
+    println!("game tick:    {:?} per tick", ticked);
+}
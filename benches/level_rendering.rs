@@ -0,0 +1,124 @@
+//! Compares drawing a 200x200 Level one Tile at a time, as the GameView used
+//! to, with drawing it from a LevelBatch. The Graphics backend only counts
+//! what it's asked to draw, so the times are the time spent preparing the
+//! draw calls. Run with `cargo bench --bench level_rendering`.
+
+extern crate rust_game;
+
+use rust_game::game::{GameModel, LevelBatch, Rules, TextureAtlas};
+use rust_game::game::consts::{SPRITE_SHEET_PATH, TILE_SIZE, WINDOW_HEIGHT, WINDOW_WIDTH};
+use rust_game::levels::Level;
+use rust_game::levels::map::MapIdx;
+use rust_game::math::random::create_seed;
+use rust_game::render::SoftwareTexture;
+use rust_game::traits::draw::Draw;
+use graphics::{Context, Graphics, Transformed};
+use graphics::draw_state::DrawState;
+use graphics::types::Color;
+use std::time::{Duration, Instant};
+
+const LEVEL_SIZE: usize = 200;
+const ITERATIONS: u32 = 100;
+
+/// A Graphics backend which counts its draw calls and the vertices drawn.
+#[derive(Default)]
+struct Counter {
+    calls: usize,
+    vertices: usize,
+}
+
+impl Graphics for Counter {
+    type Texture = SoftwareTexture;
+
+    fn clear_color(&mut self, _color: Color) {}
+
+    fn clear_stencil(&mut self, _value: u8) {}
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]]))
+    {
+        self.calls += 1;
+        let vertices = &mut self.vertices;
+        f(&mut |part| *vertices += part.len());
+    }
+
+    fn tri_list_uv<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], _texture: &SoftwareTexture, mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 2]]))
+    {
+        self.calls += 1;
+        let vertices = &mut self.vertices;
+        f(&mut |part, _| *vertices += part.len());
+    }
+}
+
+/// Returns a LEVEL_SIZE square Level of rooms with a wall every few Tiles,
+/// and some water, mud and rubble.
+fn level_rows() -> Vec<String> {
+    (0..LEVEL_SIZE).map(|y| {
+        (0..LEVEL_SIZE).map(|x| match (x % 12, y % 9) {
+            _ if x == 1 && y == 1 => 'B',
+            _ if x == 3 && y == 1 => 'P',
+            (0, _) | (_, 0) if (x + y) % 5 != 0 => '#',
+            (4, 4) => '~',
+            (5, 4) => '%',
+            (6, 4) => ':',
+            _ => '.',
+        }).collect()
+    }).collect()
+}
+
+/// Draws every Tile of the Level as its own shape, looking each one up in
+/// the Map.
+fn per_tile(model: &GameModel, c: &Context, g: &mut Counter) {
+    for h in 0..model.level.height {
+        for w in 0..model.level.width {
+            if let Some(tile) = model.level.map.get(&MapIdx::new(w, h)) {
+                tile.shape.draw(c, g);
+            }
+        }
+    }
+}
+
+/// Returns the average time taken by f, and the draw calls and vertices it
+/// made each time.
+fn time<F: FnMut(&mut Counter)>(mut f: F) -> (Duration, usize, usize) {
+    let mut counter = Counter::default();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f(&mut counter);
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    (elapsed, counter.calls / ITERATIONS as usize, counter.vertices / ITERATIONS as usize)
+}
+
+fn report(name: &str, (elapsed, calls, vertices): (Duration, usize, usize)) {
+    println!("{:<20} {:>10.3?} per frame, {:>6} draw calls, {:>7} vertices", name, elapsed, calls, vertices);
+}
+
+fn main() {
+    let seed = create_seed(true);
+    let rows = level_rows();
+    let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+    let model = GameModel::with_rules(Level::from_ascii(&rows, seed), seed, Rules::default())
+        .expect("Couldn't create the game!");
+    let sprites = TextureAtlas::<SoftwareTexture>::load(SPRITE_SHEET_PATH).expect("Couldn't load the sprites!");
+
+    // Look at the middle of the Level
+    let middle = (LEVEL_SIZE as f64 * TILE_SIZE - WINDOW_WIDTH) / 2.0;
+    let view = [middle, middle, WINDOW_WIDTH, WINDOW_HEIGHT];
+    let c = Context::new_abs(WINDOW_WIDTH, WINDOW_HEIGHT).trans(-middle, -middle);
+
+    let mut shapes = LevelBatch::new();
+    let mut textured = LevelBatch::new();
+    let rebuild = time(|_| {
+        shapes = LevelBatch::new();
+        shapes.update::<SoftwareTexture>(&model, None);
+    });
+    textured.update(&model, Some(&sprites));
+
+    println!("{0}x{0} level", LEVEL_SIZE);
+    report("per tile:", time(|g| per_tile(&model, &c, g)));
+    report("batched:", time(|g| shapes.draw(&model, view, None, 0.0, &c, g)));
+    report("batched, sprites:", time(|g| textured.draw(&model, view, Some(&sprites), 0.0, &c, g)));
+    println!("{:<20} {:>10.3?}", "building the batch:", rebuild.0);
+}
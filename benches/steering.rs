@@ -0,0 +1,52 @@
+//! Compares how long it takes to move a crowd of Enemies with and without 
+//! steering. Run with `cargo bench --bench steering`.
+
+extern crate rust_game;
+
+use rust_game::ecs::World;
+use rust_game::entity::enemy::spawn_enemy;
+use rust_game::game::GameModel;
+use rust_game::game::consts::map_idx_to_point2;
+use rust_game::levels::map::pathfind;
+use rust_game::math::Point2;
+use rust_game::math::random::create_seed;
+use std::time::{Duration, Instant};
+
+const ENEMIES: usize = 500;
+const TICKS: u32 = 200;
+const DT: f64 = 1.0 / 60.0;
+
+/// Fills the model with a crowd of Enemies at the first spawner, all 
+/// following the same path to the first Beacon.
+fn crowd(model: &mut GameModel) {
+    let start = model.spawners[0].idx;
+    let (path, _) = pathfind(&model.level.map, &start, &model.beacons[0].idx).expect("No path to the Beacon!");
+    let path: Vec<Point2> = path.into_iter().map(map_idx_to_point2).collect();
+    model.world = World::new();
+    for _ in 0..ENEMIES {
+        let enemy = spawn_enemy(&mut model.world, map_idx_to_point2(start));
+        model.world.enemies[enemy].path = path.clone();
+    }
+}
+
+/// Returns the average time taken to steer and move the crowd each tick.
+fn time(model: &mut GameModel, steering: bool) -> Duration {
+    model.steering = steering;
+    crowd(model);
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        model.steer_enemies();
+        model.move_enemies(DT);
+    }
+    start.elapsed() / TICKS
+}
+
+fn main() {
+    let mut model = GameModel::new(create_seed(true)).expect("Couldn't create the game!");
+
+    let following = time(&mut model, false);
+    let steering = time(&mut model, true);
+    println!("{} enemies", ENEMIES);
+    println!("path following: {:?} per tick", following);
+    println!("steering:       {:?} per tick", steering);
+}
@@ -0,0 +1,29 @@
+use crate::math::Vec2;
+
+/// A component for anything which moves on its own. Each tick the entity 
+/// moves speed along direction, plus any knockback it has taken, which decays
+/// over time.
+#[derive(Clone, Copy, Debug)]
+pub struct Velocity {
+    pub direction: Vec2,
+    pub speed: f64,
+    pub knockback: Vec2,
+}
+
+impl Velocity {
+    /// Creates a new Velocity moving at speed along direction.
+    pub fn new(direction: Vec2, speed: f64) -> Self {
+        Self {
+            direction,
+            speed,
+            knockback: Vec2::new(0.0, 0.0),
+        }
+    }
+}
+
+/// A component for the Player's projectiles, which are destroyed when they 
+/// hit something, dealing damage.
+#[derive(Clone, Copy, Debug)]
+pub struct Projectile {
+    pub damage: i32,
+}
@@ -0,0 +1,257 @@
+pub mod components;
+pub mod storage;
+pub mod systems;
+
+pub use self::components::{Velocity, Projectile};
+pub use self::storage::Storage;
+
+use crate::traits::draw::GenericShape;
+use crate::entity::health::Health;
+use crate::entity::enemy::EnemyAi;
+use crate::entity::drops::Resource;
+use crate::entity::towers::tower::Tower;
+
+/// Identifies an entity in a World. The index of a despawned entity is 
+/// reused by later entities, but each reuse bumps the generation, so an old
+/// id never refers to the new entity.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct EntityId {
+    index: u32,
+    generation: u32,
+}
+
+impl EntityId {
+
+    /// Returns the index of the entity's components in each Storage.
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns how many entities have used the index before this one.
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+
+}
+
+/// A small entity-component-system for the things which come and go during a
+/// game: Enemies, Towers, Resources and the Player's projectiles. The Player,
+/// the Beacons and the spawners are unique or tied to the Map, so they're 
+/// kept in the GameModel as before.
+///
+/// An entity is just an EntityId. What an entity is made of is decided by 
+/// the components it's given, each of which is kept in a Storage in the 
+/// World. The GenericShape of an entity is its transform (its position and 
+/// rotation) as well as its outline for drawing and collisions. Each new kind
+/// of component needs a Storage here, and to be retired in despawn().
+///
+/// # Despawning
+///
+/// Entities which are destroyed during a tick, such as an Enemy killed by a
+/// projectile, are queued with queue_despawn() rather than being removed 
+/// straight away. They keep their components until maintain() is called 
+/// once at the end of the tick, but is_despawning() lets anything else 
+/// checking them in the same tick skip them. Since queue_despawn() only 
+/// returns true the first time, an Enemy hit twice in one tick is only 
+/// killed once.
+///
+/// # Systems
+///
+/// Systems are functions which run over every entity with the components 
+/// they need, replacing the per-type tick() and draw() loops:
+///
+/// - The Entity trait's tick() is split into systems: follow_paths() steers
+///   each Enemy along its path, movement() moves anything with a Velocity, 
+///   and spin() turns Resources.
+/// - target() aims each Tower and moves its bullet. A Tower's bullet is 
+///   still part of its Tower, since it only ever has the one.
+/// - collide() checks the Towers' bullets and the projectiles against 
+///   spawners, walls and Enemies, and touching() finds the entities touching
+///   anything else, such as a Swing or a Beacon. What a hit does to the rest
+///   of the game is left to the caller.
+/// - The State trait is still used to change an Enemy's or a Tower's state.
+/// - The Draw trait is used by render(), which draws every entity with a 
+///   given component. Towers draw themselves.
+/// - index() keeps a SpatialHash up to date with every entity with a given
+///   component, for the collision and targeting systems to search.
+///
+/// # Example
+///
+/// ```
+/// extern crate rust_game;
+/// use rust_game::ecs::World;
+/// use rust_game::entity::enemy::spawn_enemy;
+/// use rust_game::math::Point2;
+///
+/// fn main() {
+///     let mut world = World::new();
+///     let enemy = spawn_enemy(&mut world, Point2{x: 20.0, y: 20.0});
+///     assert!(world.enemies.contains(enemy));
+///
+///     assert!(world.queue_despawn(enemy));
+///     assert!(!world.queue_despawn(enemy));
+///     assert!(world.is_alive(enemy));
+///
+///     world.maintain();
+///     assert!(!world.is_alive(enemy));
+///     assert!(world.shapes.is_empty());
+/// }
+/// ```
+#[derive(Default)]
+pub struct World {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    queued: Vec<bool>,
+    free: Vec<usize>,
+    despawning: Vec<EntityId>,
+    pub shapes: Storage<GenericShape>,
+    pub healths: Storage<Health>,
+    pub velocities: Storage<Velocity>,
+    pub enemies: Storage<EnemyAi>,
+    pub towers: Storage<Tower>,
+    pub pickups: Storage<Resource>,
+    pub projectiles: Storage<Projectile>,
+}
+
+impl World {
+
+    /// Creates a new empty World.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new entity with no components.
+    pub fn spawn(&mut self) -> EntityId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.generations.push(0);
+                self.alive.push(false);
+                self.queued.push(false);
+                self.alive.len() - 1
+            },
+        };
+        self.alive[index] = true;
+        EntityId {
+            index: index as u32,
+            generation: self.generations[index],
+        }
+    }
+
+    /// Removes the entity and all of its components straight away. 
+    /// Despawning an entity which has already been despawned does nothing.
+    /// During a tick, use queue_despawn() instead.
+    pub fn despawn(&mut self, id: EntityId) {
+        if !self.is_alive(id) {
+            return;
+        }
+        let index = id.index();
+        self.alive[index] = false;
+        self.queued[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(index);
+        self.shapes.retire(id);
+        self.healths.retire(id);
+        self.velocities.retire(id);
+        self.enemies.retire(id);
+        self.towers.retire(id);
+        self.pickups.retire(id);
+        self.projectiles.retire(id);
+    }
+
+    /// Returns true if the entity hasn't been despawned. Entities queued for
+    /// despawning are alive until maintain() is called.
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        let index = id.index();
+        index < self.alive.len() && self.alive[index] && self.generations[index] == id.generation
+    }
+
+    /// Queues the entity to be despawned by the next call to maintain(). 
+    /// Returns true if the entity was alive and hadn't already been queued.
+    pub fn queue_despawn(&mut self, id: EntityId) -> bool {
+        if !self.is_alive(id) || self.is_despawning(id) {
+            return false;
+        }
+        self.queued[id.index()] = true;
+        self.despawning.push(id);
+        true
+    }
+
+    /// Returns true if the entity is queued to be despawned.
+    pub fn is_despawning(&self, id: EntityId) -> bool {
+        self.is_alive(id) && self.queued[id.index()]
+    }
+
+    /// Despawns every entity in the despawn queue. Called once at the end of
+    /// each tick.
+    pub fn maintain(&mut self) {
+        for id in std::mem::take(&mut self.despawning) {
+            self.despawn(id);
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod world_tests {
+
+    use super::{Velocity, World};
+    use crate::entity::enemy::spawn_enemy;
+    use crate::entity::drops::{spawn_resource, ResourceVariant};
+    use crate::math::{Point2, Vec2};
+
+    #[test]
+    fn test_despawn_removes_components() {
+        let mut world = World::new();
+        let enemy = spawn_enemy(&mut world, Point2{x: 0.0, y: 0.0});
+        let resource = spawn_resource(&mut world, Point2{x: 5.0, y: 5.0}, ResourceVariant::Ammo);
+        assert_eq!(2, world.shapes.len());
+
+        world.despawn(enemy);
+        assert!(!world.is_alive(enemy));
+        assert!(!world.enemies.contains(enemy));
+        assert!(!world.healths.contains(enemy));
+        assert!(world.enemies.is_empty());
+        assert_eq!(vec![resource], world.shapes.ids());
+
+        // Despawning twice does nothing.
+        world.despawn(enemy);
+        assert_eq!(1, world.shapes.len());
+        assert!(world.is_alive(resource));
+    }
+
+    #[test]
+    fn test_stale_ids() {
+        let mut world = World::new();
+        let a = spawn_enemy(&mut world, Point2{x: 0.0, y: 0.0});
+        let b = world.spawn();
+        world.despawn(a);
+
+        // A despawned entity can't be given components.
+        world.velocities.insert(a, Velocity::new(Vec2::new(1.0, 0.0), 1.0));
+        assert!(world.velocities.is_empty());
+
+        // The slot is reused, but the old id can't reach the new entity.
+        let c = spawn_resource(&mut world, Point2{x: 5.0, y: 5.0}, ResourceVariant::Tower);
+        assert_eq!(a.index(), c.index());
+        assert_ne!(a, c);
+        assert!(!world.is_alive(a));
+        assert!(world.is_alive(b) && world.is_alive(c));
+        assert!(world.shapes.get(a).is_none());
+        assert!(world.pickups.get(a).is_none());
+        assert_eq!(Some(c), world.pickups.id_at(c.index()));
+        // Nor can it take the slot back from the new entity.
+        let shape = world.shapes[c];
+        world.velocities.insert(a, Velocity::new(Vec2::new(1.0, 0.0), 1.0));
+        world.shapes.insert(a, shape);
+        assert!(world.velocities.is_empty());
+        assert_eq!(Some(c), world.shapes.id_at(c.index()));
+
+        // Despawning the old id again leaves the new entity alone.
+        world.despawn(a);
+        assert!(!world.queue_despawn(a));
+        world.maintain();
+        assert!(world.is_alive(c) && world.shapes.contains(c));
+    }
+
+}
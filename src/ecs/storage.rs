@@ -0,0 +1,149 @@
+use crate::ecs::EntityId;
+use std::ops::{Index, IndexMut};
+
+/// Stores one kind of component for every entity which has it. Components are
+/// kept in a slot per entity index, so looking up an entity's component is a
+/// single index into a Vec, and iterating visits entities in index order.
+///
+/// Each slot also keeps the full EntityId of its entity, so an id left over
+/// from an entity which has since been despawned never finds the component
+/// of a newer entity reusing the slot. Each slot remembers the oldest 
+/// generation it still accepts too, so a stale id can't give a component to
+/// a despawned entity either, or replace a newer entity's.
+pub struct Storage<T> {
+    slots: Vec<Option<(EntityId, T)>>,
+    generations: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Storage<T> {
+
+    /// Creates a new empty Storage.
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            generations: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Gives the entity the component, replacing any it already had. Ids 
+    /// older than the slot's current entity, or of an entity which has been
+    /// retired, are ignored.
+    pub fn insert(&mut self, id: EntityId, component: T) {
+        let index = id.index();
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+            self.generations.resize(index + 1, 0);
+        }
+        if id.generation() < self.generations[index] {
+            return;
+        }
+        self.generations[index] = id.generation();
+        if self.slots[index].replace((id, component)).is_none() {
+            self.len += 1;
+        }
+    }
+
+    /// Takes the component away from the entity, returning it if it had one.
+    pub fn remove(&mut self, id: EntityId) -> Option<T> {
+        let slot = self.slots.get_mut(id.index())?;
+        match slot {
+            Some((owner, _)) if *owner == id => {
+                self.len -= 1;
+                slot.take().map(|(_, component)| component)
+            },
+            _ => None,
+        }
+    }
+
+    /// Takes the component away from a despawned entity, and stops its id 
+    /// from being given components again. See World::despawn().
+    pub fn retire(&mut self, id: EntityId) -> Option<T> {
+        let component = self.remove(id);
+        if let Some(generation) = self.generations.get_mut(id.index()) {
+            if id.generation() >= *generation {
+                *generation = id.generation().wrapping_add(1);
+            }
+        }
+        component
+    }
+
+    /// Returns the entity's component, if it has one.
+    pub fn get(&self, id: EntityId) -> Option<&T> {
+        match self.slots.get(id.index()) {
+            Some(Some((owner, component))) if *owner == id => Some(component),
+            _ => None,
+        }
+    }
+
+    /// Returns the entity's component mutably, if it has one.
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut T> {
+        match self.slots.get_mut(id.index()) {
+            Some(Some((owner, component))) if *owner == id => Some(component),
+            _ => None,
+        }
+    }
+
+    /// Returns the id of the entity with the component at index, if there is
+    /// one. Used to turn the indices stored in a SpatialHash back into ids.
+    pub fn id_at(&self, index: usize) -> Option<EntityId> {
+        self.slots.get(index).and_then(|slot| slot.as_ref()).map(|(id, _)| *id)
+    }
+
+    /// Returns true if the entity has the component.
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Returns the number of entities with the component.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no entity has the component.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the ids of every entity with the component, in index order.
+    pub fn ids(&self) -> Vec<EntityId> {
+        self.iter().map(|(id, _)| id).collect()
+    }
+
+    /// Iterates over every entity with the component, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &T)> {
+        self.slots.iter()
+            .filter_map(|slot| slot.as_ref().map(|(id, component)| (*id, component)))
+    }
+
+    /// Iterates mutably over every entity with the component, in index order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut T)> {
+        self.slots.iter_mut()
+            .filter_map(|slot| slot.as_mut().map(|(id, component)| (*id, component)))
+    }
+
+}
+
+impl<T> Index<EntityId> for Storage<T> {
+    type Output = T;
+
+    /// Returns the entity's component. Panics if the entity doesn't have it.
+    fn index(&self, id: EntityId) -> &T {
+        self.get(id).expect("entity is missing the component")
+    }
+}
+
+impl<T> IndexMut<EntityId> for Storage<T> {
+    /// Returns the entity's component mutably. Panics if the entity doesn't
+    /// have it.
+    fn index_mut(&mut self, id: EntityId) -> &mut T {
+        self.get_mut(id).expect("entity is missing the component")
+    }
+}
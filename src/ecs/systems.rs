@@ -0,0 +1,299 @@
+use crate::ecs::{World, EntityId, Storage};
+use crate::entity::enemy::hit_enemy;
+use crate::entity::spawner::Spawner;
+use crate::entity::towers::tower::TowerState;
+use crate::levels::map::{Map, MapIdx, speed_at, sweep_circle};
+use crate::levels::spatial_hash::SpatialHash;
+use crate::traits::draw::{Draw, Context, Graphics, GenericShape, check_collision};
+use crate::traits::entity::Entity;
+use crate::traits::state::State;
+use crate::math::{Point2, Vec2};
+use crate::game::consts::{
+    point2_to_map_idx,
+    ENEMY_SPEED,
+    ENEMY_RADIUS,
+    ENEMY_KNOCKBACK_DECAY,
+    DROP_ROTATION_SPEED,
+    PI,
+    INF,
+};
+
+/// Something a Tower's bullet or one of the Player's projectiles hit, found
+/// by collide().
+#[derive(Clone, Copy, Debug)]
+pub enum Hit {
+    /// An Enemy took damage, which killed it if killed is true. by_tower is
+    /// true if the damage came from a Tower's bullet.
+    Enemy{id: EntityId, position: Point2, killed: bool, by_tower: bool},
+    /// The spawner in the Tile at idx took damage.
+    Spawner{idx: MapIdx, damage: i32},
+}
+
+/// Points each Enemy along its path, at a speed depending on the terrain 
+/// under it. Enemies with nowhere left to go stop. See EnemyAi::heading().
+pub fn follow_paths(world: &mut World, map: &Map) {
+    let World { shapes, velocities, enemies, .. } = world;
+    for (id, ai) in enemies.iter_mut() {
+        let (shape, velocity) = match (shapes.get(id), velocities.get_mut(id)) {
+            (Some(shape), Some(velocity)) => (shape, velocity),
+            _ => continue,
+        };
+        ai.terrain_speed = speed_at(map, shape.center_point());
+        match ai.heading(shape.get_position()) {
+            Some(direction) => {
+                velocity.direction = direction;
+                velocity.speed = ENEMY_SPEED * ai.terrain_speed;
+            },
+            None => velocity.speed = 0.0,
+        }
+    }
+}
+
+/// Moves every entity with a Velocity, along with any knockback it has 
+/// taken. Knockback decays over time. Enemies are swept against the walls of
+/// the Map like the Player, so neither walking nor knockback can carry them
+/// through a wall, and any knockback into a wall is cancelled. Projectiles 
+/// aren't swept, since they're destroyed when they enter a wall.
+pub fn movement(world: &mut World, map: &Map, dt: f64) {
+    let World { shapes, velocities, enemies, .. } = world;
+    for (id, velocity) in velocities.iter_mut() {
+        let shape = match shapes.get_mut(id) {
+            Some(shape) => shape,
+            None => continue,
+        };
+        let (direction, mut knockback) = (velocity.direction, velocity.knockback);
+        let mut delta = Point2 {
+            x: (direction.x * velocity.speed + knockback.x) * dt,
+            y: (direction.y * velocity.speed + knockback.y) * dt,
+        };
+        if enemies.contains(id) {
+            let sweep = sweep_circle(map, shape.center_point(), ENEMY_RADIUS, delta);
+            delta = sweep.delta;
+            if let Some(normal) = sweep.normal {
+                let into = Vec2::dot_product(knockback, normal);
+                if into > 0.0 {
+                    knockback = Vec2::new(knockback.x - normal.x * into, knockback.y - normal.y * into);
+                }
+            }
+        }
+        shape.update(delta, None);
+
+        if knockback.length() > 0.0 {
+            let decay = (1.0 - ENEMY_KNOCKBACK_DECAY * dt).max(0.0);
+            knockback = Vec2::new(knockback.x * decay, knockback.y * decay);
+            if knockback.length() < 1.0 {
+                knockback = Vec2::new(0.0, 0.0);
+            }
+        }
+        velocity.knockback = knockback;
+    }
+}
+
+/// Rotates every Resource.
+pub fn spin(world: &mut World, dt: f64) {
+    let World { shapes, pickups, .. } = world;
+    for (id, _) in pickups.iter() {
+        if let Some(shape) = shapes.get_mut(id) {
+            shape.update(Point2{x: 0.0, y: 0.0}, Some(DROP_ROTATION_SPEED * dt));
+        }
+    }
+}
+
+/// Brings the grid up to date with every entity with a component from 
+/// storage, registered by the index of its EntityId. Entities which are gone
+/// or queued for despawning are removed, and only entities which have moved
+/// into other cells since the last call are registered again, see 
+/// SpatialHash::insert(). Use Storage::id_at() to turn the indices found in 
+/// the grid back into ids.
+pub fn index<T>(world: &World, storage: &Storage<T>, grid: &mut SpatialHash) {
+    grid.retain(|i| storage.id_at(i).is_some_and(|id| !world.is_despawning(id)));
+    for (id, _) in storage.iter().filter(|(id, _)| !world.is_despawning(*id)) {
+        if let Some(shape) = world.shapes.get(id) {
+            grid.insert_shape(id.index(), shape);
+        }
+    }
+}
+
+/// Returns the ids of the entities with a component from storage which are
+/// touching shape. Only the entities found in the grid near shape are 
+/// checked, and entities queued for despawning are left out.
+pub fn touching<T>(world: &World, storage: &Storage<T>, grid: &SpatialHash, shape: &GenericShape) -> Vec<EntityId> {
+    grid.query_shape(shape).into_iter()
+        .filter_map(|i| storage.id_at(i))
+        .filter(|id| !world.is_despawning(*id))
+        .filter(|id| world.shapes.get(*id).is_some_and(|other| check_collision(*shape, *other)))
+        .collect()
+}
+
+/// Aims each Tower at the closest Enemy or spawner it can see within its 
+/// range, which it starts firing at if it's Ready, and moves the bullet of
+/// each Tower which is Attacking. Only the Enemies found in the grid near a
+/// Tower are considered.
+pub fn target(world: &mut World, grid: &SpatialHash, map: &Map, spawners: &[Spawner], dt: f64) {
+    for id in world.towers.ids() {
+        // Any Enemy within range (measured along the axes) is also within
+        // a circle of that radius, so only those Enemies are considered.
+        let (center, range) = (world.towers[id].base_shape.center_point(), world.towers[id].range);
+        let targets: Vec<Point2> = grid.query_radius(center, range).into_iter()
+            .filter_map(|i| world.enemies.id_at(i))
+            .filter(|enemy| !world.is_despawning(*enemy))
+            .map(|enemy| world.shapes[enemy].center_point())
+            .chain(spawners.iter().map(|spawner| spawner.shape.center_point()))
+            .collect();
+        let tower = &mut world.towers[id];
+        let mut new_dir = Point2{x: 0.0, y: 0.0};
+        let mut min_dist = INF;
+        for target in targets {
+
+            let dir = target - tower.base_shape.center_point();
+            let slope = dir.y / dir.x;
+            let vertical_offset = tower.base_shape.center_point().y;
+            let p0 = point2_to_map_idx(tower.base_shape.center_point());
+            let pn = point2_to_map_idx(target);
+
+            let mut wall_hit = false;
+            let mut previous = point2_to_map_idx(tower.base_shape.get_position());
+            for x in p0.x..pn.x {
+                let next = point2_to_map_idx(Point2{x: 0.0, y: slope * x as f64 + vertical_offset});
+                if next.y != previous.y {
+                    if let Some(tile) = map.get(&MapIdx::new(previous.x, next.y)) {
+                        if tile.variant.is_solid() {
+                            wall_hit = true;
+                            continue;
+                        }
+                    }
+                }
+                
+                if let Some(tile) = map.get(&MapIdx::new(x, next.y)) {
+                    if tile.variant.is_solid() {
+                        wall_hit = true;
+                        continue;
+                    }
+                } 
+                previous = MapIdx::new(x, next.y);
+            }
+            if wall_hit {
+                continue;
+            }
+
+            let dist = dir.x.abs() + dir.y.abs();
+            if dist < min_dist {
+                min_dist = dist;
+                new_dir = dir; 
+            }
+
+        }
+
+        if min_dist < tower.range {
+            
+            let mut rad = new_dir.y / new_dir.x;
+            rad = rad.atan();
+            
+            match [new_dir.x < 0.0, new_dir.y < 0.0] {
+                [true, true] => rad = PI * 2.0 - rad,
+                [true, false] => rad = -rad,
+                [false, true] => rad = PI - rad,
+                [false, false] => rad = PI - rad
+            }
+
+            rad = PI - rad;
+            tower.set_rotation(rad);
+
+            if let TowerState::Ready = tower.state {
+                tower.change_state(TowerState::Attacking);
+            }
+        }
+
+        tower.tick(dt);
+
+    }
+}
+
+/// Checks the bullet of each Tower which is Attacking, and each of the 
+/// Player's projectiles, against the spawners, the walls of the Map and the
+/// Enemies found in the grid near it, returning everything which was hit.
+/// 
+/// Enemies hit take the damage straight away, but killing them and damaging
+/// spawners is left to the caller. A bullet hits every Enemy it touches and
+/// its Tower is made Ready again, while a projectile only hits the first 
+/// and is queued for despawning. Either is stopped by a wall or a spawner.
+/// An Enemy killed by one hit can't be hit again.
+pub fn collide(world: &mut World, grid: &SpatialHash, map: &Map, spawners: &[Spawner]) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = Vec::new();
+    let mut killed: Vec<EntityId> = Vec::new();
+    let in_wall = |shape: &GenericShape| match map.get(&point2_to_map_idx(shape.center_point())) {
+        Some(tile) => tile.variant.is_solid(),
+        None => true,
+    };
+
+    for tower in world.towers.ids() {
+        if let TowerState::Ready = world.towers[tower].state {
+            continue;
+        }
+        let (shape, damage) = (world.towers[tower].bullet.shape, world.towers[tower].bullet.damage);
+        if let Some(spawner) = spawners.iter().find(|spawner| check_collision(shape, spawner.shape)) {
+            world.towers[tower].change_state(TowerState::Ready);
+            hits.push(Hit::Spawner{idx: spawner.idx, damage});
+            continue;
+        }
+        if in_wall(&shape) {
+            world.towers[tower].change_state(TowerState::Ready);
+            continue;
+        }
+        for enemy in touching(world, &world.enemies, grid, &shape) {
+            if killed.contains(&enemy) {
+                continue;
+            }
+            let dead = hit_enemy(world, enemy, damage, Vec2::new(0.0, 0.0));
+            if dead {
+                killed.push(enemy);
+            }
+            hits.push(Hit::Enemy{id: enemy, position: world.shapes[enemy].center_point(), killed: dead, by_tower: true});
+            world.towers[tower].change_state(TowerState::Ready);
+        }
+    }
+
+    for id in world.projectiles.ids() {
+        let (shape, damage) = (world.shapes[id], world.projectiles[id].damage);
+        if let Some(spawner) = spawners.iter().find(|spawner| check_collision(shape, spawner.shape)) {
+            hits.push(Hit::Spawner{idx: spawner.idx, damage});
+            world.queue_despawn(id);
+            continue;
+        }
+        if in_wall(&shape) {
+            world.queue_despawn(id);
+            continue;
+        }
+        let enemy = touching(world, &world.enemies, grid, &shape).into_iter().find(|enemy| !killed.contains(enemy));
+        if let Some(enemy) = enemy {
+            let dead = hit_enemy(world, enemy, damage, Vec2::new(0.0, 0.0));
+            if dead {
+                killed.push(enemy);
+            }
+            hits.push(Hit::Enemy{id: enemy, position: world.shapes[enemy].center_point(), killed: dead, by_tower: false});
+            world.queue_despawn(id);
+        }
+    }
+
+    hits
+}
+
+/// Draws the shape of every entity with a component from storage, for which
+/// visible returns true. Each shape is drawn alpha of the way between ticks,
+/// see GenericShape::interpolate().
+pub fn render<T, G: Graphics, F: Fn(&GenericShape) -> bool>(
+    shapes: &Storage<GenericShape>,
+    storage: &Storage<T>,
+    visible: F,
+    alpha: f64,
+    c: &Context,
+    g: &mut G
+) {
+    for (id, _) in storage.iter() {
+        if let Some(shape) = shapes.get(id) {
+            if visible(shape) {
+                shape.interpolate(alpha).draw(c, g);
+            }
+        }
+    }
+}
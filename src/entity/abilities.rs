@@ -0,0 +1,183 @@
+use crate::game::consts::{
+    DASH_DURATION,
+    DASH_COOLDOWN,
+    SHIELD_DURATION,
+    SHIELD_COOLDOWN,
+    RECALL_CHANNEL_TIME,
+    RECALL_COOLDOWN,
+};
+
+/// The abilities available to the Player.
+///
+/// A Dash quickly moves the Player in the direction they're moving (or
+/// facing) and makes them invulnerable while dashing. A Shield absorbs a
+/// number of hits for a short time. A Recall is channelled, and teleports the
+/// Player back to the Beacon if the channel isn't interrupted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AbilityKind {
+    Dash,
+    Shield,
+    Recall,
+}
+
+/// A single ability with a duration and a cooldown. The cooldown starts once
+/// the ability has been activated.
+#[derive(Clone, Copy, Debug)]
+pub struct Ability {
+    pub kind: AbilityKind,
+    pub duration: f64,
+    pub cooldown: f64,
+    time_active: f64,
+    time_cooling: f64,
+}
+
+impl Ability {
+
+    /// Creates a new Ability which is ready to use.
+    pub fn new(kind: AbilityKind, duration: f64, cooldown: f64) -> Self {
+        Self {
+            kind,
+            duration,
+            cooldown,
+            time_active: 0.0,
+            time_cooling: 0.0,
+        }
+    }
+
+    /// Returns true if the Ability can be activated.
+    pub fn is_ready(&self) -> bool {
+        self.time_cooling <= 0.0 && self.time_active <= 0.0
+    }
+
+    /// Returns true while the Ability's effect lasts.
+    pub fn is_active(&self) -> bool {
+        self.time_active > 0.0
+    }
+
+    /// Returns how much of the cooldown has passed, from 0.0 right after the
+    /// Ability was used, to 1.0 when the Ability is ready again.
+    pub fn readiness(&self) -> f64 {
+        if self.is_active() {
+            0.0
+        } else if self.cooldown <= 0.0 {
+            1.0
+        } else {
+            1.0 - self.time_cooling / self.cooldown
+        }
+    }
+
+    /// Activates the Ability if it's ready. Returns true if the Ability was
+    /// activated.
+    pub fn activate(&mut self) -> bool {
+        if self.is_ready() {
+            self.time_active = self.duration;
+            self.time_cooling = self.cooldown;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Ends the Ability's effect early. The cooldown still has to pass before
+    /// the Ability can be used again.
+    pub fn cancel(&mut self) {
+        self.time_active = 0.0;
+    }
+
+    /// Advances the Ability by dt seconds. The cooldown only starts counting
+    /// down once the effect is over. Returns true if the effect ended during
+    /// this tick.
+    pub fn tick(&mut self, dt: f64) -> bool {
+        if self.is_active() {
+            self.time_active -= dt;
+            if self.time_active <= 0.0 {
+                self.time_active = 0.0;
+                return true;
+            }
+        } else {
+            self.time_cooling = (self.time_cooling - dt).max(0.0);
+        }
+        false
+    }
+
+}
+
+/// The set of Abilities belonging to the Player.
+pub struct Abilities {
+    pub dash: Ability,
+    pub shield: Ability,
+    pub recall: Ability,
+}
+
+impl Abilities {
+
+    /// Creates the Player's Abilities, all of which are ready to use.
+    pub fn new() -> Self {
+        Self {
+            dash: Ability::new(AbilityKind::Dash, DASH_DURATION, DASH_COOLDOWN),
+            shield: Ability::new(AbilityKind::Shield, SHIELD_DURATION, SHIELD_COOLDOWN),
+            recall: Ability::new(AbilityKind::Recall, RECALL_CHANNEL_TIME, RECALL_COOLDOWN),
+        }
+    }
+
+    /// Returns the Ability of the input kind.
+    pub fn get(&self, kind: AbilityKind) -> &Ability {
+        match kind {
+            AbilityKind::Dash => &self.dash,
+            AbilityKind::Shield => &self.shield,
+            AbilityKind::Recall => &self.recall,
+        }
+    }
+
+    /// Returns each Ability, in the order they're shown in the HUD.
+    pub fn iter(&self) -> impl Iterator<Item = &Ability> {
+        vec![&self.dash, &self.shield, &self.recall].into_iter()
+    }
+
+}
+
+impl Default for Abilities {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod ability_tests {
+
+    use super::{Ability, AbilityKind};
+
+    #[test]
+    fn test_ability_cycle() {
+        let mut ability = Ability::new(AbilityKind::Dash, 0.5, 2.0);
+        assert!(ability.is_ready());
+        assert!(ability.activate());
+        assert!(ability.is_active());
+        assert!(!ability.activate());
+
+        // The effect ends before the cooldown starts.
+        assert!(!ability.tick(0.25));
+        assert!(ability.tick(0.25));
+        assert!(!ability.is_active());
+        assert_eq!(0.0, ability.readiness());
+
+        ability.tick(1.0);
+        assert!(!ability.is_ready());
+        assert_eq!(0.5, ability.readiness());
+        ability.tick(1.0);
+        assert!(ability.is_ready());
+        assert_eq!(1.0, ability.readiness());
+    }
+
+    #[test]
+    fn test_ability_cancel() {
+        let mut ability = Ability::new(AbilityKind::Recall, 3.0, 1.0);
+        ability.activate();
+        ability.cancel();
+        assert!(!ability.is_active());
+        assert!(!ability.is_ready());
+        ability.tick(1.0);
+        assert!(ability.is_ready());
+    }
+
+}
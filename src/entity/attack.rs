@@ -4,29 +4,330 @@ use crate::game::consts::{
     PLAYER_ATTACK_HEIGHT,
     PLAYER_ATTACK_WIDTH,
     PLAYER_ATTACK_COLOR,
+    PI,
 };
 
+/// A description of a single swing of a Weapon. Durations are in seconds and
+/// the arc is the total angle (in radians) the blade sweeps through while the
+/// swing is active. Knockback is the speed an Enemy is pushed away at when
+/// it's hit.
+#[derive(Clone, Copy, Debug)]
+pub struct Swing {
+    pub windup: f64,
+    pub active: f64,
+    pub recovery: f64,
+    pub damage: i32,
+    pub knockback: f64,
+    pub arc: f64,
+    pub reach: f64,
+    pub thickness: f64,
+}
+
+/// A Weapon is a combo of Swings. Pressing attack again before a Swing has
+/// recovered chains into the next Swing of the combo. Once the last Swing
+/// finishes, the combo starts over.
+#[derive(Clone, Debug)]
+pub struct Weapon {
+    pub name: &'static str,
+    pub combo: Vec<Swing>,
+}
+
+impl Weapon {
+
+    /// A quick three hit sword combo. The final hit is slower, but does more
+    /// damage and knocks enemies further back.
+    pub fn sword() -> Self {
+        let swing = Swing {
+            windup: 0.08,
+            active: 0.12,
+            recovery: 0.2,
+            damage: 1,
+            knockback: 250.0,
+            arc: PI * 2.0 / 3.0,
+            reach: PLAYER_ATTACK_WIDTH,
+            thickness: PLAYER_ATTACK_HEIGHT,
+        };
+        Self {
+            name: "Sword",
+            combo: vec![
+                swing,
+                swing,
+                Swing {
+                    windup: 0.15,
+                    active: 0.15,
+                    recovery: 0.35,
+                    damage: 2,
+                    knockback: 450.0,
+                    arc: PI,
+                    ..swing
+                },
+            ],
+        }
+    }
+
+}
+
+/// The phases of an Attack. While Ready, a new Swing can be started. During
+/// the Windup the blade is drawn back, during the Active frames the blade
+/// sweeps through its arc and can hit enemies, and during Recovery the Player
+/// can't start a new Swing, but can queue up the next Swing of the combo.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttackPhase {
+    Ready,
+    Windup,
+    Active,
+    Recovery,
+}
+
 /// A structure representing the player attack. Attacks are of a RectangleType
+/// which rotates around the Player's center as the Swing progresses.
 pub struct Attack {
-    pub shape: GenericShape
+    pub shape: GenericShape,
+    pub weapon: Weapon,
+    pub phase: AttackPhase,
+    pub swing_id: u32,
+    combo_step: usize,
+    queued_aim: Option<f64>,
+    timer: f64,
+    aim: f64,
 }
 
 impl Attack {
-    
+
     pub fn new() -> Self {
-        Self { 
-            shape: GenericShape::new(
-                ShapeVariant::Rect{
-                    width: PLAYER_ATTACK_WIDTH, 
-                    height: PLAYER_ATTACK_HEIGHT
+        Self::with_weapon(Weapon::sword())
+    }
+
+    /// Creates a new Attack for the input Weapon.
+    pub fn with_weapon(weapon: Weapon) -> Self {
+        let swing = weapon.combo[0];
+        Self {
+            shape: Attack::blade(&swing),
+            weapon,
+            phase: AttackPhase::Ready,
+            swing_id: 0,
+            combo_step: 0,
+            queued_aim: None,
+            timer: 0.0,
+            aim: 0.0,
+        }
+    }
+
+    /// Creates the shape of the blade for a Swing. The blade is offset so
+    /// that it rotates around its base.
+    fn blade(swing: &Swing) -> GenericShape {
+        let mut shape = GenericShape::new(
+            ShapeVariant::Rect{
+                width: swing.reach,
+                height: swing.thickness
+            },
+            PLAYER_ATTACK_COLOR,
+            Point2 {
+                x: 0.0,
+                y: 0.0,
+            }
+        );
+        shape.set_offset(Point2{x: 0.0, y: -swing.thickness / 2.0});
+        shape
+    }
+
+    /// Returns the Swing currently being performed.
+    pub fn swing(&self) -> Swing {
+        self.weapon.combo[self.combo_step]
+    }
+
+    /// Returns true while the blade can hit enemies.
+    pub fn is_active(&self) -> bool {
+        self.phase == AttackPhase::Active
+    }
+
+    /// Tries to start attacking in the direction of aim (in radians). If a
+    /// Swing is already underway, the next Swing of the combo is queued
+    /// instead, aimed at aim once it starts. Returns true if a new Swing was
+    /// started.
+    pub fn start(&mut self, aim: f64) -> bool {
+        match self.phase {
+            AttackPhase::Ready => {
+                self.begin_swing(aim);
+                true
+            },
+            _ => {
+                self.queued_aim = Some(aim);
+                false
+            }
+        }
+    }
+
+    /// Starts the Swing at the current combo step.
+    fn begin_swing(&mut self, aim: f64) {
+        self.aim = aim;
+        self.queued_aim = None;
+        self.timer = 0.0;
+        self.swing_id += 1;
+        self.phase = AttackPhase::Windup;
+        self.shape = Attack::blade(&self.swing());
+    }
+
+    /// Returns the rotation of the blade at the current point of the Swing.
+    /// Every other Swing of a combo sweeps in the opposite direction.
+    fn blade_rotation(&self) -> f64 {
+        let swing = self.swing();
+        let side = [1.0, -1.0][self.combo_step % 2];
+        let start = self.aim - side * swing.arc / 2.0;
+        match self.phase {
+            AttackPhase::Ready | AttackPhase::Windup => start,
+            AttackPhase::Active => start + side * swing.arc * (self.timer / swing.active).min(1.0),
+            AttackPhase::Recovery => start + side * swing.arc,
+        }
+    }
+
+    /// Advances the Attack by dt seconds, keeping the blade attached to the
+    /// origin. A tick which reaches the Active phase stops there, so a long
+    /// tick can't skip past it. Returns true if the Attack finished and is 
+    /// Ready again.
+    pub fn tick(&mut self, dt: f64, origin: Point2) -> bool {
+        if self.phase == AttackPhase::Ready {
+            return false;
+        }
+
+        self.timer += dt;
+        let swing = self.swing();
+        let mut finished = false;
+        loop {
+            let duration = match self.phase {
+                AttackPhase::Windup => swing.windup,
+                AttackPhase::Active => swing.active,
+                AttackPhase::Recovery => swing.recovery,
+                AttackPhase::Ready => break,
+            };
+            if self.timer < duration {
+                break;
+            }
+            self.timer -= duration;
+            self.phase = match self.phase {
+                AttackPhase::Windup => AttackPhase::Active,
+                AttackPhase::Active => AttackPhase::Recovery,
+                _ => AttackPhase::Ready,
+            };
+            // However long the tick, the blade is Active for at least one 
+            // tick, so it gets a chance to hit.
+            if self.phase == AttackPhase::Active {
+                break;
+            }
+        }
+
+        if self.phase == AttackPhase::Ready {
+            match self.queued_aim {
+                Some(aim) if self.combo_step + 1 < self.weapon.combo.len() => {
+                    self.combo_step += 1;
+                    self.begin_swing(aim);
                 },
-                PLAYER_ATTACK_COLOR, 
-                Point2 {
-                    x: 0.0,
-                    y: 0.0,
-                }
-            ),
+                _ => {
+                    self.combo_step = 0;
+                    self.queued_aim = None;
+                    finished = true;
+                },
+            }
         }
+
+        self.shape.set_position(origin);
+        self.shape.set_rotation(self.blade_rotation());
+        finished
+    }
+
+}
+
+#[cfg(test)]
+mod attack_tests {
+
+    use super::{Attack, AttackPhase, Weapon};
+    use crate::math::Point2;
+
+    const ORIGIN: Point2 = Point2{x: 0.0, y: 0.0};
+
+    #[test]
+    fn test_swing_phases() {
+        let mut attack = Attack::new();
+        let swing = attack.swing();
+
+        assert!(attack.start(0.0));
+        assert_eq!(AttackPhase::Windup, attack.phase);
+        assert!(!attack.tick(swing.windup + 0.001, ORIGIN));
+        assert!(attack.is_active());
+        assert!(!attack.tick(swing.active, ORIGIN));
+        assert_eq!(AttackPhase::Recovery, attack.phase);
+        assert!(attack.tick(swing.recovery, ORIGIN));
+        assert_eq!(AttackPhase::Ready, attack.phase);
+    }
+
+    #[test]
+    fn test_blade_sweeps_arc() {
+        let mut attack = Attack::new();
+        let swing = attack.swing();
+        attack.start(0.0);
+
+        attack.tick(swing.windup + 0.0001, ORIGIN);
+        let start = attack.shape.get_rotation().unwrap();
+        attack.tick(swing.active / 2.0, ORIGIN);
+        let middle = attack.shape.get_rotation().unwrap();
+
+        assert!((start + swing.arc / 2.0).abs() < 0.01);
+        assert!(middle.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_combo_chains() {
+        let mut attack = Attack::with_weapon(Weapon::sword());
+        let first = attack.swing();
+        attack.start(0.0);
+        assert_eq!(1, attack.swing_id);
+
+        // Queue the next swing, and let the first one finish.
+        assert!(!attack.start(0.0));
+        attack.tick(first.windup, ORIGIN);
+        let finished = attack.tick(first.active + first.recovery + 0.001, ORIGIN);
+        assert!(!finished);
+        assert_eq!(2, attack.swing_id);
+        assert_eq!(AttackPhase::Windup, attack.phase);
+
+        // Without queueing another swing the combo ends.
+        let second = attack.swing();
+        attack.tick(second.windup, ORIGIN);
+        assert!(attack.tick(second.active + second.recovery + 0.001, ORIGIN));
+        assert_eq!(AttackPhase::Ready, attack.phase);
+    }
+
+    #[test]
+    fn test_queued_swing_keeps_current_aim() {
+        let mut attack = Attack::new();
+        let swing = attack.swing();
+        attack.start(0.0);
+        attack.tick(swing.windup + swing.active / 2.0, ORIGIN);
+        let rotation = attack.shape.get_rotation().unwrap();
+
+        // The next swing is aimed elsewhere, but the current blade stays put.
+        assert!(!attack.start(1.5));
+        attack.tick(0.0, ORIGIN);
+        assert_eq!(rotation, attack.shape.get_rotation().unwrap());
+
+        // Once it starts, the next swing sweeps back from the new aim.
+        attack.tick(swing.active + swing.recovery, ORIGIN);
+        assert_eq!(2, attack.swing_id);
+        let next = attack.swing();
+        assert!((attack.shape.get_rotation().unwrap() - (1.5 + next.arc / 2.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_long_tick_stops_at_active() {
+        let mut attack = Attack::new();
+        let swing = attack.swing();
+        attack.start(0.0);
+
+        // A tick longer than the whole Swing still leaves the blade Active.
+        assert!(!attack.tick(swing.windup + swing.active + swing.recovery + 1.0, ORIGIN));
+        assert!(attack.is_active());
+        assert!(attack.tick(0.0, ORIGIN));
+        assert_eq!(AttackPhase::Ready, attack.phase);
     }
 
 }
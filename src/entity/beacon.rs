@@ -1,29 +1,94 @@
 use crate::traits::entity;
 use crate::traits::draw::{GenericShape, ShapeVariant};
+use crate::entity::health::{Health, DamageOutcome};
+use crate::entity::health_bar::HealthBar;
 use crate::levels::map::MapIdx;
-use crate::math::Point2;
+use crate::math::{Point2, Vec2};
 use crate::game::consts::{
     BEACON_SIZE,
     BEACON_COLOR,
     BEACON_STARTING_HEALTH,
     BEACON_ROTATION_SPEED,
+    BEACON_INVULNERABILITY,
+    BEACON_FLASH_COLOR,
+    BEACON_FALLEN_COLOR,
+    BEACON_UPGRADE_MAX_LEVEL,
+    BEACON_AURA_RADIUS,
+    BEACON_AURA_HEAL_INTERVAL,
+    BEACON_REGENERATION,
+    BEACON_SHIELD_HITS,
+    BEACON_SHIELD_RECHARGE,
+    BEACON_PULSE_RADIUS,
+    BEACON_PULSE_COOLDOWN,
+    BEACON_PULSE_FLASH,
     HEALTH_BAR_HEIGHT,
-    HEALTH_COLOR,
-    DAMAGE_COLOR,
-    map_idx_to_point2
+    map_idx_to_point2,
+    point2_to_map_idx,
 };
 
-/// A struct representing the Beacon game component. The beacon is the game 
+/// The upgrades which can be bought for the Beacon with resources.
+///
+/// The Aura heals the Player while they stand near the Beacon. Regeneration
+/// restores the Beacon's health between waves. The Shield absorbs hits, and
+/// recharges over time. The Pulse damages and knocks back nearby enemies on a
+/// cooldown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BeaconUpgrade {
+    Aura,
+    Regeneration,
+    Shield,
+    Pulse,
+}
+
+/// The level of each of the Beacon's upgrades. A level of 0 means the upgrade
+/// hasn't been bought yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BeaconUpgrades {
+    pub aura: u32,
+    pub regeneration: u32,
+    pub shield: u32,
+    pub pulse: u32,
+}
+
+impl BeaconUpgrades {
+
+    /// Returns the level of the input upgrade.
+    pub fn level(&self, upgrade: BeaconUpgrade) -> u32 {
+        match upgrade {
+            BeaconUpgrade::Aura => self.aura,
+            BeaconUpgrade::Regeneration => self.regeneration,
+            BeaconUpgrade::Shield => self.shield,
+            BeaconUpgrade::Pulse => self.pulse,
+        }
+    }
+
+    fn level_mut(&mut self, upgrade: BeaconUpgrade) -> &mut u32 {
+        match upgrade {
+            BeaconUpgrade::Aura => &mut self.aura,
+            BeaconUpgrade::Regeneration => &mut self.regeneration,
+            BeaconUpgrade::Shield => &mut self.shield,
+            BeaconUpgrade::Pulse => &mut self.pulse,
+        }
+    }
+
+}
+
+/// A struct representing the Beacon game component. The beacon is the game
 /// piece the player is trying to defend. If enemies collide with the Beacon,
-/// the Beacon will lose health. If the Beacon runs out of health, the game 
-/// will be over.
+/// the Beacon will lose health. A Beacon which runs out of health has fallen,
+/// and depending on the game's Rules the game may be over.
 pub struct Beacon {
     pub idx: MapIdx,
     pub shape: GenericShape,
-    pub health_bar: GenericShape,
-    pub damage_bar: GenericShape,
-    pub health: i32,
+    pub health_bar: HealthBar,
+    pub health: Health,
     pub rotation: f64,
+    pub upgrades: BeaconUpgrades,
+    pub shield_hits: i32,
+    shield_recharge: f64,
+    aura_timer: f64,
+    pulse_cooldown: f64,
+    pulse_flash: f64,
 }
 
 impl Beacon {
@@ -49,74 +114,253 @@ impl Beacon {
              y: -BEACON_SIZE / 2.0,
         });
 
-        let mut health_bar = GenericShape::new(
-            ShapeVariant::Rect{
-                width: BEACON_SIZE,
-                height: HEALTH_BAR_HEIGHT 
-            }, 
-            HEALTH_COLOR,
-            shape.get_position() + Point2{x: 0.0, y: BEACON_SIZE + HEALTH_BAR_HEIGHT * 2.0}
-        );
-        health_bar.set_offset(Point2{
-             x: -BEACON_SIZE / 2.0,
-             y: -BEACON_SIZE / 2.0,
-        });
-        let mut damage_bar = GenericShape::new(
-            ShapeVariant::Rect{
-                width: 0.0,
-                height: HEALTH_BAR_HEIGHT 
-            }, 
-            DAMAGE_COLOR,
-            shape.get_position() + Point2{x: 0.0, y: BEACON_SIZE + HEALTH_BAR_HEIGHT * 2.0}
+        let health_bar = HealthBar::new(
+            map_idx_to_point2(pos) + Point2{x: 0.0, y: BEACON_SIZE + HEALTH_BAR_HEIGHT * 2.0},
+            BEACON_SIZE
         );
-        damage_bar.set_offset(Point2{
-             x: -BEACON_SIZE / 2.0,
-             y: -BEACON_SIZE / 2.0,
-        });
         Self {
             idx: pos,
             shape: shape,
             health_bar: health_bar,
-            damage_bar: damage_bar,
-            health: BEACON_STARTING_HEALTH,
-            rotation: 0.0
+            health: Health::new(BEACON_STARTING_HEALTH).with_invulnerability(BEACON_INVULNERABILITY),
+            rotation: 0.0,
+            upgrades: BeaconUpgrades::default(),
+            shield_hits: 0,
+            shield_recharge: 0.0,
+            aura_timer: 0.0,
+            pulse_cooldown: 0.0,
+            pulse_flash: 0.0,
+        }
+    }
+
+    /// Returns the number of resources the next level of an upgrade costs, or
+    /// None if the upgrade is already at the maximum level.
+    pub fn upgrade_cost(&self, upgrade: BeaconUpgrade) -> Option<i32> {
+        let level = self.upgrades.level(upgrade);
+        if level < BEACON_UPGRADE_MAX_LEVEL {
+            Some(level as i32 + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Buys the next level of an upgrade if there are enough resources,
+    /// taking the cost out of resources. Returns true if the upgrade was
+    /// bought. Upgrading the Shield fully recharges it.
+    pub fn upgrade(&mut self, upgrade: BeaconUpgrade, resources: &mut i32) -> bool {
+        match self.upgrade_cost(upgrade) {
+            Some(cost) if cost <= *resources => {
+                *resources -= cost;
+                *self.upgrades.level_mut(upgrade) += 1;
+                if upgrade == BeaconUpgrade::Shield {
+                    self.shield_hits = self.max_shield_hits();
+                }
+                true
+            },
+            _ => false,
         }
     }
 
-    pub fn damage(&mut self) {
-        self.health -= 1;
-        if let Some(offset) = self.shape.get_offset(){
-            self.health_bar = GenericShape::new(
-                ShapeVariant::Rect{
-                    width: BEACON_SIZE * self.health as f64 / BEACON_STARTING_HEALTH as f64,
-                    height: HEALTH_BAR_HEIGHT 
-                }, 
-                HEALTH_COLOR,
-                self.shape.get_position() + Point2{x: 0.0, y: BEACON_SIZE + HEALTH_BAR_HEIGHT * 2.0}
-            );
-            self.health_bar.set_offset(offset);
-            self.damage_bar = GenericShape::new(
-                ShapeVariant::Rect{
-                    width: BEACON_SIZE * (BEACON_STARTING_HEALTH - self.health) as f64 / BEACON_STARTING_HEALTH as f64,
-                    height: HEALTH_BAR_HEIGHT 
-                }, 
-                DAMAGE_COLOR,
-                self.shape.get_position() + Point2{x: BEACON_SIZE * self.health as f64 / BEACON_STARTING_HEALTH as f64, y:  BEACON_SIZE + HEALTH_BAR_HEIGHT * 2.0}
-            );
-            self.damage_bar.set_offset(offset);
+    /// Returns the radius of the healing Aura, or 0.0 without the upgrade.
+    /// Each level after the first widens the Aura by half.
+    pub fn aura_radius(&self) -> f64 {
+        match self.upgrades.aura {
+            0 => 0.0,
+            level => BEACON_AURA_RADIUS * (1.0 + (level - 1) as f64 * 0.5),
         }
-        
-        
+    }
+
+    /// Returns the radius of the Pulse, or 0.0 without the upgrade.
+    pub fn pulse_radius(&self) -> f64 {
+        match self.upgrades.pulse {
+            0 => 0.0,
+            _ => BEACON_PULSE_RADIUS,
+        }
+    }
+
+    /// Returns the damage the Pulse does to each enemy it hits.
+    pub fn pulse_damage(&self) -> i32 {
+        self.upgrades.pulse as i32
+    }
+
+    /// Returns the number of hits a fully charged Shield can absorb.
+    pub fn max_shield_hits(&self) -> i32 {
+        BEACON_SHIELD_HITS * self.upgrades.shield as i32
+    }
 
+    /// Returns true if the point is within distance of the Beacon's center.
+    pub fn within(&self, point: Point2, distance: f64) -> bool {
+        Vec2::new_from_point(point - self.shape.center_point()).length() <= distance
+    }
+
+    /// Turns the Beacon's Regeneration on or off. The Beacon only regenerates
+    /// between waves.
+    pub fn set_regenerating(&mut self, regenerating: bool) {
+        self.health.regeneration = if regenerating {
+            BEACON_REGENERATION * self.upgrades.regeneration as f64
+        } else {
+            0.0
+        };
+    }
+
+    /// Advances the Aura's healing timer while the Player at position is
+    /// inside it. Returns the amount the Player should be healed by this
+    /// tick. Higher levels heal more often.
+    pub fn aura_heal(&mut self, dt: f64, position: Point2) -> i32 {
+        if !self.within(position, self.aura_radius()) {
+            self.aura_timer = 0.0;
+            return 0;
+        }
+
+        self.aura_timer += dt * self.upgrades.aura as f64;
+        let heals = (self.aura_timer / BEACON_AURA_HEAL_INTERVAL).floor();
+        self.aura_timer -= heals * BEACON_AURA_HEAL_INTERVAL;
+        heals as i32
+    }
+
+    /// Returns true if the Pulse has been bought and has cooled down.
+    pub fn pulse_ready(&self) -> bool {
+        self.upgrades.pulse > 0 && self.pulse_cooldown <= 0.0
+    }
+
+    /// Returns true for a short time after the Pulse fires.
+    pub fn is_pulsing(&self) -> bool {
+        self.pulse_flash > 0.0
+    }
+
+    /// Fires the Pulse and starts its cooldown. Higher levels cool down
+    /// faster.
+    pub fn fire_pulse(&mut self) {
+        self.pulse_cooldown = BEACON_PULSE_COOLDOWN / self.upgrades.pulse as f64;
+        self.pulse_flash = BEACON_PULSE_FLASH;
+    }
+
+    /// Returns true once the Beacon has run out of health.
+    pub fn is_fallen(&self) -> bool {
+        self.health.is_dead()
+    }
+
+    /// Moves the Beacon and its HealthBar by delta. Returns true if the
+    /// Beacon moved onto a different Tile.
+    pub fn translate(&mut self, delta: Point2) -> bool {
+        self.shape.update(delta, None);
+        self.health_bar.update(delta);
+        let idx = point2_to_map_idx(self.shape.center_point());
+        let moved = idx != self.idx;
+        self.idx = idx;
+        moved
+    }
+
+    /// Damages the Beacon, and updates the HealthBar to match. While the
+    /// Shield has charge left, it absorbs the hit instead. A fallen Beacon
+    /// is greyed out.
+    pub fn damage(&mut self, amount: i32) -> DamageOutcome {
+        if self.shield_hits > 0 && !self.health.is_invulnerable() {
+            self.shield_hits -= 1;
+            self.shield_recharge = BEACON_SHIELD_RECHARGE;
+            return DamageOutcome::Ignored;
+        }
+
+        let outcome = self.health.damage(amount);
+        self.health_bar.set_fraction(self.health.fraction());
+        if outcome == DamageOutcome::Killed {
+            self.shield_hits = 0;
+            self.shape.set_color(BEACON_FALLEN_COLOR);
+        }
+        outcome
     }
 }
 
 impl entity::Entity for Beacon {
+    /// Spins the Beacon, recharges the Shield, cools down the Pulse, and
+    /// flashes the Beacon while it recovers from a hit.
     fn tick(&mut self, dt: f64) {
         let delta = Point2{
             x: 0.0,
             y: 0.0,
         };
         self.shape.update(delta, Some(BEACON_ROTATION_SPEED * dt));
+
+        // Recharge the Shield one hit at a time.
+        if self.shield_hits < self.max_shield_hits() {
+            self.shield_recharge -= dt;
+            if self.shield_recharge <= 0.0 {
+                self.shield_hits += 1;
+                self.shield_recharge = BEACON_SHIELD_RECHARGE;
+            }
+        }
+        self.pulse_cooldown = (self.pulse_cooldown - dt).max(0.0);
+        self.pulse_flash = (self.pulse_flash - dt).max(0.0);
+
+        self.health.tick(dt);
+        self.health_bar.set_fraction(self.health.fraction());
+        if self.health.is_flashing() {
+            self.shape.set_color(BEACON_FLASH_COLOR);
+        } else {
+            self.shape.set_color(BEACON_COLOR);
+        }
+    }
+}
+
+#[cfg(test)]
+mod beacon_tests {
+
+    use super::{Beacon, BeaconUpgrade};
+    use crate::entity::health::DamageOutcome;
+    use crate::levels::map::MapIdx;
+    use crate::traits::entity::Entity;
+    use crate::game::consts::{
+        BEACON_UPGRADE_MAX_LEVEL,
+        BEACON_SHIELD_HITS,
+        BEACON_SHIELD_RECHARGE,
+        BEACON_STARTING_HEALTH,
+    };
+
+    #[test]
+    fn test_upgrade_costs() {
+        let mut beacon = Beacon::new(MapIdx::new(5, 5));
+        let mut resources = 3;
+        assert!(beacon.upgrade(BeaconUpgrade::Aura, &mut resources));
+        assert!(beacon.upgrade(BeaconUpgrade::Aura, &mut resources));
+        assert_eq!(0, resources);
+        assert!(!beacon.upgrade(BeaconUpgrade::Aura, &mut resources));
+        assert_eq!(2, beacon.upgrades.aura);
+
+        let mut resources = 100;
+        beacon.upgrade(BeaconUpgrade::Aura, &mut resources);
+        assert_eq!(None, beacon.upgrade_cost(BeaconUpgrade::Aura));
+        assert!(!beacon.upgrade(BeaconUpgrade::Aura, &mut resources));
+        assert_eq!(BEACON_UPGRADE_MAX_LEVEL, beacon.upgrades.aura);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_shield_absorbs_and_recharges() {
+        let mut beacon = Beacon::new(MapIdx::new(5, 5));
+        let mut resources = 1;
+        beacon.upgrade(BeaconUpgrade::Shield, &mut resources);
+
+        for _ in 0..BEACON_SHIELD_HITS {
+            assert_eq!(DamageOutcome::Ignored, beacon.damage(1));
+        }
+        assert_eq!(BEACON_STARTING_HEALTH, beacon.health.current);
+        assert_eq!(DamageOutcome::Damaged, beacon.damage(1));
+
+        beacon.tick(BEACON_SHIELD_RECHARGE);
+        assert_eq!(1, beacon.shield_hits);
+    }
+
+    #[test]
+    fn test_aura_heals_inside_radius() {
+        let mut beacon = Beacon::new(MapIdx::new(5, 5));
+        let mut resources = 1;
+        beacon.upgrade(BeaconUpgrade::Aura, &mut resources);
+
+        let inside = beacon.shape.center_point();
+        let outside = inside + inside * 10.0;
+        assert_eq!(0, beacon.aura_heal(10.0, outside));
+        assert_eq!(1, beacon.aura_heal(2.5, inside));
+        assert_eq!(0, beacon.aura_heal(1.0, inside));
+        assert_eq!(1, beacon.aura_heal(0.5, inside));
+    }
+
+}
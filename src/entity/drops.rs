@@ -1,42 +1,46 @@
-use crate::traits::{entity};
 use crate::traits::draw::{GenericShape, ShapeVariant};
 use crate::math::Point2;
+use crate::ecs::{World, EntityId};
 use crate::game::consts::{
     DROP_SIZE,
-    DROP_ROTATION_SPEED,
     RESOURCE_COLOR,
+    AMMO_COLOR,
 };
-/// A structure to represent a tower resource which can be used by the Player
-pub struct Resource {
-    pub shape: GenericShape,
-    pub rotation: f64,
+
+/// The different kinds of Resource. Tower resources are spent building 
+/// Towers, and Ammo resources refill the Player's ranged weapon.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResourceVariant {
+    Tower,
+    Ammo,
 }
 
-impl Resource {
-    /// Creates a new Resource
-    pub fn new(position: Point2) -> Self {
-        let mut shape = GenericShape::new(
-            ShapeVariant::Rect{
-                width: DROP_SIZE,
-                height: DROP_SIZE,
-            },
-            RESOURCE_COLOR, 
-            position
-        );
-        shape.set_offset(Point2{
-            x: DROP_SIZE / -2.0, 
-            y: DROP_SIZE / -2.0
-        });
-        Self {
-            shape: shape,
-            rotation: 0.0,
-        }
-    }
+/// The pickup component of a Resource, which can be collected by the Player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Resource {
+    pub variant: ResourceVariant,
 }
 
-impl entity::Entity for Resource {
-    /// Rotates the Resource
-    fn tick(&mut self, dt: f64){
-        self.shape.update(Point2{x: 0.0, y: 0.0}, Some(DROP_ROTATION_SPEED * dt));
-    }
-}
\ No newline at end of file
+/// Creates a new Resource in the world centered on position.
+pub fn spawn_resource(world: &mut World, position: Point2, variant: ResourceVariant) -> EntityId {
+    let color = match variant {
+        ResourceVariant::Tower => RESOURCE_COLOR,
+        ResourceVariant::Ammo => AMMO_COLOR,
+    };
+    let mut shape = GenericShape::new(
+        ShapeVariant::Rect{
+            width: DROP_SIZE,
+            height: DROP_SIZE,
+        },
+        color, 
+        position
+    );
+    shape.set_offset(Point2{
+        x: DROP_SIZE / -2.0, 
+        y: DROP_SIZE / -2.0
+    });
+    let id = world.spawn();
+    world.shapes.insert(id, shape);
+    world.pickups.insert(id, Resource{variant});
+    id
+}
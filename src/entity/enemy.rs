@@ -1,11 +1,15 @@
-use crate::traits::{entity, state};
+use crate::traits::state;
 use crate::traits::draw::{GenericShape,ShapeVariant};
 use crate::math::{Vec2, Point2};
+use crate::entity::health::{Health, DamageOutcome};
+use crate::ecs::{World, EntityId, Velocity};
+use crate::levels::map::MapIdx;
 use crate::game::consts::{
     ENEMY_SIZE, 
     ENEMY_RADIUS, 
     ENEMY_COLOR, 
-    ENEMY_SPEED
+    ENEMY_STARTING_HEALTH,
+    ENEMY_LOOKAHEAD,
 };
 
 /// A structure describing the states of the Enemy game components. While in
@@ -16,62 +20,133 @@ pub enum EnemyState {
     Player,
 }
 
-/// A structure to describe the Enemy game component. They'll try to hunt down
-/// the Beacon and the Player. 
-pub struct Enemy {
-    pub shape: GenericShape,
-    pub direction: Vec2,
+/// The AI component of an Enemy. Enemies try to hunt down the Beacons and the
+/// Player. The target is the index of the Beacon the Enemy is heading for.
+/// 
+/// The steering force is worked out by the GameModel each tick from the 
+/// Enemy's neighbours and the walls around it. While steering is None, the
+/// Enemy heads straight for the next point of its path. terrain_speed is the
+/// fraction of ENEMY_SPEED the Enemy moves at on the Tile it's on.
+/// 
+/// An Enemy with no way through to any Beacon paths up to a 
+/// Tile::DestructibleWall instead, and attacks it once it gets there. breach
+/// is the wall being broken through, and wall_attack_timer counts down to the
+/// Enemy's next attack on it.
+pub struct EnemyAi {
     pub path: Vec<Point2>,
     pub state: EnemyState,
+    pub last_swing_hit: u32,
+    pub target: usize,
+    pub steering: Option<Vec2>,
+    pub terrain_speed: f64,
+    pub breach: Option<MapIdx>,
+    pub wall_attack_timer: f64,
 }
 
-impl Enemy {
+impl Default for EnemyAi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnemyAi {
 
-    /// Creates a new enemy in the start position.
-    pub fn new(start_position: Point2) -> Self {
+    /// Creates a new EnemyAi with nowhere to go.
+    pub fn new() -> Self {
         Self {
-            shape: GenericShape::new(
-                ShapeVariant::Circle{
-                    size: ENEMY_SIZE,
-                    radius: ENEMY_RADIUS,
-                }, 
-                ENEMY_COLOR,
-                start_position
-            ),
-            direction: Vec2 {x: 0.0, y: 0.0},
             path: Vec::new(),
-            state: EnemyState::Beacon
+            state: EnemyState::Beacon,
+            last_swing_hit: 0,
+            target: 0,
+            steering: None,
+            terrain_speed: 1.0,
+            breach: None,
+            wall_attack_timer: 0.0,
         }
     }
 
-}
+    /// Returns the point ENEMY_LOOKAHEAD further along the path than 
+    /// position, or the end of the path if it's closer than that. Aiming 
+    /// ahead lets the Enemy cut smoothly through the corners of its path.
+    fn lookahead(&self, position: Point2) -> Point2 {
+        let mut from = position;
+        let mut remaining = ENEMY_LOOKAHEAD;
+        for point in self.path.iter() {
+            let leg = *point - from;
+            let len = Vec2::new_from_point(leg).length();
+            if len >= remaining {
+                return from + leg * (remaining / len);
+            }
+            remaining -= len;
+            from = *point;
+        }
+        from
+    }
 
-impl entity::Entity for Enemy {
-    fn tick(&mut self, dt: f64) {
-        if self.path.len() > 0 {
-            let mut dist = self.path[0] - self.shape.get_position();
-            if (dist.x).abs() + (dist.y).abs() < 5.0 {
-                self.path.remove(0);
-                if self.path.len() > 0 {
-                    dist = self.path[0] - self.shape.get_position();
+    /// Returns the direction an Enemy at position should head in to follow
+    /// its path, or None once the path is done. Points of the path the Enemy
+    /// has reached are dropped. While steering, the Enemy aims ahead along 
+    /// its path and adds the steering force to its heading, without going 
+    /// faster than full speed.
+    pub fn heading(&mut self, position: Point2) -> Option<Vec2> {
+        let mut dist = *self.path.first()? - position;
+        if (dist.x).abs() + (dist.y).abs() < 5.0 {
+            self.path.remove(0);
+            dist = *self.path.first()? - position;
+        }
+        Some(match self.steering {
+            Some(force) => {
+                let ahead = Vec2::new_unit_from_point(self.lookahead(position) - position);
+                let heading = Vec2::new(ahead.x + force.x, ahead.y + force.y);
+                let len = heading.length();
+                if len > 1.0 {
+                    Vec2::new(heading.x / len, heading.y / len)
                 } else {
-                    return;
+                    heading
                 }
-            }
-            self.direction = Vec2::new_unit_from_point(dist);
-            let delta = Point2 { 
-                x: self.direction.x * ENEMY_SPEED * dt, 
-                y: self.direction.y * ENEMY_SPEED * dt
-            };
-            self.shape.update(delta, None);
-        }
-        
+            },
+            None => Vec2::new_unit_from_point(dist),
+        })
     }
+
 }
 
-impl state::State for Enemy {
+impl state::State for EnemyAi {
     type StateEnum = EnemyState;
     fn change_state(&mut self, new_state: Self::StateEnum) {
         self.state = new_state;
     }
-}
\ No newline at end of file
+}
+
+/// Creates a new Enemy in the world at the start position, with nowhere to
+/// go.
+pub fn spawn_enemy(world: &mut World, start_position: Point2) -> EntityId {
+    let id = world.spawn();
+    world.shapes.insert(id, GenericShape::new(
+        ShapeVariant::Circle{
+            size: ENEMY_SIZE,
+            radius: ENEMY_RADIUS,
+        }, 
+        ENEMY_COLOR,
+        start_position
+    ));
+    world.healths.insert(id, Health::new(ENEMY_STARTING_HEALTH));
+    world.velocities.insert(id, Velocity::new(Vec2::new(0.0, 0.0), 0.0));
+    world.enemies.insert(id, EnemyAi::new());
+    id
+}
+
+/// Deals damage to the Enemy and pushes it away with the knockback 
+/// velocity. Returns true if the Enemy has run out of health.
+pub fn hit_enemy(world: &mut World, id: EntityId, damage: i32, knockback: Vec2) -> bool {
+    if let Some(velocity) = world.velocities.get_mut(id) {
+        velocity.knockback = Vec2::new(
+            velocity.knockback.x + knockback.x,
+            velocity.knockback.y + knockback.y
+        );
+    }
+    match world.healths.get_mut(id) {
+        Some(health) => health.damage(damage) == DamageOutcome::Killed,
+        None => false,
+    }
+}
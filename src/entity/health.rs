@@ -0,0 +1,164 @@
+use crate::game::consts::HEALTH_FLASH_RATE;
+
+/// The result of trying to damage something with Health.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DamageOutcome {
+    Ignored,
+    Damaged,
+    Killed,
+}
+
+/// A component describing how much damage something can take.
+///
+/// After being hit, Health is invulnerable for a short time, during which 
+/// any further damage is ignored. Health regenerates at a constant rate (in
+/// health per second) up to the maximum. Running out of Health is reported 
+/// once, by damage() returning Killed, and it's up to whatever was hit to 
+/// handle its death.
+pub struct Health {
+    pub current: i32,
+    pub max: i32,
+    pub regeneration: f64,
+    pub invulnerability: f64,
+    invulnerable_for: f64,
+    regen_progress: f64,
+}
+
+impl Health {
+
+    /// Creates new Health with max health, and no regeneration or 
+    /// invulnerability.
+    pub fn new(max: i32) -> Self {
+        Self {
+            current: max,
+            max,
+            regeneration: 0.0,
+            invulnerability: 0.0,
+            invulnerable_for: 0.0,
+            regen_progress: 0.0,
+        }
+    }
+
+    /// Sets the health regenerated each second.
+    pub fn with_regeneration(mut self, regeneration: f64) -> Self {
+        self.regeneration = regeneration;
+        self
+    }
+
+    /// Sets how long (in seconds) damage is ignored for after being hit.
+    pub fn with_invulnerability(mut self, invulnerability: f64) -> Self {
+        self.invulnerability = invulnerability;
+        self
+    }
+
+    /// Returns true if the Health has run out.
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0
+    }
+
+    /// Returns true while recovering from a hit.
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_for > 0.0
+    }
+
+    /// Returns true if something recovering from a hit should currently be
+    /// drawn in its flash colour. Alternates HEALTH_FLASH_RATE times a second.
+    pub fn is_flashing(&self) -> bool {
+        self.is_invulnerable() && (self.invulnerable_for * HEALTH_FLASH_RATE) as i32 % 2 == 1
+    }
+
+    /// Returns the fraction of the maximum health remaining.
+    pub fn fraction(&self) -> f64 {
+        if self.max <= 0 {
+            0.0
+        } else {
+            self.current.max(0) as f64 / self.max as f64
+        }
+    }
+
+    /// Deals damage, unless invulnerable or already dead.
+    pub fn damage(&mut self, amount: i32) -> DamageOutcome {
+        if self.is_dead() || self.is_invulnerable() || amount <= 0 {
+            return DamageOutcome::Ignored;
+        }
+
+        self.current -= amount;
+        self.invulnerable_for = self.invulnerability;
+        self.regen_progress = 0.0;
+        if self.is_dead() {
+            self.current = 0;
+            DamageOutcome::Killed
+        } else {
+            DamageOutcome::Damaged
+        }
+    }
+
+    /// Restores health, up to the maximum. The dead can't be healed.
+    pub fn heal(&mut self, amount: i32) {
+        if !self.is_dead() {
+            self.current = (self.current + amount).min(self.max);
+        }
+    }
+
+    /// Advances the invulnerability timer and regenerates health.
+    pub fn tick(&mut self, dt: f64) {
+        self.invulnerable_for = (self.invulnerable_for - dt).max(0.0);
+        if self.is_dead() || self.current >= self.max {
+            self.regen_progress = 0.0;
+            return;
+        }
+
+        self.regen_progress += self.regeneration * dt;
+        if self.regen_progress >= 1.0 {
+            let amount = self.regen_progress.floor();
+            self.regen_progress -= amount;
+            self.heal(amount as i32);
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod health_tests {
+
+    use super::{Health, DamageOutcome};
+
+    #[test]
+    fn test_invulnerability() {
+        let mut health = Health::new(10).with_invulnerability(1.0);
+        health.damage(1);
+        assert!(health.is_invulnerable());
+        assert_eq!(DamageOutcome::Ignored, health.damage(1));
+        assert_eq!(9, health.current);
+        health.tick(1.0);
+        assert!(!health.is_invulnerable());
+        health.damage(1);
+        assert_eq!(8, health.current);
+    }
+
+    #[test]
+    fn test_regeneration() {
+        let mut health = Health::new(10).with_regeneration(2.0);
+        health.damage(5);
+        health.tick(0.25);
+        assert_eq!(5, health.current);
+        health.tick(0.25);
+        assert_eq!(6, health.current);
+        health.tick(10.0);
+        assert_eq!(10, health.current);
+    }
+
+    #[test]
+    fn test_death() {
+        let mut health = Health::new(2);
+        assert_eq!(DamageOutcome::Damaged, health.damage(1));
+        assert_eq!(DamageOutcome::Killed, health.damage(5));
+        assert_eq!(0, health.current);
+
+        // Death is only reported once, and the dead can't be healed.
+        assert_eq!(DamageOutcome::Ignored, health.damage(1));
+        health.heal(1);
+        assert!(health.is_dead());
+    }
+
+}
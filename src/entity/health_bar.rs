@@ -0,0 +1,69 @@
+use crate::traits::draw::{Draw, Context, Graphics, GenericShape, ShapeVariant};
+use crate::math::Point2;
+use crate::game::consts::{
+    HEALTH_BAR_HEIGHT,
+    HEALTH_COLOR,
+    DAMAGE_COLOR,
+};
+
+/// A widget showing how much health something has left. The remaining health
+/// is drawn in HEALTH_COLOR from the left, and the missing health is drawn in
+/// DAMAGE_COLOR to the right of it.
+pub struct HealthBar {
+    health_shape: GenericShape,
+    damage_shape: GenericShape,
+    width: f64,
+    fraction: f64,
+}
+
+impl HealthBar {
+
+    /// Creates a full HealthBar with its top left corner at position.
+    pub fn new(position: Point2, width: f64) -> Self {
+        Self {
+            health_shape: GenericShape::new(
+                ShapeVariant::Rect{width, height: HEALTH_BAR_HEIGHT},
+                HEALTH_COLOR,
+                position
+            ),
+            damage_shape: GenericShape::new(
+                ShapeVariant::Rect{width: 0.0, height: HEALTH_BAR_HEIGHT},
+                DAMAGE_COLOR,
+                position
+            ),
+            width,
+            fraction: 1.0,
+        }
+    }
+
+    /// Sets the fraction of the bar which is filled with health.
+    pub fn set_fraction(&mut self, fraction: f64) {
+        let fraction = fraction.clamp(0.0, 1.0);
+        self.fraction = fraction;
+
+        let position = self.health_shape.get_position();
+        let filled = self.width * fraction;
+        self.health_shape.shape = ShapeVariant::Rect{width: filled, height: HEALTH_BAR_HEIGHT};
+        self.damage_shape.shape = ShapeVariant::Rect{width: self.width - filled, height: HEALTH_BAR_HEIGHT};
+        self.damage_shape.set_position(position + Point2{x: filled, y: 0.0});
+    }
+
+    /// Returns the fraction of the bar which is filled with health.
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Moves the HealthBar by delta.
+    pub fn update(&mut self, delta: Point2) {
+        self.health_shape.update(delta, None);
+        self.damage_shape.update(delta, None);
+    }
+
+}
+
+impl Draw for HealthBar {
+    fn draw<G: Graphics>(&self, c: &Context, g: &mut G) {
+        self.health_shape.draw(c, g);
+        self.damage_shape.draw(c, g);
+    }
+}
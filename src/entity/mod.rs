@@ -1,7 +1,11 @@
 pub mod tile;
 pub mod player;
 pub mod attack;
+pub mod abilities;
+pub mod health;
+pub mod health_bar;
 pub mod beacon;
 pub mod enemy;
+pub mod spawner;
 pub mod drops;
 pub mod towers;
\ No newline at end of file
@@ -1,44 +1,80 @@
 use crate::entity::attack::Attack;
+use crate::entity::abilities::{Abilities, AbilityKind};
+use crate::entity::health::{Health, DamageOutcome};
+use crate::entity::health_bar::HealthBar;
+use crate::entity::towers::bullet::Bullet;
 use crate::math::Vec2;
 use crate::math::Point2;
 use crate::traits::draw::{GenericShape, ShapeVariant};
 use crate::traits::{entity, state};
+use crate::traits::state::State;
 use crate::game::consts::{
     PLAYER_SIZE, 
     PLAYER_RADIUS, 
     PLAYER_COLOR, 
     PLAYER_SPEED, 
+    PLAYER_ACCELERATION,
+    PLAYER_FRICTION,
     PLAYER_STARTING_HEALTH,
+    PLAYER_REGENERATION,
+    PLAYER_INVULNERABILITY,
+    PLAYER_FLASH_COLOR,
+    PLAYER_STARTING_AMMO,
+    PLAYER_FIRE_COOLDOWN,
+    PLAYER_BULLET_DAMAGE,
+    DASH_SPEED,
+    SHIELD_HITS,
     HEALTH_BAR_HEIGHT,
-    HEALTH_COLOR,
-    DAMAGE_COLOR,
-    PI,
 };
 
 /// A struct defining the different states a Player can have. While Stationary,
-/// the Player isn't moving. While Moving, the player will move in the 
-/// direction of the Mouse Cursor. While attacking, the player will remain
+/// the Player isn't trying to move. While Moving, the player will accelerate
+/// in the direction of its move input. While attacking, the player will remain
 /// stationary. The Player can only move out of the Attacking state to the 
-/// FinishedAttacking state, which acts as a signal saying the attacking 
-/// animation has finished. 
+/// FinishedAttacking state, which acts as a signal saying the Attack's combo
+/// has finished. 
+/// 
+/// While Dashing, the Player moves at DASH_SPEED and can't be damaged or 
+/// change state until the Dash ends. While Channelling, the Player is 
+/// recalling to the Beacon, which is interrupted by moving, attacking or 
+/// taking damage.
 pub enum PlayerState{
     Stationary,
     Moving,
     Attacking,
     FinishedAttacking,
+    Dashing,
+    Channelling,
+}
+
+/// The ways the Player can be steered. While Strafing, the movement keys move
+/// the Player in any of 8 directions independently of where it's aiming. 
+/// While following the Cursor, holding the forward key moves the Player 
+/// towards the mouse.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MovementMode {
+    Strafe,
+    CursorFollow,
 }
 
 /// A representation of the Player. The Player struct is responsible for 
 /// the logic surrounding how to update itself.
 pub struct Player {
     pub shape: GenericShape,
-    pub health_bar: GenericShape,
-    pub damage_bar: GenericShape,
+    pub health_bar: HealthBar,
     pub attack: Attack,
-    pub health: i32,
+    pub health: Health,
     pub state: PlayerState,
     pub direction: Vec2,
+    pub velocity: Vec2,
+    pub move_input: Vec2,
+    pub movement_mode: MovementMode,
     pub resources: i32,
+    pub ammo: i32,
+    pub fire_cooldown: f64,
+    pub abilities: Abilities,
+    pub shield_hits: i32,
+    recalled: bool,
 }
 
 impl Player {
@@ -54,115 +90,275 @@ impl Player {
                 PLAYER_COLOR,
                 start_position
             ),
-            health_bar: GenericShape::new(
-                ShapeVariant::Rect{
-                    width: PLAYER_SIZE,
-                    height: HEALTH_BAR_HEIGHT,
-                }, 
-                HEALTH_COLOR,
-                start_position + Point2{x: 0.0, y: PLAYER_SIZE + HEALTH_BAR_HEIGHT * 1.2}
-            ),
-            damage_bar: GenericShape::new(
-                ShapeVariant::Rect{
-                    width: 0.0,
-                    height: HEALTH_BAR_HEIGHT,
-                }, 
-                DAMAGE_COLOR,
-                start_position + Point2{x: 0.0, y: PLAYER_SIZE + HEALTH_BAR_HEIGHT * 1.2}
+            health_bar: HealthBar::new(
+                start_position + Point2{x: 0.0, y: PLAYER_SIZE + HEALTH_BAR_HEIGHT * 1.2},
+                PLAYER_SIZE
             ),
             attack: Attack::new(), 
-            health: PLAYER_STARTING_HEALTH,
+            health: Health::new(PLAYER_STARTING_HEALTH)
+                .with_regeneration(PLAYER_REGENERATION)
+                .with_invulnerability(PLAYER_INVULNERABILITY),
             state: PlayerState::Stationary,
             direction: Vec2::new_unit(0.0, 1.0),
+            velocity: Vec2::new(0.0, 0.0),
+            move_input: Vec2::new(0.0, 0.0),
+            movement_mode: MovementMode::Strafe,
             resources: 0,
+            ammo: PLAYER_STARTING_AMMO,
+            fire_cooldown: 0.0,
+            abilities: Abilities::new(),
+            shield_hits: 0,
+            recalled: false,
         }
     }
 
-    /// A function to move the player. The Player moves at PLAYER_SPEED in the 
-    /// direction defined by the unit vector self.direction. The Player only 
-    /// moves while in the Moving state.
-    /// 
-    /// Assumes that direction is a unit vector.
-    pub fn update_position(&mut self, dt: f64) {
-        match self.state {
-            PlayerState::Moving => {
-                let delta = Point2{
-                    x: self.direction.x * PLAYER_SPEED * dt,
-                    y: self.direction.y * PLAYER_SPEED * dt
-                };
-                self.shape.update(delta, None);
-                self.health_bar.update(delta, None);
-                self.damage_bar.update(delta, None);
-            },
-            _ => {}
+    /// Sets the direction the Player wants to move in. Any non zero input is
+    /// scaled to unit length so that diagonal movement isn't faster than 
+    /// moving along a single axis.
+    pub fn set_move_input(&mut self, input: Vec2) {
+        let len = input.length();
+        if len > 0.0 {
+            self.move_input = Vec2::new(input.x / len, input.y / len);
+        } else {
+            self.move_input = Vec2::new(0.0, 0.0);
         }
     }
 
+    /// Accelerates the Player towards PLAYER_SPEED in the direction of the 
+    /// move input. Without any input (or while Attacking or Channelling) 
+    /// friction slows the Player down until it stops. The velocity of a Dash
+    /// is left untouched.
+    pub fn update_velocity(&mut self, dt: f64) {
+        let input = match self.state {
+            PlayerState::Dashing => return,
+            PlayerState::Attacking | PlayerState::Channelling => Vec2::new(0.0, 0.0),
+            _ => self.move_input,
+        };
+        let rate = if input.length() > 0.0 {
+            PLAYER_ACCELERATION
+        } else {
+            PLAYER_FRICTION
+        };
+
+        let diff = Vec2::new(
+            input.x * PLAYER_SPEED - self.velocity.x,
+            input.y * PLAYER_SPEED - self.velocity.y
+        );
+        let dist = diff.length();
+        let max_change = rate * dt;
+        if dist <= max_change {
+            self.velocity = Vec2::new(input.x * PLAYER_SPEED, input.y * PLAYER_SPEED);
+        } else {
+            self.velocity = Vec2::new(
+                self.velocity.x + diff.x * max_change / dist,
+                self.velocity.y + diff.y * max_change / dist
+            );
+        }
+    }
+
+    /// Moves the Player and its health bars by delta. Collision with the 
+    /// Level is expected to have been resolved already.
+    pub fn translate(&mut self, delta: Point2) {
+        self.shape.update(delta, None);
+        self.health_bar.update(delta);
+        self.attack.shape.update(delta, None);
+    }
+
     /// Sets the Player direction to point towards the cursor. The direction 
-    /// must be a unit vector. 
+    /// must be a unit vector. Swings are aimed when they start, so changing
+    /// direction doesn't move a Swing which is already underway.
     pub fn update_direction(&mut self, cursor_pos: &Point2) {
 
         let delta = *cursor_pos - self.shape.center_point();
         self.direction = Vec2::new_unit_from_point(delta);
-    
+
+    }
+
+    /// Starts a Swing in the direction the Player is facing. If the Player is
+    /// already attacking, the next Swing of the Weapon's combo is queued. The
+    /// Player can't attack while Dashing.
+    pub fn attack(&mut self) {
+        if let PlayerState::Dashing = self.state {
+            return;
+        }
+        let aim = self.direction.y.atan2(self.direction.x);
+        self.attack.start(aim);
+        self.change_state(PlayerState::Attacking);
+    }
+
+    /// Fires a Bullet from the center of the Player in the direction it's 
+    /// facing. Firing uses one ammo and can't be done while attacking or 
+    /// before the previous shot has cooled down. Returns None if the Player
+    /// couldn't fire.
+    pub fn fire(&mut self) -> Option<Bullet> {
         match self.state {
-            PlayerState::Attacking => {
-                let mut rad = self.direction.y / self.direction.x;
-                rad = rad.atan();
-                
-                match [self.direction.x < 0.0, self.direction.y < 0.0] {
-                    [true, true] => rad = PI * 2.0 - rad,
-                    [true, false] => rad = rad * -1.0,
-                    [false, true] => rad = PI + rad * -1.0,
-                    [false, false] => rad = PI - rad
+            PlayerState::Attacking => None,
+            _ if self.ammo <= 0 || self.fire_cooldown > 0.0 => None,
+            _ => {
+                self.ammo -= 1;
+                self.fire_cooldown = PLAYER_FIRE_COOLDOWN;
+                let aim = self.direction.y.atan2(self.direction.x);
+                let mut bullet = Bullet::aimed(self.shape.center_point(), aim);
+                bullet.damage = PLAYER_BULLET_DAMAGE;
+                Some(bullet)
+            }
+        }
+    }
+
+    /// Tries to use an Ability. Abilities can't be used while Dashing, and
+    /// only the Shield can be used while Attacking. Returns true if the 
+    /// Ability was used.
+    pub fn use_ability(&mut self, kind: AbilityKind) -> bool {
+        match (&self.state, kind) {
+            (PlayerState::Dashing, _) => false,
+            (PlayerState::Attacking, AbilityKind::Dash) |
+            (PlayerState::Attacking, AbilityKind::Recall) => false,
+            (_, AbilityKind::Dash) => {
+                if !self.abilities.dash.activate() {
+                    return false;
+                }
+                let dir = if self.move_input.length() > 0.0 {
+                    self.move_input
+                } else {
+                    self.direction
+                };
+                let len = dir.length();
+                self.velocity = Vec2::new(dir.x / len * DASH_SPEED, dir.y / len * DASH_SPEED);
+                self.abilities.recall.cancel();
+                self.change_state(PlayerState::Dashing);
+                true
+            },
+            (_, AbilityKind::Shield) => {
+                if !self.abilities.shield.activate() {
+                    return false;
+                }
+                self.shield_hits = SHIELD_HITS;
+                true
+            },
+            (_, AbilityKind::Recall) => {
+                if !self.abilities.recall.activate() {
+                    return false;
                 }
+                self.change_state(PlayerState::Channelling);
+                true
+            }
+        }
+    }
+
+    /// Returns true if the Player can't currently be damaged, either because
+    /// it's Dashing or because it's recovering from a hit.
+    pub fn is_invulnerable(&self) -> bool {
+        matches!(self.state, PlayerState::Dashing) || self.health.is_invulnerable()
+    }
+
+    /// Returns true if a Shield is up and has hits left to absorb.
+    pub fn is_shielded(&self) -> bool {
+        self.abilities.shield.is_active() && self.shield_hits > 0
+    }
 
-                rad = PI - rad;
+    /// Returns true once, after a Recall channel has completed. The caller is
+    /// responsible for moving the Player to the Beacon.
+    pub fn take_recall(&mut self) -> bool {
+        let recalled = self.recalled;
+        self.recalled = false;
+        recalled
+    }
 
-                self.attack.shape.set_rotation(rad);
+    /// Moves the Player, so that its center is at the input position. The
+    /// jump is instant, rather than being drawn sliding across the map.
+    pub fn teleport(&mut self, center: Point2) {
+        let delta = center - self.shape.center_point();
+        self.translate(delta);
+        self.velocity = Vec2::new(0.0, 0.0);
+        self.shape.save_transform();
+        self.attack.shape.save_transform();
+    }
+
+    /// Advances each of the Player's Abilities, ending a Dash or completing a
+    /// Recall when their time is up.
+    fn tick_abilities(&mut self, dt: f64) {
+        if self.abilities.dash.tick(dt) {
+            let len = self.velocity.length();
+            if len > PLAYER_SPEED {
+                self.velocity = Vec2::new(
+                    self.velocity.x / len * PLAYER_SPEED,
+                    self.velocity.y / len * PLAYER_SPEED
+                );
+            }
+            self.state = PlayerState::Stationary;
+        }
+        if self.abilities.shield.tick(dt) {
+            self.shield_hits = 0;
+        }
+        if self.abilities.recall.tick(dt) {
+            if let PlayerState::Channelling = self.state {
+                self.recalled = true;
+                self.state = PlayerState::Stationary;
             }
-            _ => {}
         }
-
     }
 
-    pub fn damage(&mut self) {
-        self.health -= 1;
-        self.health_bar = GenericShape::new(
-            ShapeVariant::Rect{
-                width: PLAYER_SIZE * self.health as f64 / PLAYER_STARTING_HEALTH as f64,
-                height: HEALTH_BAR_HEIGHT 
-            }, 
-            HEALTH_COLOR,
-            self.health_bar.get_position(),
-        );
-        self.damage_bar = GenericShape::new(
-            ShapeVariant::Rect{
-                width: PLAYER_SIZE * (PLAYER_STARTING_HEALTH - self.health) as f64 / PLAYER_STARTING_HEALTH as f64,
-                height: HEALTH_BAR_HEIGHT 
-            }, 
-            DAMAGE_COLOR,
-            self.health_bar.get_position() + Point2{x: PLAYER_SIZE * self.health as f64 / PLAYER_STARTING_HEALTH as f64, y: 0.0}
-        );
+    /// Damages the Player, unless it's invulnerable or the hit is absorbed by
+    /// a Shield. Taking damage interrupts a Recall.
+    pub fn damage(&mut self, amount: i32) -> DamageOutcome {
+        if self.is_invulnerable() {
+            return DamageOutcome::Ignored;
+        }
+        if self.is_shielded() {
+            self.shield_hits -= 1;
+            return DamageOutcome::Ignored;
+        }
+        if let PlayerState::Channelling = self.state {
+            self.abilities.recall.cancel();
+            self.state = PlayerState::Stationary;
+        }
+        let outcome = self.health.damage(amount);
+        self.health_bar.set_fraction(self.health.fraction());
+        outcome
     }
 
 }
 
 impl entity::Entity for Player {
+    /// Updates the Player's Abilities, Health, velocity, weapon cooldown and
+    /// Attack. The Player flashes while it recovers from a hit. The Player isn't moved here,
+    /// as the movement has to be swept against the Level first. Once the 
+    /// Attack has finished, the Player moves to the FinishedAttacking state.
     fn tick(&mut self, dt: f64) {
-        self.update_position(dt);
+        self.tick_abilities(dt);
+        self.health.tick(dt);
+        self.health_bar.set_fraction(self.health.fraction());
+        if self.health.is_flashing() {
+            self.shape.set_color(PLAYER_FLASH_COLOR);
+        } else {
+            self.shape.set_color(PLAYER_COLOR);
+        }
+        self.update_velocity(dt);
+        self.fire_cooldown = (self.fire_cooldown - dt).max(0.0);
+        if self.attack.tick(dt, self.shape.center_point()) {
+            self.change_state(PlayerState::FinishedAttacking);
+        }
     }
 }
 
 impl state::State for Player {
     type StateEnum = PlayerState;
     /// Can transition from any state to any state with the exception of the 
-    /// Attacking state. 
+    /// Attacking, Dashing and Channelling states. 
     /// 
-    /// The Player can transition to the attacking state from any state, but
-    /// must transition to the FinishedAttacking state from the Attacking state
+    /// The Player can transition to the attacking state from any state other
+    /// than Dashing, but must transition to the FinishedAttacking state from
+    /// the Attacking state. A Dash can't be left until it ends. A Channel is
+    /// interrupted by Moving or Attacking, but not by standing still.
     fn change_state(&mut self, new_state: Self::StateEnum) {
         match [&self.state, &new_state] {
+            [PlayerState::Dashing, _] => (),
+            [PlayerState::Channelling, PlayerState::Stationary] => (),
+            [PlayerState::Channelling, _] => {
+                self.abilities.recall.cancel();
+                self.state = PlayerState::Stationary;
+                self.change_state(new_state);
+            },
             [PlayerState::Attacking, PlayerState::FinishedAttacking] => {
                 self.state = new_state;
             },
@@ -178,6 +374,9 @@ impl state::State for Player {
             },[PlayerState::Stationary, _] => {
                 self.state = new_state;
             },
+            [_, PlayerState::Dashing] => {
+                self.state = new_state;
+            },
             _ => ()
             
         }
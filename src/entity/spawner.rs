@@ -0,0 +1,54 @@
+use crate::traits::draw::{GenericShape, ShapeVariant};
+use crate::entity::health::{Health, DamageOutcome};
+use crate::entity::health_bar::HealthBar;
+use crate::levels::map::MapIdx;
+use crate::math::Point2;
+use crate::game::consts::{
+    TILE_SIZE,
+    SPAWNER_COLOR,
+    HEALTH_BAR_HEIGHT,
+    map_idx_to_point2,
+};
+
+/// A struct representing a Spawner, which enemies come out of. Spawners take
+/// the place of a Tile::Wall, and can be attacked by the Player and by 
+/// Towers. A Spawner which runs out of health is destroyed, and its Tile 
+/// reverts to a Tile::Wall.
+pub struct Spawner {
+    pub idx: MapIdx,
+    pub shape: GenericShape,
+    pub health: Health,
+    pub health_bar: HealthBar,
+    pub last_swing_hit: u32,
+}
+
+impl Spawner {
+
+    /// Creates a new Spawner at pos with the input amount of health.
+    pub fn new(pos: MapIdx, health: i32) -> Self {
+        let position = map_idx_to_point2(pos);
+        Self {
+            idx: pos,
+            shape: GenericShape::new(
+                ShapeVariant::Rect{width: TILE_SIZE, height: TILE_SIZE},
+                SPAWNER_COLOR,
+                position
+            ),
+            health: Health::new(health),
+            health_bar: HealthBar::new(
+                position - Point2{x: 0.0, y: HEALTH_BAR_HEIGHT * 2.0},
+                TILE_SIZE
+            ),
+            last_swing_hit: 0,
+        }
+    }
+
+    /// Damages the Spawner, and updates the HealthBar to match. Returns true
+    /// if the Spawner was destroyed.
+    pub fn hit(&mut self, damage: i32) -> bool {
+        let outcome = self.health.damage(damage);
+        self.health_bar.set_fraction(self.health.fraction());
+        outcome == DamageOutcome::Killed
+    }
+
+}
@@ -6,15 +6,94 @@ use crate::game::consts::{
     FLOOR_COLOR,
     WALL_COLOR,
     SPAWNER_COLOR,
-    ERROR_COLOR,
+    MUD_COLOR,
+    WATER_COLOR,
+    RUBBLE_COLOR,
+    DESTRUCTIBLE_WALL_COLOR,
+    DOOR_CLOSED_COLOR,
+    DOOR_OPEN_COLOR,
+    SPIKE_TRAP_COLOR,
+    SLOW_TRAP_COLOR,
+    MUD_SPEED,
+    WATER_SPEED,
+    RUBBLE_SPEED,
+    SLOW_TRAP_SPEED,
+    PATH_STEP_COST,
+    PATH_BREACH_COST,
     map_idx_to_point2,
 };
 
+/// Mud, Water and Rubble are terrain which can be crossed, but slow down 
+/// anything moving across them.
+/// 
+/// A DestructibleWall blocks movement like a Wall, but Enemies with no other
+/// way through will attack it until it runs out of health and collapses into
+/// Rubble. A closed Door blocks movement and pathing, while an open Door can
+/// be crossed like Floor. The Player can open and close Doors, and build
+/// traps on the Floor: a SpikeTrap damages Enemies standing on it whenever
+/// its cooldown runs out, and a SlowTrap slows down anything crossing it. 
+/// Enemies don't know about traps, so they don't avoid them.
 pub enum TileVariant {
     Floor,
     Wall,
     Spawner,
-    Cust(i32),
+    Mud,
+    Water,
+    Rubble,
+    DestructibleWall { health: i32 },
+    Door { open: bool },
+    SpikeTrap { cooldown: f64 },
+    SlowTrap,
+}
+
+impl TileVariant {
+    /// Returns true if the variant blocks movement.
+    pub fn is_solid(&self) -> bool {
+        matches!(self, TileVariant::Wall | TileVariant::DestructibleWall{..} | TileVariant::Door{open: false})
+    }
+
+    /// Returns the fraction of their full speed that the Player and Enemies
+    /// move at while on the variant.
+    pub fn speed(&self) -> f64 {
+        match self {
+            TileVariant::Mud => MUD_SPEED,
+            TileVariant::Water => WATER_SPEED,
+            TileVariant::Rubble => RUBBLE_SPEED,
+            TileVariant::SlowTrap => SLOW_TRAP_SPEED,
+            _ => 1.0,
+        }
+    }
+
+    /// Returns the cost of stepping onto the variant when pathfinding, or 
+    /// None if it can't be walked on. Slower terrain costs more to cross, 
+    /// while traps cost the same as Floor. When breaching, DestructibleWalls
+    /// can be pathed through at a high cost.
+    pub fn cost(&self, breaching: bool) -> Option<u32> {
+        match self {
+            TileVariant::Wall | TileVariant::Door{open: false} => None,
+            TileVariant::DestructibleWall{..} if breaching => Some(PATH_BREACH_COST),
+            TileVariant::DestructibleWall{..} => None,
+            TileVariant::SpikeTrap{..} | TileVariant::SlowTrap => Some(PATH_STEP_COST),
+            _ => Some((PATH_STEP_COST as f64 / self.speed()).round() as u32),
+        }
+    }
+}
+
+/// The traps the Player can build on the Floor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrapKind {
+    Spike,
+    Slow,
+}
+
+impl TrapKind {
+    /// Returns the TileVariant of a newly built trap.
+    pub fn variant(self) -> TileVariant {
+        match self {
+            TrapKind::Spike => TileVariant::SpikeTrap{cooldown: 0.0},
+            TrapKind::Slow => TileVariant::SlowTrap,
+        }
+    }
 }
 
 pub struct Tile {
@@ -24,60 +103,29 @@ pub struct Tile {
 
 impl Tile {
     pub fn new(variant: TileVariant, idx: MapIdx) -> Self {
-        match variant {
-            TileVariant::Floor => {
-                Self {
-                    variant: variant,
-                    shape: GenericShape::new(
-                        ShapeVariant::Rect{
-                            width: TILE_SIZE,
-                            height: TILE_SIZE
-                        },
-                        FLOOR_COLOR, 
-                        map_idx_to_point2(idx)
-                    ),
-                }
-            },
-            TileVariant::Wall => {
-                Self {
-                    variant: variant,
-                    shape: GenericShape::new(
-                        ShapeVariant::Rect{
-                            width: TILE_SIZE,
-                            height: TILE_SIZE
-                        },
-                        WALL_COLOR, 
-                        map_idx_to_point2(idx)
-                    ),
-                }
-            },
-            TileVariant::Spawner => {
-                Self {
-                    variant: variant,
-                    shape: GenericShape::new(
-                        ShapeVariant::Rect{
-                            width: TILE_SIZE,
-                            height: TILE_SIZE
-                        },
-                        SPAWNER_COLOR, 
-                        map_idx_to_point2(idx)
-                    ),
-                }
-            },
-            TileVariant::Cust(_) => {
-                Self {
-                    variant: variant,
-                    shape: GenericShape::new(
-                        ShapeVariant::Rect{
-                            width: TILE_SIZE,
-                            height: TILE_SIZE
-                        },
-                        ERROR_COLOR, 
-                        map_idx_to_point2(idx)
-                    ),
-                }
-            }
+        let color = match variant {
+            TileVariant::Floor => FLOOR_COLOR,
+            TileVariant::Wall => WALL_COLOR,
+            TileVariant::Spawner => SPAWNER_COLOR,
+            TileVariant::Mud => MUD_COLOR,
+            TileVariant::Water => WATER_COLOR,
+            TileVariant::Rubble => RUBBLE_COLOR,
+            TileVariant::DestructibleWall{..} => DESTRUCTIBLE_WALL_COLOR,
+            TileVariant::Door{open: false} => DOOR_CLOSED_COLOR,
+            TileVariant::Door{open: true} => DOOR_OPEN_COLOR,
+            TileVariant::SpikeTrap{..} => SPIKE_TRAP_COLOR,
+            TileVariant::SlowTrap => SLOW_TRAP_COLOR,
+        };
+        Self {
+            variant,
+            shape: GenericShape::new(
+                ShapeVariant::Rect{
+                    width: TILE_SIZE,
+                    height: TILE_SIZE
+                },
+                color, 
+                map_idx_to_point2(idx)
+            ),
         }
-        
     }
-}
\ No newline at end of file
+}
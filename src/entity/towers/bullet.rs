@@ -2,16 +2,19 @@ use crate::traits::draw::{GenericShape, ShapeVariant};
 use crate::traits::entity::Entity;
 use crate::math::Point2;
 use crate::math::Vec2;
+use crate::ecs::{World, EntityId, Velocity, Projectile};
 use crate::game::consts::{
     BULLET_WIDTH,
     BULLET_HEIGHT,
     BULLET_COLOR,
-    BULLET_SPEED
+    BULLET_SPEED,
+    BULLET_DAMAGE,
 };
 
-/// A structure to represent a bullet fired from a tower.
+/// A structure to represent a bullet fired from a tower or the Player.
 pub struct Bullet {
     pub shape: GenericShape,
+    pub damage: i32,
     direction: Vec2,
 }
 
@@ -27,10 +30,33 @@ impl Bullet {
                 BULLET_COLOR, 
                 position,
             ),
+            damage: BULLET_DAMAGE,
             direction: direction,
         }
     }
 
+    /// Returns a new Bullet centered on the input position, travelling and 
+    /// rotated in the direction of rotation (in radians).
+    pub fn aimed(position: Point2, rotation: f64) -> Self {
+        let mut bullet = Bullet::new(position, Vec2::new(rotation.cos(), rotation.sin()));
+        bullet.shape.set_offset(Point2{
+            x: 0.0,
+            y: -BULLET_HEIGHT / 2.0
+        });
+        bullet.shape.set_rotation(rotation);
+        bullet
+    }
+
+}
+
+/// Creates a projectile in the world from the Bullet, which carries on in the
+/// same direction.
+pub fn spawn_projectile(world: &mut World, bullet: Bullet) -> EntityId {
+    let id = world.spawn();
+    world.shapes.insert(id, bullet.shape);
+    world.velocities.insert(id, Velocity::new(bullet.direction, BULLET_SPEED));
+    world.projectiles.insert(id, Projectile{damage: bullet.damage});
+    id
 }
 
 impl Entity for Bullet {
@@ -4,6 +4,7 @@ use crate::traits::draw::{Draw, Context, Graphics, GenericShape, ShapeVariant};
 use crate::traits::state::State;
 use crate::traits::entity::Entity;
 use crate::entity::towers::bullet::Bullet;
+use crate::ecs::{World, EntityId};
 use crate::game::consts::{
     TOWER_COLOR,
     TOWER_SIZE,
@@ -12,7 +13,6 @@ use crate::game::consts::{
     TOWER_CANNON_HEIGHT,
     TOWER_CANNON_COLOR,
     TOWER_RANGE,
-    BULLET_HEIGHT,
 };
 
 /// Enumeration describing the states of the Tower. While Ready, if an Enemy is
@@ -23,7 +23,8 @@ pub enum TowerState {
     Attacking,
 }
 
-/// A struct describing the towers in the game. 
+/// A component describing the towers in the game. A Tower keeps its own 
+/// shapes, and fires one Bullet at a time.
 pub struct Tower {
     pub base_shape: GenericShape,
     pub cannon_shape: GenericShape,
@@ -76,6 +77,12 @@ impl Tower {
     }
 }
 
+/// Creates a new Tower in the world at position.
+pub fn spawn_tower(world: &mut World, position: Point2) -> EntityId {
+    let id = world.spawn();
+    world.towers.insert(id, Tower::new(position));
+    id
+}
 
 impl Draw for Tower {
     
@@ -102,15 +109,7 @@ impl State for Tower {
         match new_state {
             TowerState::Attacking => {
                 if let Some(rot) = self.cannon_shape.get_rotation() {
-                    self.bullet = Bullet::new(
-                        self.base_shape.center_point(),
-                        Vec2::new(rot.cos(), rot.sin())
-                    );
-                    self.bullet.shape.set_offset(Point2{
-                        x: 0.0,
-                        y: -BULLET_HEIGHT / 2.0
-                    });
-                    self.bullet.shape.set_rotation(rot);
+                    self.bullet = Bullet::aimed(self.base_shape.center_point(), rot);
                 }
                 
             },
@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use piston::input::{Button, Key, MouseButton};
+
+/// Something the Player can do. Each Action is triggered by whichever
+/// Bindings it has, see Bindings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Attack,
+    Fire,
+    Dash,
+    Shield,
+    Recall,
+    UpgradeAura,
+    UpgradeRegeneration,
+    UpgradeShield,
+    UpgradePulse,
+    Build,
+    CycleTower,
+    ToggleDoor,
+    BuildSpikeTrap,
+    BuildSlowTrap,
+    Pause,
+    Rebind,
+}
+
+impl Action {
+
+    /// Every Action, in the order they're written to a bindings file.
+    pub const ALL: [Action; 20] = [
+        Action::MoveForward,
+        Action::MoveBack,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Attack,
+        Action::Fire,
+        Action::Dash,
+        Action::Shield,
+        Action::Recall,
+        Action::UpgradeAura,
+        Action::UpgradeRegeneration,
+        Action::UpgradeShield,
+        Action::UpgradePulse,
+        Action::Build,
+        Action::CycleTower,
+        Action::ToggleDoor,
+        Action::BuildSpikeTrap,
+        Action::BuildSlowTrap,
+        Action::Pause,
+        Action::Rebind,
+    ];
+
+    /// Returns the name of the Action in a bindings file, such as
+    /// "move_forward".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveForward => "move_forward",
+            Action::MoveBack => "move_back",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Attack => "attack",
+            Action::Fire => "fire",
+            Action::Dash => "dash",
+            Action::Shield => "shield",
+            Action::Recall => "recall",
+            Action::UpgradeAura => "upgrade_aura",
+            Action::UpgradeRegeneration => "upgrade_regeneration",
+            Action::UpgradeShield => "upgrade_shield",
+            Action::UpgradePulse => "upgrade_pulse",
+            Action::Build => "build",
+            Action::CycleTower => "cycle_tower",
+            Action::ToggleDoor => "toggle_door",
+            Action::BuildSpikeTrap => "build_spike_trap",
+            Action::BuildSlowTrap => "build_slow_trap",
+            Action::Pause => "pause",
+            Action::Rebind => "rebind",
+        }
+    }
+
+    /// Parses an Action from its name in a bindings file.
+    pub fn parse(name: &str) -> Option<Self> {
+        Action::ALL.iter().copied().find(|action| action.name() == name)
+    }
+
+}
+
+/// A key or mouse button which triggers an Action. Gamepads can't be bound,
+/// since the GlutinWindow the game opens never sends piston's controller 
+/// events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+
+    /// Returns the Binding for a pressed or released Button, or None for a
+    /// gamepad's buttons and hat.
+    pub fn from_button(button: Button) -> Option<Self> {
+        match button {
+            Button::Keyboard(key) => Some(Binding::Key(key)),
+            Button::Mouse(button) => Some(Binding::Mouse(button)),
+            Button::Controller(_) | Button::Hat(_) => None,
+        }
+    }
+
+    /// Parses a Binding from its name in a bindings file, ignoring case. Keys
+    /// are named as they are in piston ("W", "Space", "LShift", "D1"), mouse
+    /// buttons are "MouseLeft", "MouseRight", "MouseMiddle" and so on.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if let Some(button) = MOUSE_BUTTONS.iter().find(|button| Binding::Mouse(**button).to_string().to_ascii_lowercase() == name) {
+            return Some(Binding::Mouse(*button));
+        }
+        // Every Key has an SDL key code, either an ASCII character or one of
+        // the codes from 0x40000039.
+        (0..0x80).chain(0x4000_0039..0x4000_011B)
+            .map(Key::from)
+            .find(|key| *key != Key::Unknown && format!("{:?}", key).to_ascii_lowercase() == name)
+            .map(Binding::Key)
+    }
+
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::Mouse(button) => write!(f, "Mouse{:?}", button),
+        }
+    }
+}
+
+/// The mouse buttons which can be bound.
+const MOUSE_BUTTONS: [MouseButton; 8] = [
+    MouseButton::Left,
+    MouseButton::Right,
+    MouseButton::Middle,
+    MouseButton::X1,
+    MouseButton::X2,
+    MouseButton::Button6,
+    MouseButton::Button7,
+    MouseButton::Button8,
+];
+
+/// The Bindings of each Action. An Action can have any number of Bindings,
+/// and is pressed while any of them are.
+///
+/// # Bindings Files
+/// Bindings are saved in and loaded from a text file with a line for each
+/// Action, naming the Action and then its Bindings separated by commas:
+///
+/// ```text
+/// # Lines starting with a # are ignored
+/// move_forward = W, Up, I
+/// fire = MouseLeft, MouseRight
+/// pause =
+/// ```
+///
+/// Any Action left out of the file keeps its default Bindings, and an Action
+/// with nothing after the = is unbound. See Action::name() and
+/// Binding::parse() for the names of Actions and Bindings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bindings {
+    bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Bindings {
+
+    /// Loads Bindings from a bindings file, on top of the defaults. Returns
+    /// an InvalidData error naming the line which couldn't be parsed.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Bindings::parse(&contents).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    /// Saves the Bindings to a bindings file which Bindings::load() can read.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Parses the contents of a bindings file on top of the default
+    /// Bindings. Returns a description of the first line which couldn't be
+    /// parsed.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut bindings = Bindings::default();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |reason: String| format!("line {}: {}", number + 1, reason);
+            let (name, list) = match line.split_once('=') {
+                Some((name, list)) => (name.trim(), list.trim()),
+                None => return Err(error(format!("expected <action> = <bindings>, found \"{}\"", line))),
+            };
+            let action = Action::parse(name).ok_or_else(|| error(format!("unknown action \"{}\"", name)))?;
+            let mut parsed = Vec::new();
+            for name in list.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                parsed.push(Binding::parse(name).ok_or_else(|| error(format!("unknown binding \"{}\"", name)))?);
+            }
+            bindings.bindings.insert(action, parsed);
+        }
+        Ok(bindings)
+    }
+
+    /// Returns the Bindings of the Action.
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    /// Returns the names of the Action's Bindings separated by commas, as 
+    /// they're written in a bindings file.
+    pub fn names(&self, action: Action) -> String {
+        let names: Vec<String> = self.get(action).iter().map(|binding| binding.to_string()).collect();
+        names.join(", ")
+    }
+
+    /// Returns true if any of the Action's Bindings are among the pressed
+    /// Bindings.
+    pub fn is_pressed<'a, I: IntoIterator<Item = &'a Binding>>(&self, action: Action, pressed: I) -> bool {
+        pressed.into_iter().any(|binding| self.get(action).contains(binding))
+    }
+
+    /// Adds a Binding to the Action, keeping its other Bindings.
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes a Binding from every Action it's bound to.
+    pub fn unbind(&mut self, binding: Binding) {
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|other| *other != binding);
+        }
+    }
+
+    /// Replaces the Bindings of the Action with the input Binding, taking it
+    /// from any other Action it was bound to. This is how an Action is 
+    /// rebound in game, see GameController.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        self.unbind(binding);
+        self.bindings.insert(action, vec![binding]);
+    }
+
+}
+
+impl Default for Bindings {
+    /// Returns the default Bindings for a keyboard and mouse. See 
+    /// GameController for what each Action does.
+    fn default() -> Self {
+        use self::Binding::{Key as K, Mouse};
+        let bindings = vec![
+            (Action::MoveForward, vec![K(Key::W), K(Key::Up)]),
+            (Action::MoveBack, vec![K(Key::S), K(Key::Down)]),
+            (Action::MoveLeft, vec![K(Key::A), K(Key::Left)]),
+            (Action::MoveRight, vec![K(Key::D), K(Key::Right)]),
+            (Action::Attack, vec![K(Key::Space)]),
+            (Action::Fire, vec![Mouse(MouseButton::Left)]),
+            (Action::Dash, vec![K(Key::LShift)]),
+            (Action::Shield, vec![K(Key::Q)]),
+            (Action::Recall, vec![K(Key::R)]),
+            (Action::UpgradeAura, vec![K(Key::D1)]),
+            (Action::UpgradeRegeneration, vec![K(Key::D2)]),
+            (Action::UpgradeShield, vec![K(Key::D3)]),
+            (Action::UpgradePulse, vec![K(Key::D4)]),
+            (Action::Build, vec![K(Key::E)]),
+            (Action::CycleTower, vec![K(Key::C)]),
+            (Action::ToggleDoor, vec![K(Key::F)]),
+            (Action::BuildSpikeTrap, vec![K(Key::Z)]),
+            (Action::BuildSlowTrap, vec![K(Key::X)]),
+            (Action::Pause, vec![K(Key::P)]),
+            (Action::Rebind, vec![K(Key::F1)]),
+        ].into_iter().collect();
+        Self { bindings }
+    }
+}
+
+impl fmt::Display for Bindings {
+    /// Formats the Bindings as a bindings file.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for action in Action::ALL.iter() {
+            writeln!(f, "{} = {}", action.name(), self.names(*action))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod bindings_tests {
+
+    use super::{Action, Binding, Bindings};
+    use piston::input::{Key, MouseButton};
+
+    #[test]
+    fn test_binding_names() {
+        let bindings = [
+            Binding::Key(Key::W),
+            Binding::Key(Key::LShift),
+            Binding::Key(Key::D1),
+            Binding::Key(Key::F12),
+            Binding::Mouse(MouseButton::Right),
+        ];
+        for binding in bindings.iter() {
+            assert_eq!(Some(*binding), Binding::parse(&binding.to_string()));
+        }
+        assert_eq!(Some(Binding::Key(Key::Space)), Binding::parse("space"));
+        assert_eq!(None, Binding::parse("NotAKey"));
+    }
+
+    #[test]
+    fn test_bindings_file() {
+        let defaults = Bindings::default();
+        assert_eq!(Ok(defaults.clone()), Bindings::parse(&defaults.to_string()));
+
+        let bindings = Bindings::parse("# Fire with the right mouse button\nfire = MouseRight, MouseMiddle\n\npause =").unwrap();
+        assert_eq!(&[Binding::Mouse(MouseButton::Right), Binding::Mouse(MouseButton::Middle)], bindings.get(Action::Fire));
+        assert!(bindings.get(Action::Pause).is_empty());
+        assert_eq!("MouseRight, MouseMiddle", bindings.names(Action::Fire));
+        assert_eq!(defaults.get(Action::Attack), bindings.get(Action::Attack));
+
+        assert!(Bindings::parse("fly = Space").unwrap_err().starts_with("line 1"));
+        assert!(Bindings::parse("\nattack = Spacebar").unwrap_err().starts_with("line 2"));
+    }
+
+    #[test]
+    fn test_rebind() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Attack, Binding::Key(Key::E));
+        assert_eq!(&[Binding::Key(Key::E)], bindings.get(Action::Attack));
+        assert!(!bindings.get(Action::Build).contains(&Binding::Key(Key::E)));
+
+        bindings.bind(Action::Attack, Binding::Key(Key::Space));
+        assert!(bindings.is_pressed(Action::Attack, &[Binding::Key(Key::Space)]));
+        assert!(!bindings.is_pressed(Action::Build, &[Binding::Key(Key::E)]));
+    }
+
+}
@@ -10,46 +10,140 @@ pub const MIN: f64 = f64::MIN;
 
 pub const WINDOW_WIDTH: f64 = 1000.0;
 pub const WINDOW_HEIGHT: f64 = 1000.0;
+pub const UPDATES_PER_SECOND: u64 = 120;
 pub const OPEN_GL_VERSION: OpenGL = OpenGL::V3_2;
+pub const BINDINGS_PATH: &str = "bindings.txt";
 
 pub const LEVEL_WIDTH: i32 = 50;
 pub const LEVEL_HEIGHT: i32 = 50;
 pub const LEVEL_GEN_ITERS: i32 = 5;
+pub const TERRAIN_PATCHES: u32 = 8;
+pub const TERRAIN_PATCH_SIZE: u32 = 14;
 
 pub const TILE_SIZE: f64 = 20.0;
 pub const FLOOR_COLOR: Color = [0.2, 0.13, 0.08, 1.0];
 pub const WALL_COLOR: Color = [0.3, 0.3, 0.2, 1.0];
 pub const SPAWNER_COLOR: Color = [0.4, 0.06, 0.0, 1.0];
+pub const MUD_COLOR: Color = [0.27, 0.2, 0.1, 1.0];
+pub const WATER_COLOR: Color = [0.1, 0.22, 0.35, 1.0];
+pub const RUBBLE_COLOR: Color = [0.25, 0.22, 0.18, 1.0];
+pub const DESTRUCTIBLE_WALL_COLOR: Color = [0.38, 0.33, 0.2, 1.0];
+pub const DOOR_CLOSED_COLOR: Color = [0.45, 0.28, 0.1, 1.0];
+pub const DOOR_OPEN_COLOR: Color = [0.3, 0.2, 0.1, 1.0];
+pub const SPIKE_TRAP_COLOR: Color = [0.45, 0.45, 0.45, 1.0];
+pub const SLOW_TRAP_COLOR: Color = [0.2, 0.35, 0.15, 1.0];
+pub const DESTRUCTIBLE_WALL_HEALTH: i32 = 10;
+pub const DOOR_RANGE: f64 = TILE_SIZE * 1.5;
+pub const TRAP_COST: i32 = 1;
+pub const SPIKE_TRAP_DAMAGE: i32 = 1;
+pub const SPIKE_TRAP_COOLDOWN: f64 = 1.0;
+pub const SLOW_TRAP_SPEED: f64 = 0.3;
+pub const PATH_BREACH_COST: u32 = 200;
+pub const MUD_SPEED: f64 = 0.5;
+pub const WATER_SPEED: f64 = 0.35;
+pub const RUBBLE_SPEED: f64 = 0.75;
+pub const PATH_STEP_COST: u32 = 10;
+pub const PATH_DIAGONAL_COST: u32 = 14;
+pub const SPAWNER_TOWER_DROPS: usize = 2;
+pub const SPAWNER_AMMO_DROPS: usize = 1;
 pub const ERROR_COLOR: Color = [1.0, 0.0, 0.0, 1.0];
+pub const FOG_COLOR: Color = [0.0, 0.0, 0.0, 1.0];
+pub const FOG_EXPLORED_COLOR: Color = [0.0, 0.0, 0.0, 0.6];
 
 pub const PLAYER_SIZE: f64 = 16.0;
 pub const PLAYER_RADIUS: f64 = PLAYER_SIZE/2.0;
+pub const PLAYER_VISION_RADIUS: i32 = 8;
 pub const PLAYER_STARTING_HEALTH: i32 = 10;
+pub const PLAYER_REGENERATION: f64 = 0.1;
+pub const PLAYER_INVULNERABILITY: f64 = 1.0;
+pub const PLAYER_FLASH_COLOR: Color = [1.0, 1.0, 1.0, 1.0];
 pub const PLAYER_SPEED: f64 = 7.5 * TILE_SIZE;
+pub const PLAYER_ACCELERATION: f64 = PLAYER_SPEED * 10.0;
+pub const PLAYER_FRICTION: f64 = PLAYER_SPEED * 8.0;
 pub const PLAYER_COLOR: Color = [0.75, 0.12, 0.08,1.0];
 
 pub const PLAYER_ATTACK_WIDTH: f64 = PLAYER_SIZE * 1.5;
 pub const PLAYER_ATTACK_HEIGHT: f64 = PLAYER_SIZE / 3.0;
 pub const PLAYER_ATTACK_COLOR: Color = [0.5, 0.5, 0.5 ,1.0]; 
+pub const PLAYER_STARTING_AMMO: i32 = 10;
+pub const PLAYER_FIRE_COOLDOWN: f64 = 0.3;
+pub const PLAYER_BULLET_DAMAGE: i32 = 1;
+
+pub const DASH_SPEED: f64 = PLAYER_SPEED * 4.0;
+pub const DASH_DURATION: f64 = 0.15;
+pub const DASH_COOLDOWN: f64 = 1.5;
+pub const SHIELD_DURATION: f64 = 4.0;
+pub const SHIELD_COOLDOWN: f64 = 12.0;
+pub const SHIELD_HITS: i32 = 3;
+pub const SHIELD_COLOR: Color = [0.3, 0.6, 1.0, 0.4];
+pub const RECALL_CHANNEL_TIME: f64 = 2.0;
+pub const RECALL_COOLDOWN: f64 = 20.0;
+pub const RECALL_COLOR: Color = [0.88, 0.68, 0.1, 0.4];
 
 pub const ENEMY_SIZE: f64 = 16.0;
 pub const ENEMY_RADIUS: f64 = ENEMY_SIZE/2.0;
 pub const ENEMY_COLOR: Color = [0.04, 0.13, 0.27, 1.0];
 pub const ENEMY_SPEED: f64 = 4.0 * TILE_SIZE;
+pub const ENEMY_STARTING_HEALTH: i32 = 3;
+pub const ENEMY_KNOCKBACK_DECAY: f64 = 8.0;
+pub const ENEMY_DAMAGE: i32 = 1;
+pub const ENEMY_CONTACT_KNOCKBACK: f64 = 300.0;
+pub const ENEMY_WALL_DAMAGE: i32 = 1;
+pub const ENEMY_WALL_ATTACK_INTERVAL: f64 = 0.5;
+pub const ENEMY_LOOKAHEAD: f64 = TILE_SIZE * 0.75;
+pub const ENEMY_PERSONAL_SPACE: f64 = ENEMY_RADIUS * 1.5;
+pub const ENEMY_SEPARATION_WEIGHT: f64 = 1.5;
+pub const ENEMY_AVOIDANCE_WEIGHT: f64 = 2.0;
+pub const ENEMY_FEELER_LENGTH: f64 = TILE_SIZE;
+pub const ENEMY_FEELER_ANGLE: f64 = PI / 6.0;
 
 pub const DROP_SIZE: f64 = TILE_SIZE / 2.0;
 pub const DROP_ROTATION_SPEED: f64 = -2.0 * PI;
 pub const RESOURCE_COLOR: Color = BEACON_COLOR;
+pub const AMMO_COLOR: Color = [0.8, 0.8, 0.8, 1.0];
+pub const AMMO_PER_PICKUP: i32 = 5;
 
 pub const BEACON_SIZE: f64 = 18.0;
 pub const BEACON_COLOR: Color = [0.88, 0.68, 0.1, 1.0];
 pub const BEACON_ROTATION_SPEED: f64 = 2.0 * PI;
 pub const BEACON_STARTING_HEALTH: i32 = 10;
+pub const BEACON_INVULNERABILITY: f64 = 0.25;
+pub const BEACON_FLASH_COLOR: Color = [1.0, 1.0, 1.0, 1.0];
+pub const BEACON_FALLEN_COLOR: Color = [0.25, 0.25, 0.25, 1.0];
+pub const BEACON_SEPARATION: i32 = 10;
+pub const BEACON_ESCORT_RANGE: f64 = TILE_SIZE * 4.0;
+pub const BEACON_ESCORT_SPEED: f64 = 1.5 * TILE_SIZE;
+pub const ESCORT_GOAL_COLOR: Color = [0.88, 0.68, 0.1, 0.5];
+pub const BEACON_UPGRADE_MAX_LEVEL: u32 = 3;
+pub const BEACON_UPGRADE_RANGE: f64 = TILE_SIZE * 3.0;
+pub const BEACON_AURA_RADIUS: f64 = TILE_SIZE * 3.0;
+pub const BEACON_AURA_HEAL_INTERVAL: f64 = 2.0;
+pub const BEACON_AURA_COLOR: Color = [0.3, 0.9, 0.3, 0.15];
+pub const BEACON_REGENERATION: f64 = 0.5;
+pub const BEACON_SHIELD_HITS: i32 = 2;
+pub const BEACON_SHIELD_RECHARGE: f64 = 5.0;
+pub const BEACON_SHIELD_COLOR: Color = SHIELD_COLOR;
+pub const BEACON_PULSE_RADIUS: f64 = TILE_SIZE * 2.5;
+pub const BEACON_PULSE_COOLDOWN: f64 = 6.0;
+pub const BEACON_PULSE_KNOCKBACK: f64 = 400.0;
+pub const BEACON_PULSE_FLASH: f64 = 0.2;
+pub const BEACON_PULSE_COLOR: Color = [0.88, 0.68, 0.1, 0.3];
+
+pub const KILL_SCORE: i64 = 10;
+pub const BEACON_HEALTH_SCORE: i64 = 25;
+pub const RESOURCE_SCORE: i64 = 5;
+pub const TIME_SCORE: i64 = 1;
+pub const SPAWNER_SCORE: i64 = 50;
+pub const WIN_SCORE: i64 = 1000;
+
+pub const WAVE_DURATION: f64 = 45.0;
+pub const WAVE_BREAK_DURATION: f64 = 15.0;
 
 pub const TOWER_COLOR: Color = [0.33, 0.33, 0.33, 1.0];
 pub const TOWER_SIZE: f64 = PLAYER_SIZE;
 pub const TOWER_RADIUS: f64 = TOWER_SIZE / 2.0;
 pub const TOWER_RANGE: f64 = TILE_SIZE * 4.0;
+pub const TOWER_VISION_RADIUS: i32 = 4;
 pub const TOWER_CANNON_COLOR: Color = PLAYER_ATTACK_COLOR;
 pub const TOWER_CANNON_WIDTH: f64 = TOWER_SIZE;
 pub const TOWER_CANNON_HEIGHT: f64 = TOWER_SIZE / 3.0;
@@ -57,10 +151,48 @@ pub const BULLET_WIDTH: f64 = TOWER_SIZE / 2.0;
 pub const BULLET_HEIGHT: f64 = TOWER_CANNON_HEIGHT * 0.75;
 pub const BULLET_COLOR: Color = [0.0, 0.0, 0.0, 1.0];
 pub const BULLET_SPEED: f64 = ENEMY_SPEED * 2.0;
+pub const BULLET_DAMAGE: i32 = ENEMY_STARTING_HEALTH;
 
 pub const HEALTH_BAR_HEIGHT: f64 = 5.0;
 pub const HEALTH_COLOR: Color = [0.0, 1.0, 0.0, 1.0];
 pub const DAMAGE_COLOR: Color = [1.0, 0.0, 0.0, 1.0];
+pub const HEALTH_FLASH_RATE: f64 = 10.0;
+
+pub const HUD_MARGIN: f64 = 10.0;
+pub const HUD_ICON_SIZE: f64 = 30.0;
+pub const HUD_BACKGROUND_COLOR: Color = [0.1, 0.1, 0.1, 0.8];
+pub const DASH_ICON_COLOR: Color = PLAYER_COLOR;
+pub const SHIELD_ICON_COLOR: Color = [0.3, 0.6, 1.0, 1.0];
+pub const RECALL_ICON_COLOR: Color = BEACON_COLOR;
+pub const MINIMAP_SIZE: f64 = 200.0;
+pub const MINIMAP_DOT_SIZE: f64 = 4.0;
+pub const MINIMAP_VIEW_COLOR: Color = [1.0, 1.0, 1.0, 0.8];
+pub const MINIMAP_ENEMY_COLOR: Color = [0.3, 0.6, 1.0, 1.0];
+pub const MINIMAP_SPAWNER_COLOR: Color = [0.9, 0.1, 0.0, 1.0];
+pub const PAUSED_OVERLAY_COLOR: Color = [0.0, 0.0, 0.0, 0.5];
+pub const PAUSE_ICON_COLOR: Color = [1.0, 1.0, 1.0, 0.9];
+pub const PAUSE_ICON_SIZE: f64 = 60.0;
+pub const REBIND_FONT_SIZE: u32 = 24;
+pub const REBIND_TEXT_COLOR: Color = [1.0, 1.0, 1.0, 1.0];
+
+pub const MAX_PARTICLES: usize = 512;
+pub const PARTICLE_DRAG: f64 = 4.0;
+pub const SPARK_COLOR: Color = [1.0, 0.9, 0.6, 1.0];
+pub const DUST_COLOR: Color = RUBBLE_COLOR;
+pub const HIT_FLASH_COLOR: Color = [1.0, 1.0, 1.0, 1.0];
+pub const HIT_FLASH_DURATION: f64 = 0.08;
+pub const SHAKE_MAX_OFFSET: f64 = 8.0;
+pub const SHAKE_DECAY: f64 = 1.5;
+pub const SHAKE_FREQUENCY: f64 = 30.0;
+pub const SHAKE_PLAYER_HIT: f64 = 0.4;
+pub const SHAKE_BEACON_HIT: f64 = 0.3;
+pub const SHAKE_BEACON_FALLEN: f64 = 0.8;
+pub const SHAKE_RUBBLE: f64 = 0.2;
+
+pub const SPRITE_SHEET_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/assets/sprites.toml");
+pub const FONT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/assets/FiraSans-Regular.ttf");
+pub const SPRITE_PHASE_STEP: f64 = 0.37;
+pub const LEVEL_CHUNK_SIZE: i32 = 16;
 
 pub fn map_idx_to_point2(idx: MapIdx) -> Point2 {
 
@@ -0,0 +1,70 @@
+/// How hard the game is. The Difficulty decides how often new spawners are
+/// created, how many spawners there can be, and how much health they have.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+
+    /// Parses a Difficulty from a command line argument. Valid arguments are
+    /// "easy", "normal" and "hard".
+    pub fn parse(arg: &str) -> Option<Self> {
+        match arg {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// Returns the average number of seconds between new spawners being
+    /// created during the input wave. Spawners are created more often as the
+    /// waves go on.
+    pub fn spawner_interval(&self, wave: u32) -> f64 {
+        let interval = match self {
+            Difficulty::Easy => 40.0,
+            Difficulty::Normal => 25.0,
+            Difficulty::Hard => 15.0,
+        };
+        interval / (1.0 + wave.saturating_sub(1) as f64 * 0.25)
+    }
+
+    /// Returns the most spawners which can be in the Level at once.
+    pub fn max_spawners(&self) -> usize {
+        match self {
+            Difficulty::Easy => 4,
+            Difficulty::Normal => 6,
+            Difficulty::Hard => 10,
+        }
+    }
+
+    /// Returns the health each spawner starts with.
+    pub fn spawner_health(&self) -> i32 {
+        match self {
+            Difficulty::Easy => 6,
+            Difficulty::Normal => 10,
+            Difficulty::Hard => 15,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod difficulty_tests {
+
+    use super::Difficulty;
+
+    #[test]
+    fn test_spawner_interval_shrinks() {
+        let difficulty = Difficulty::Normal;
+        assert!(difficulty.spawner_interval(5) < difficulty.spawner_interval(1));
+        assert!(Difficulty::Hard.spawner_interval(1) < Difficulty::Easy.spawner_interval(1));
+        assert_eq!(Some(Difficulty::Hard), Difficulty::parse("hard"));
+        assert_eq!(None, Difficulty::parse("impossible"));
+    }
+
+}
@@ -0,0 +1,37 @@
+use crate::ecs::EntityId;
+use crate::entity::drops::ResourceVariant;
+use crate::entity::health::DamageOutcome;
+use crate::entity::tile::TrapKind;
+use crate::game::Outcome;
+use crate::levels::map::MapIdx;
+use crate::math::Point2;
+
+/// Something which happened during a tick of the game. The GameModel collects
+/// the events of each tick in its events queue, which GameController::tick()
+/// clears at the start of every tick and returns at the end, so anything
+/// which wants to react to the game (the view's effects and sounds,
+/// statistics, replays, tests) can do so without the controller knowing
+/// about it.
+///
+/// Beacons are identified by their index in the GameModel's Beacons, and
+/// spawners by the index of their Tile, since destroying a spawner shifts the
+/// rest along.
+#[derive(Clone, Copy, Debug)]
+pub enum GameEvent {
+    EnemySpawned { id: EntityId, position: Point2 },
+    EnemyHit { id: EntityId, position: Point2 },
+    EnemyKilled { id: EntityId, position: Point2 },
+    BeaconDamaged { beacon: usize, outcome: DamageOutcome },
+    BeaconFallen { beacon: usize },
+    PlayerDamaged { outcome: DamageOutcome },
+    ResourceDropped { id: EntityId, variant: ResourceVariant },
+    ResourceCollected { variant: ResourceVariant },
+    ProjectileFired { id: EntityId },
+    TowerBuilt { id: EntityId, position: Point2 },
+    TrapBuilt { idx: MapIdx, kind: TrapKind },
+    SpawnerCreated { idx: MapIdx },
+    SpawnerDestroyed { idx: MapIdx },
+    WallCollapsed { idx: MapIdx },
+    DoorToggled { idx: MapIdx, open: bool },
+    GameOver { outcome: Outcome },
+}
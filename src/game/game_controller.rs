@@ -1,82 +1,156 @@
-use crate::game::{GameModel, GameView};
+use crate::game::{Action, Binding, Bindings, GameModel, GameView, GameResults, GameEvent, Rules};
+use crate::levels::Level;
 use crate::math::random::Seed;
-use crate::math::Point2;
+use crate::math::{Point2, Vec2};
 use crate::traits::entity::Entity;
 use crate::traits::state::State;
-use crate::traits::draw::check_collision;
-use crate::entity::player;
-use crate::entity::tile::TileVariant;
-use crate::entity::towers::tower::TowerState;
-use crate::levels::map::MapIdx;
+use crate::traits::draw::{check_collision, contact};
+use crate::entity::player::{self, MovementMode};
+use crate::entity::drops::ResourceVariant;
+use crate::entity::abilities::AbilityKind;
+use crate::entity::beacon::BeaconUpgrade;
+use crate::entity::tile::TrapKind;
+use crate::entity::enemy::hit_enemy;
+use crate::entity::health::DamageOutcome;
+use crate::ecs::{EntityId, systems};
+use crate::ecs::systems::Hit;
+use crate::levels::map::{MapIdx, speed_at, sweep_circle};
 use crate::game::consts::{
-    point2_to_map_idx,
-    map_idx_to_point2,
-    TILE_SIZE,
-    PLAYER_SIZE,
+    PLAYER_RADIUS,
+    AMMO_PER_PICKUP,
+    ENEMY_DAMAGE,
+    ENEMY_CONTACT_KNOCKBACK,
 };
 
 use std::collections::HashSet;
+use std::path::PathBuf;
 
-use piston::input::{GenericEvent, Button, Key};
+use piston::input::{GenericEvent, Button, MouseButton};
 
 /// An enumeration describeing the different states for the Game. Running 
-/// implies that both the GameModel Player and the GameModel Beacon have
-/// health remaining. Finished implies that either the Beacon or the Player
-/// have run out of health, and so the game is over.
+/// implies that the game hasn't been won or lost yet. Paused is the same, 
+/// except that the game isn't ticked until it's unpaused. Finished implies 
+/// that either the Objective has been completed, or the Player has run out of 
+/// health or too many Beacons have fallen, and so the game is over.
 pub enum GameState {
     Running,
+    Paused,
     Finished,
 }
 
 /// A struct to control the game processes including user input, graphics and
-/// game ticks.
+/// game ticks. Once the game is Finished, the results of the game are kept
+/// in results.
 /// 
 /// # Input Handling
-/// The position of the cursor is updated every time it is updated. 
-/// Every key pressed is tracked in the keys_pressed list, and whenever a 
-/// key is released, the key is removed from the key pressed list. 
+/// The position of the cursor is updated every time it is updated. Every key
+/// and mouse button pressed is tracked in the inputs_pressed list, and
+/// whenever one is released, it's removed from the list. An Action is pressed
+/// while any of its Bindings are pressed. Actions which happen once per press
+/// are locked until all of their Bindings are released. The Bindings can be
+/// loaded from a bindings file, see Bindings, and the defaults are given
+/// below. Gamepads aren't supported, see Binding.
 /// 
 /// ## Cursor Position
-/// This input is used to control which direction the Player is facing.
+/// This input is used to control which direction the Player is facing. The
+/// cursor is on the screen, so it's moved into the Level by the Camera.
 /// 
-/// ## W Key
-/// This input is used to start and stop player movement. So long as this 
-/// key is pressed, and the Space Bar isn't pressed, the player will move
-/// towards the direction it's facing (towards the mouse). 
+/// ## MoveForward, MoveBack, MoveLeft and MoveRight
+/// W/Up, S/Down, A/Left and D/Right. These Actions move the Player. In the
+/// Strafe MovementMode, they move the Player up, down, left and right, and
+/// can be combined to move diagonally regardless of where the Player is
+/// aiming. In the CursorFollow MovementMode, holding MoveForward moves the
+/// Player towards the mouse. So long as the Player isn't attacking, the
+/// Player accelerates in the chosen direction, and slows to a stop once the
+/// Actions are released.
 /// 
-/// ## Space Bar
-/// This input is used to control the player attack animation. Pressing 
-/// space will start the animation. This will prevent all player movement 
-/// until the space bar is released. The animation will draw a box in the
-/// center of the Player towards the mouse. 
+/// ## Attack
+/// The Space Bar. Pressing Attack starts a Swing of the Player's Weapon
+/// towards the mouse, and pressing it again before the Swing has recovered
+/// chains into the next Swing of the Weapon's combo. The Player can't move
+/// until the combo has finished.
+/// 
+/// ## Fire
+/// The Left Mouse Button. This Action fires the Player's ranged weapon
+/// towards the mouse. Holding it keeps firing so long as the Player has ammo.
+/// Ammo is replenished by collecting Ammo resources. Clicking on the minimap
+/// with the Left Mouse Button pans the Camera to that part of the Level
+/// instead, until the Player moves.
+/// 
+/// ## Dash, Shield and Recall
+/// Left Shift, Q and R. These Actions use the Player's Abilities. Dash moves
+/// quickly in the direction the Player is moving, Shield raises a Shield, and
+/// Recall starts channelling a Recall back to the Beacon. Each Ability has a
+/// cooldown which is shown in the HUD.
+/// 
+/// ## UpgradeAura, UpgradeRegeneration, UpgradeShield and UpgradePulse
+/// The 1, 2, 3 and 4 Keys. These Actions spend resources on upgrading a
+/// Beacon while the Player is standing near it. UpgradeAura upgrades the
+/// healing Aura, UpgradeRegeneration the Beacon's Regeneration between waves,
+/// UpgradeShield the Beacon's Shield and UpgradePulse the damaging Pulse.
+/// 
+/// ## Build, ToggleDoor, BuildSpikeTrap and BuildSlowTrap
+/// E, F, Z and X. Build builds a Tower where the Player is standing, and
+/// ToggleDoor opens or closes the nearest Door. BuildSpikeTrap builds a Spike
+/// Trap and BuildSlowTrap builds a Slow Trap on the Floor under the Player,
+/// each costing a resource.
+/// 
+/// ## CycleTower
+/// C. Chooses the next kind of Tower to Build. There's only one kind of 
+/// Tower so far, so this Action does nothing yet.
+/// 
+/// ## Pause
+/// P. Pauses the game, or carries on with it if it's already paused.
+/// 
+/// ## Rebind
+/// F1, only while the game is Paused. Each press chooses the next Action to
+/// rebind, in the order of Action::ALL, which the pause screen shows along
+/// with its Bindings. The next other input pressed replaces the chosen 
+/// Action's Bindings, see Bindings::rebind(), and the Bindings are saved to
+/// bindings_path. Since pressing Rebind moves on to the next Action, its 
+/// Bindings can't be bound to anything else. Rebind is the last Action, and
+/// while it's chosen any input is bound to it, including its own, which 
+/// leaves it as it was. Unpausing stops rebinding.
 pub struct GameController {
     pub model: GameModel,
     pub view: GameView,
     pub state: GameState,
+    pub results: Option<GameResults>,
+    pub bindings: Bindings,
+    pub bindings_path: Option<PathBuf>,
+    rebinding: Option<Action>,
     cursor_pos: Point2,
-    keys_pressed: HashSet<Key>,
-    keys_locked: HashSet<Key>,
+    inputs_pressed: HashSet<Binding>,
+    actions_locked: HashSet<Action>,
 }
 
 impl GameController {
     
-    /// Creates a new GameController. The GameModel will start with 2 spawning
-    /// spaces created. 
+    /// Creates a new GameController on a generated Level, played by the 
+    /// default Rules. The GameModel will start with 2 spawning spaces created.
     pub fn new(seed: Seed) -> Option<Self> {
+        GameController::with_rules(Level::new(seed), seed, Rules::default())
+    }
+
+    /// Creates a new GameController on the input Level, played by the input
+    /// Rules. See GameModel::with_rules().
+    pub fn with_rules(level: Level, seed: Seed, rules: Rules) -> Option<Self> {
         
         let view = GameView::new();
-        if let Some(model) = GameModel::new(seed) {
+        if let Some(model) = GameModel::with_rules(level, seed, rules) {
             let cursor_pos = Point2 {x: 0.0, y: 0.0};
-            let keys_pressed = HashSet::new();
-            let keys_locked = HashSet::new();
 
             Some(Self {
                 model: model, 
                 view: view, 
                 state: GameState::Running, 
+                results: None,
+                bindings: Bindings::default(),
+                bindings_path: None,
+                rebinding: None,
                 cursor_pos: cursor_pos, 
-                keys_pressed: keys_pressed,
-                keys_locked: keys_locked,
+                inputs_pressed: HashSet::new(),
+                actions_locked: HashSet::new(),
             })
 
         } else {
@@ -85,254 +159,446 @@ impl GameController {
         
     }
 
-    /// Parses the event for cursor position, Keyboard presses and keyboard
-    /// relseases. 
+    /// Parses the event for cursor position, and Keyboard and Mouse presses
+    /// and releases. The game is only ticked while it's Running.
     pub fn handle_event<E: GenericEvent>(&mut self, e: &E) {
         if let Some(pos) = e.mouse_cursor_args() {
             self.cursor_pos = Point2 {x: pos[0], y: pos[1]};
             return;
         }
         if let Some(args) = e.update_args() {
-            self.tick(args.dt);
+            if let GameState::Running = self.state {
+                self.tick(args.dt);
+            }
             return;
         } 
-        if let Some(Button::Keyboard(key)) = e.press_args() {
-            self.keys_pressed.insert(key);
+        if let Some(button) = e.press_args() {
+            if button == Button::Mouse(MouseButton::Left) && self.view.pan_minimap(&self.model, self.cursor_pos) {
+                return;
+            }
+            if let Some(binding) = Binding::from_button(button) {
+                self.press(binding);
+            }
             return;
         }
-        if let Some(Button::Keyboard(key)) = e.release_args() {
-            if self.keys_pressed.contains(&key) {
-                self.keys_pressed.remove(&key);
-                self.keys_locked.remove(&key);
-                match key {
-                    Key::Space => {
-                        self.model.player.change_state(player::PlayerState::FinishedAttacking);
-                    },
-                    _ => (),
-                }
+        if let Some(button) = e.release_args() {
+            if let Some(binding) = Binding::from_button(button) {
+                self.release(binding);
             }
-            return;
         }
     }
 
-    /// Executes a single game tick 
-    pub fn tick(&mut self, dt: f64) {
-        self.model.player.update_direction(&self.cursor_pos);
-        // Update Movement state if W is pressed
-        if self.keys_pressed.contains(&Key::W) {
-            self.model.player.change_state(player::PlayerState::Moving);
-        } else {
-            self.model.player.change_state(player::PlayerState::Stationary);
+    /// Executes a single game tick, returning the GameEvents which happened
+    /// during it. The events are kept until the next tick, see events().
+    pub fn tick(&mut self, dt: f64) -> &[GameEvent] {
+        self.model.events.clear();
+        self.model.save_transforms();
+        let cursor = self.view.camera.to_world(self.cursor_pos);
+        self.model.player.update_direction(&cursor);
+        // Update Movement state from the movement keys
+        self.update_move_input();
+        // Start a swing, or queue the next swing of the combo, each time 
+        // Attack is pressed
+        if self.take_press(Action::Attack) {
+            self.model.player.attack();
         }
-        // Start animation if Space is pressed
-        if self.keys_pressed.contains(&Key::Space) {
-            self.model.player.change_state(player::PlayerState::Attacking);
+
+        // Fire the ranged weapon while Fire is held
+        if self.is_pressed(Action::Fire) {
+            self.model.fire_projectile();
         }
 
-        if self.keys_pressed.contains(&Key::E) && !self.keys_locked.contains(&Key::E){
+        // Use abilities once per press
+        for (action, kind) in [(Action::Dash, AbilityKind::Dash), (Action::Shield, AbilityKind::Shield), (Action::Recall, AbilityKind::Recall)].iter() {
+            if self.take_press(*action) {
+                self.model.player.use_ability(*kind);
+            }
+        }
+
+        // Upgrade the Beacon once per press
+        for (action, upgrade) in [(Action::UpgradeAura, BeaconUpgrade::Aura), (Action::UpgradeRegeneration, BeaconUpgrade::Regeneration), (Action::UpgradeShield, BeaconUpgrade::Shield), (Action::UpgradePulse, BeaconUpgrade::Pulse)].iter() {
+            if self.take_press(*action) {
+                self.model.upgrade_beacon(*upgrade);
+            }
+        }
+
+        if self.take_press(Action::Build) {
             self.model.create_tower();
-            self.keys_locked.insert(Key::E);
         }
-        self.model.tick_towers(dt);
-        self.check_bullet_collision();
+        if self.take_press(Action::ToggleDoor) {
+            self.model.toggle_door();
+        }
+        // Build traps once per press
+        for (action, kind) in [(Action::BuildSpikeTrap, TrapKind::Spike), (Action::BuildSlowTrap, TrapKind::Slow)].iter() {
+            if self.take_press(*action) {
+                self.model.build_trap(*kind);
+            }
+        }
         // Tick player
         self.model.player.tick(dt);
+        if self.model.player.take_recall() {
+            let beacon = self.model.nearest_beacon(self.model.player.shape.center_point()).map(|beacon| beacon.shape.center_point());
+            if let Some(beacon) = beacon {
+                self.model.player.teleport(beacon);
+            }
+        }
+        self.move_player(dt);
+        self.model.update_visibility();
         // Check for collision
-        self.check_player_collision();
-        // Tick Beacon
-        self.model.beacon.tick(dt);
-        self.tick_resources(dt);
+        self.check_spawner_hits();
+        self.check_resource_collision();
+        // Tick Beacons and their upgrades
+        self.model.tick_beacons(dt);
+        systems::spin(&mut self.model.world, dt);
         // Tick enemies and check for collision.
         self.tick_enemies(dt);
-
-        // Chreate spawner with constant chance
-        self.model.chanced_create_spawner(250);
+        // Enemies attack walls in their way, and traps hurt Enemies on them
+        self.model.tick_breaches(dt);
+        self.model.tick_traps(dt);
+        // Aim the Towers, then check what their bullets and the Player's 
+        // projectiles have hit
+        systems::target(&mut self.model.world, &self.model.enemy_grid, &self.model.level.map, &self.model.spawners, dt);
+        self.check_hits();
+        // Despawn everything destroyed this tick
+        self.model.world.maintain();
+
+        // Chreate spawner with a chance set by the Difficulty
+        self.model.chanced_create_spawner(dt);
         // Spawn enmies from spawners
         self.model.spawn_enemies();
-        
+        // Check Gamestate to see if the game has been won or lost.
+        self.check_objective();
+        // Start the effects of this tick's events
+        self.view.tick(dt, &self.model);
+
+        &self.model.events
     }
 
-    /// Checks the position of the player against the level walls. If the bounding
-    /// box of the player overlaps with a wall, the position of the player is 
-    /// corrected by the smallest move. Will take two game ticks to resolve corner
-    /// collisions, as the player is only every moved in one direction at a time. 
-    /// 
-    /// The player's position is approximated as a square despite actually being a
-    /// circle. This is only noticeable on corners. Can improve this to compare 
-    /// circle's to rectangles in the future.
-    fn check_player_collision(&mut self) {
+    /// Returns the GameEvents which happened during the last tick.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.model.events
+    }
 
-        let min_idx = point2_to_map_idx(self.model.player.shape.get_position());
-        let max_idx = point2_to_map_idx(self.model.player.shape.get_position() + Point2{x: PLAYER_SIZE, y: PLAYER_SIZE});
-        
-        for h in min_idx.y..max_idx.y+1 {
-            for w in min_idx.x..max_idx.x+1 {
-                if let Some(tile) = self.model.level.map.get(&MapIdx::new(w,h)) {
-                    match tile.variant {
-                        TileVariant::Wall => {
-                            let tile_pos = map_idx_to_point2(MapIdx::new(w, h));
-                            let shift_left = tile_pos.x - self.model.player.shape.get_position().x - PLAYER_SIZE - 0.1;
-                            let shift_right = tile_pos.x + TILE_SIZE - self.model.player.shape.get_position().x + 0.1;
-                            let shift_up = tile_pos.y - self.model.player.shape.get_position().y - PLAYER_SIZE - 0.1;
-                            let shift_down = tile_pos.y + TILE_SIZE - self.model.player.shape.get_position().y + 0.1;
-        
-                            let moves = [shift_left, shift_right, shift_up, shift_down];
-                            let mut min_move = moves[0];
-
-                            for i in 0..4 {
-                                if moves[i].abs() < min_move.abs() {
-                                    min_move = moves[i];
-                                }
-                            }
-
-                            if min_move == shift_left || min_move == shift_right {
-                                let delta = Point2{x: min_move, y: 0.0};
-                                self.model.player.shape.update(delta, None);
-                                self.model.player.health_bar.update(delta, None);
-                                self.model.player.damage_bar.update(delta, None);
-                            } else {
-                                let delta = Point2{x: 0.0, y: min_move};
-                                self.model.player.shape.update(delta, None);
-                                self.model.player.health_bar.update(delta, None);
-                                self.model.player.damage_bar.update(delta, None);
-                            }
-                        }
-                        _ => (),
-                    }
-                }
+    /// Pauses the game while it's Running, or carries on with it while it's
+    /// Paused. Unpausing stops any rebinding.
+    pub fn toggle_pause(&mut self) {
+        match self.state {
+            GameState::Running => self.change_state(GameState::Paused),
+            GameState::Paused => {
+                self.rebinding = None;
+                self.change_state(GameState::Running);
+            },
+            GameState::Finished => (),
+        }
+    }
+
+    /// Returns the Action the next input pressed will be bound to, if an 
+    /// Action is being rebound.
+    pub fn rebinding(&self) -> Option<Action> {
+        self.rebinding
+    }
+
+    /// Returns true while the game is Paused.
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, GameState::Paused)
+    }
+
+    /// Returns true if any of the Action's Bindings are currently pressed.
+    fn is_pressed(&self, action: Action) -> bool {
+        self.bindings.is_pressed(action, &self.inputs_pressed)
+    }
+
+    /// Returns true if the Action is pressed and hasn't been taken since it
+    /// was pressed, and locks it until it's released.
+    fn take_press(&mut self, action: Action) -> bool {
+        if self.is_pressed(action) && !self.actions_locked.contains(&action) {
+            self.actions_locked.insert(action);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tracks a pressed input. Pause and Rebind are handled straight away, 
+    /// since the game isn't ticked while it's Paused. While an Action is 
+    /// being rebound, the input is bound to it instead.
+    fn press(&mut self, binding: Binding) {
+        self.inputs_pressed.insert(binding);
+        let choosing = self.is_paused() && self.rebinding != Some(Action::Rebind);
+        if choosing && self.take_press(Action::Rebind) {
+            self.rebinding = match self.rebinding {
+                Some(action) => Action::ALL.iter().skip_while(|other| **other != action).nth(1).copied(),
+                None => Action::ALL.first().copied(),
+            };
+        } else if let Some(action) = self.rebinding.take() {
+            self.rebind(action, binding);
+        } else if self.take_press(Action::Pause) {
+            self.toggle_pause();
+        }
+    }
+
+    /// Replaces the Action's Bindings with the pressed input, and saves the
+    /// Bindings. The Action stays locked until the input is released, so it
+    /// doesn't go off as soon as the game is unpaused.
+    fn rebind(&mut self, action: Action, binding: Binding) {
+        self.bindings.rebind(action, binding);
+        self.actions_locked.insert(action);
+        if let Some(path) = &self.bindings_path {
+            if let Err(error) = self.bindings.save(path) {
+                println!("Couldn't save bindings to {}: {}", path.display(), error);
             }
         }
+    }
+
+    /// Stops tracking a released input, and unlocks any Actions which are no
+    /// longer pressed.
+    fn release(&mut self, binding: Binding) {
+        if self.inputs_pressed.remove(&binding) {
+            let (bindings, inputs) = (&self.bindings, &self.inputs_pressed);
+            self.actions_locked.retain(|action| bindings.is_pressed(*action, inputs));
+        }
+    }
+
+    /// Converts the pressed movement Actions into the Player's move input, and
+    /// updates the Player's state to match.
+    fn update_move_input(&mut self) {
+        let input = match self.model.player.movement_mode {
+            MovementMode::Strafe => {
+                let mut input = Vec2::new(0.0, 0.0);
+                if self.is_pressed(Action::MoveForward) {
+                    input.y -= 1.0;
+                }
+                if self.is_pressed(Action::MoveBack) {
+                    input.y += 1.0;
+                }
+                if self.is_pressed(Action::MoveLeft) {
+                    input.x -= 1.0;
+                }
+                if self.is_pressed(Action::MoveRight) {
+                    input.x += 1.0;
+                }
+                input
+            },
+            MovementMode::CursorFollow => {
+                if self.is_pressed(Action::MoveForward) {
+                    self.model.player.direction
+                } else {
+                    Vec2::new(0.0, 0.0)
+                }
+            },
+        };
+
+        self.model.player.set_move_input(input);
+        if input.length() > 0.0 {
+            self.model.player.change_state(player::PlayerState::Moving);
+        } else {
+            self.model.player.change_state(player::PlayerState::Stationary);
+        }
+    }
 
-        let mut to_remove: Vec<usize> = Vec::new();
-        for (i,resource) in self.model.resources.iter_mut().enumerate().rev() {
-            
-            if check_collision(resource.shape, self.model.player.shape) {
-                to_remove.push(i);
-                self.model.player.resources += 1;
+    /// Moves the Player by its velocity, slowed by the terrain under it. The
+    /// Player's circle is swept against the walls of the Level, so the Player
+    /// slides along walls, rounds corners and can't pass through walls, even
+    /// after a long frame. Any velocity into a wall is cancelled.
+    fn move_player(&mut self, dt: f64) {
+        let player = &mut self.model.player;
+        let speed = speed_at(&self.model.level.map, player.shape.center_point());
+        let delta = Point2{
+            x: player.velocity.x * speed * dt,
+            y: player.velocity.y * speed * dt,
+        };
+        let sweep = sweep_circle(&self.model.level.map, player.shape.center_point(), PLAYER_RADIUS, delta);
+        player.translate(sweep.delta);
+
+        if let Some(normal) = sweep.normal {
+            let into = Vec2::dot_product(player.velocity, normal);
+            if into > 0.0 {
+                player.velocity = Vec2::new(player.velocity.x - normal.x * into, player.velocity.y - normal.y * into);
             }
+        }
+    }
+
+    /// Checks the Player's active Swing against each spawner. Like enemies,
+    /// each Swing can only hit a spawner once.
+    fn check_spawner_hits(&mut self) {
 
+        let attack = &self.model.player.attack;
+        if !attack.is_active() {
+            return;
+        }
+        let (swing_id, damage) = (attack.swing_id, attack.swing().damage);
+        for i in (0..self.model.spawners.len()).rev() {
+            let spawner = &mut self.model.spawners[i];
+            if spawner.last_swing_hit != swing_id && check_collision(self.model.player.attack.shape, spawner.shape) {
+                spawner.last_swing_hit = swing_id;
+                self.model.damage_spawner(i, damage);
+            }
         }
 
-        for i in to_remove {
-            self.model.resources.remove(i);
+    }
+
+    /// Checks the position of the Player against each Resource. Any Resource
+    /// the Player touches is collected. Tower resources are banked for 
+    /// building Towers, and Ammo resources refill the Player's ammo.
+    fn check_resource_collision(&mut self) {
+
+        let model = &mut self.model;
+        systems::index(&model.world, &model.world.pickups, &mut model.pickup_grid);
+        for id in systems::touching(&model.world, &model.world.pickups, &model.pickup_grid, &model.player.shape) {
+            let variant = model.world.pickups[id].variant;
+            match variant {
+                ResourceVariant::Tower => model.player.resources += 1,
+                ResourceVariant::Ammo => model.player.ammo += AMMO_PER_PICKUP,
+            }
+            model.events.push(GameEvent::ResourceCollected{variant});
+            model.world.queue_despawn(id);
         }
 
     }
 
-    /// Moves each enemy in the direction of its path. Then the position of 
-    /// each enemy is compared against the position of the Beacon and the 
-    /// Player. If there is a collision with either, the appropriate entity
-    /// takes damage, and the enemy is destroyed. If the Player and the Beacon
-    /// are both colliding with the enemy within the same game tick, they both
-    /// take damage.
+    /// Moves each enemy in the direction of its path. Enemies struck by the
+    /// Player's active Swing take damage and are knocked back, and those 
+    /// which run out of health are destroyed and may drop a Resource. Then
+    /// the position of each enemy is compared against the position of the 
+    /// Beacons and the Player. An enemy colliding with a Beacon which hasn't
+    /// fallen damages it and is destroyed. An enemy colliding with the Player damages it (unless
+    /// the Player is invulnerable) and is knocked away from the Player.
     /// 
     /// For checking collisions with the beacon, the center point of the Beacon
     /// must within the Enemy's radius.
     /// 
     /// For checking collisions with the Player, the Player and the Enemy must
     /// overlap. 
+    ///
+    /// Enemies are steered before they move. Moving runs the movement system
+    /// over the whole World, so the Player's projectiles move here too, and
+    /// the enemy grid is indexed once they have. Only the Enemies found in the
+    /// enemy grid near the Swing, each Beacon and the Player are checked for
+    /// collisions, see systems::touching(). Destroyed enemies are queued for
+    /// despawning, and skipped by every later check in the tick.
     fn tick_enemies(&mut self, dt: f64) {
-        let mut to_remove: Vec<(usize, bool)> = Vec::new();
-        // Loop through enemies
-        for (i, enemy) in self.model.enemies.iter_mut().enumerate().rev() {
-            // move enemy
-            enemy.tick(dt);
-            
-            if check_collision(self.model.beacon.shape, enemy.shape) {
-                to_remove.push((i,false));
-                self.model.beacon.damage();
-            }
-            if check_collision(self.model.player.shape, enemy.shape) {
-                to_remove.push((i,false));
-                self.model.player.damage();
-            }
-
-            match self.model.player.state {
-                player::PlayerState::Attacking => {
-                    
-                    if check_collision(self.model.player.attack.shape, enemy.shape) {
-                        to_remove.push((i,true));
+        self.model.steer_enemies();
+        self.model.move_enemies(dt);
+
+        // Each Swing can only hit an Enemy once, and an Enemy killed by the
+        // Swing can't damage anything.
+        let mut killed: Vec<EntityId> = Vec::new();
+        let attack = &self.model.player.attack;
+        if attack.is_active() {
+            let world = &mut self.model.world;
+            for id in systems::touching(world, &world.enemies, &self.model.enemy_grid, &attack.shape) {
+                let shape = world.shapes[id];
+                if world.enemies[id].last_swing_hit != attack.swing_id {
+                    let swing = attack.swing();
+                    let away = Vec2::new_unit_from_point(shape.center_point() - self.model.player.shape.center_point());
+                    world.enemies[id].last_swing_hit = attack.swing_id;
+                    self.model.events.push(GameEvent::EnemyHit{id, position: shape.center_point()});
+                    if hit_enemy(world, id, swing.damage, Vec2::new(away.x * swing.knockback, away.y * swing.knockback)) {
+                        killed.push(id);
                     }
-                },
-                _ => (),
+                }
             }
+        }
+        for id in killed {
+            self.model.kill_enemy(id);
+        }
 
+        let mut beacon_hits: Vec<usize> = Vec::new();
+        for (j, beacon) in self.model.beacons.iter().enumerate().filter(|(_, beacon)| !beacon.is_fallen()) {
+            let world = &mut self.model.world;
+            for id in systems::touching(world, &world.enemies, &self.model.enemy_grid, &beacon.shape) {
+                if world.queue_despawn(id) {
+                    beacon_hits.push(j);
+                }
+            }
         }
 
-        // remove all enemies which had collisions
-        for (i, resource) in to_remove {
-            
-            let enemy = self.model.enemies.remove(i);
-            if resource {
-                self.model.spawn_resource(&enemy);
+        // Enemies touching the Player survive, but are knocked away so they 
+        // don't keep hitting the Player once it can be hurt again.
+        for id in self.model.enemies_near(&self.model.player.shape) {
+            let world = &mut self.model.world;
+            if world.is_despawning(id) {
+                continue;
+            }
+            if let Some(manifold) = contact(self.model.player.shape, world.shapes[id]) {
+                let outcome = self.model.player.damage(ENEMY_DAMAGE);
+                if outcome != DamageOutcome::Ignored {
+                    self.model.events.push(GameEvent::PlayerDamaged{outcome});
+                }
+                let away = manifold.normal;
+                world.velocities[id].knockback = Vec2::new(away.x * ENEMY_CONTACT_KNOCKBACK, away.y * ENEMY_CONTACT_KNOCKBACK);
             }
         }
 
-        //Check Gamestate to see if GameOver.
-        if self.model.beacon.health == 0 || self.model.player.health == 0{
-            println!("Game Over!");
-            println!("Player Health: {}, Beacon Health: {}",self.model.player.health, self.model.beacon.health);
-            self.change_state(GameState::Finished);
+        for j in beacon_hits {
+            self.model.damage_beacon(j, ENEMY_DAMAGE);
         }
 
     }
 
-    /// Ticks each resource in the GameModels resource list
-    fn tick_resources(&mut self, dt: f64) {
-
-        for resource in self.model.resources.iter_mut() {
-            resource.tick(dt);
+    /// Finishes the game once it has been won or lost, and records the 
+    /// results.
+    fn check_objective(&mut self) {
+
+        if let Some(outcome) = self.model.check_objective() {
+            self.model.events.push(GameEvent::GameOver{outcome});
+            self.results = Some(GameResults {
+                outcome,
+                waves: self.model.waves.number,
+                score: self.model.score(),
+            });
+            self.change_state(GameState::Finished);
         }
 
     }
 
-    /// Checks the position of every Tower's bullet (if attacking) and checks
-    /// it against all Enemies and the Tile it's currently touching.
-    fn check_bullet_collision(&mut self) {
-        for tower in self.model.towers.iter_mut() {
-            let mut to_remove: Vec<usize> = Vec::new();
-            match tower.state {
-                TowerState::Attacking => {
-                    
-                    if let Some(tile) = self.model.level.map.get(&point2_to_map_idx(tower.bullet.shape.center_point())) {
-                        match tile.variant {
-                            TileVariant::Wall => {
-                                tower.change_state(TowerState::Ready);
-                                continue;
-                            },
-                            _ => (),
-                        }
-                    } else {
-                        tower.change_state(TowerState::Ready);
-                        continue;
-                    }
-                    
-                    for (i,enemy) in self.model.enemies.iter().enumerate().rev() {
-                        if check_collision(tower.bullet.shape, enemy.shape){
-                            to_remove.push(i);
-                               tower.change_state(TowerState::Ready);
-                        }
-                    }
-
-                    for i in to_remove {
-                        self.model.enemies.remove(i);
+    /// Checks what each Tower's bullet and each of the Player's projectiles 
+    /// has run into, see systems::collide(). Spawners which were hit take the
+    /// damage, and Enemies which were killed are destroyed. Only kills by 
+    /// projectiles may drop a Resource.
+    fn check_hits(&mut self) {
+        let model = &mut self.model;
+        let hits = systems::collide(&mut model.world, &model.enemy_grid, &model.level.map, &model.spawners);
+        let mut killed: Vec<(EntityId, Point2, bool)> = Vec::new();
+        let mut spawner_hits: Vec<(MapIdx, i32)> = Vec::new();
+        for hit in hits {
+            match hit {
+                Hit::Enemy{id, position, killed: dead, by_tower} => {
+                    model.events.push(GameEvent::EnemyHit{id, position});
+                    if dead {
+                        killed.push((id, position, by_tower));
                     }
-
                 },
-                _ => (),
+                Hit::Spawner{idx, damage} => spawner_hits.push((idx, damage)),
+            }
+        }
+
+        // Spawners are found again by position, since destroying one shifts
+        // the rest along.
+        for (idx, damage) in spawner_hits {
+            if let Some(j) = model.spawners.iter().position(|spawner| spawner.idx == idx) {
+                model.damage_spawner(j, damage);
             }
+        }
 
+        for (id, position, by_tower) in killed {
+            if !by_tower {
+                model.kill_enemy(id);
+            } else if model.world.queue_despawn(id) {
+                model.kills += 1;
+                model.events.push(GameEvent::EnemyKilled{id, position});
+            }
         }
     }
 
     /// Function to check the state of the GameController. Used to keep the 
-    /// game loop running. Returns true while in the Running state, otherwise
-    /// returns false if in the Finished State.
+    /// game loop running. Returns true while in the Running or Paused state,
+    /// otherwise returns false if in the Finished State.
     pub fn check_state(&self) -> bool {
 
         match self.state {
             GameState::Finished => false,
-            GameState::Running => true,
+            GameState::Running | GameState::Paused => true,
         }
 
     }
@@ -1,31 +1,65 @@
 use crate::levels::Level;
-use crate::levels::map::{MapIdx, pathfind};
-use crate::traits::state::State;
+use crate::levels::map::{MapIdx, pathfind, pathfind_breaching, smooth_path, solid_tiles, tile_corners};
+use crate::levels::spatial_hash::SpatialHash;
+use crate::levels::visibility::Visibility;
 use crate::traits::entity::Entity;
-use crate::math::random::{Seed, RNG, from_seed, next_u32};
-use crate::math::Point2;
+use crate::traits::draw::{contact, GenericShape};
+use crate::math::random::{Seed, RNG, from_seed, next_f64, next_u32};
+use crate::math::{Point2, Vec2};
+use crate::math::collision::{circle_circle_contact, circle_rect_contact, ray_rect};
 use crate::entity::player::Player;
-use crate::entity::tile::{Tile, TileVariant};
-use crate::entity::beacon::Beacon;
-use crate::entity::enemy::Enemy;
-use crate::entity::drops::Resource;
-use crate::entity::towers::tower::{Tower, TowerState};
+use crate::entity::tile::{Tile, TileVariant, TrapKind};
+use crate::entity::beacon::{Beacon, BeaconUpgrade};
+use crate::entity::health::DamageOutcome;
+use crate::entity::enemy::{spawn_enemy, hit_enemy};
+use crate::entity::spawner::Spawner;
+use crate::entity::drops::{ResourceVariant, spawn_resource};
+use crate::entity::towers::bullet::spawn_projectile;
+use crate::entity::towers::tower::spawn_tower;
+use crate::ecs::{World, EntityId, systems};
+use crate::game::{Waves, Score, GameEvent};
+use crate::game::objective::{Objective, Outcome, Rules, Targeting};
 use crate::game::consts::{
     map_idx_to_point2,
     point2_to_map_idx,
-    PI,
-    INF,
+    BEACON_SIZE,
+    DROP_SIZE,
+    SPAWNER_TOWER_DROPS,
+    SPAWNER_AMMO_DROPS,
+    BEACON_UPGRADE_RANGE,
+    BEACON_PULSE_KNOCKBACK,
+    BEACON_SEPARATION,
+    BEACON_ESCORT_RANGE,
+    BEACON_ESCORT_SPEED,
+    ENEMY_SIZE,
+    ENEMY_RADIUS,
+    ENEMY_PERSONAL_SPACE,
+    ENEMY_SEPARATION_WEIGHT,
+    ENEMY_AVOIDANCE_WEIGHT,
+    ENEMY_FEELER_LENGTH,
+    ENEMY_FEELER_ANGLE,
+    ENEMY_WALL_DAMAGE,
+    ENEMY_WALL_ATTACK_INTERVAL,
+    DOOR_RANGE,
+    TRAP_COST,
+    SPIKE_TRAP_DAMAGE,
+    SPIKE_TRAP_COOLDOWN,
+    TILE_SIZE,
+    PLAYER_VISION_RADIUS,
+    TOWER_VISION_RADIUS,
 };
 
 /// A structure to fully encapsulate all components of the game. The different
-/// components include a Level, a Player, a Beacon and a collection of enemies.
-/// A random number generator is part of the structure to allow for randomly 
-/// choosing spawn points
+/// components include a Level, a Player, the Beacons and a World holding the
+/// Enemies, Towers, Resources and projectiles. A random number generator is part of the structure to allow for
+/// randomly choosing spawn points. The Rules decide how many Beacons there
+/// are, how enemies choose between them, and how the game is won or lost.
 /// 
 /// # Entity Spawn Points 
 /// 
 /// The GameModel is also responsible for finding the spawnpoints for each 
-/// entity indlucing the Beacon, the Player, and Enemies. 
+/// entity indlucing the Beacons, the Player, and Enemies. Levels loaded from
+/// a map file can mark where the Beacons and the Player start instead.
 /// 
 /// ## Beacon
 /// 
@@ -33,18 +67,23 @@ use crate::game::consts::{
 /// the ratio of Floors to Walls surrounding the point is calculated. This 
 /// ratio is used as a way to measure how open the surrounding area is. Only 
 /// Tiles which are above a threshold are considered for spawning. Once all the
-/// candidate spaces are found, one is chosen at random. 
+/// candidate spaces are found, one is chosen at random. Each further Beacon is
+/// chosen at random from the candidates at least BEACON_SEPARATION Tiles away
+/// from every Beacon chosen so far.
 /// 
 /// ## Player
 /// 
-/// The spawn point of the player depends on the location of the Beacon. Each
-/// Tile::Floor in an area surrounding the Beacon is a candidate spawning 
+/// The spawn point of the player depends on the location of the first Beacon.
+/// Each Tile::Floor in an area surrounding the Beacon is a candidate spawning
 /// space. Once all candidate spaces have been found, one is chosen at random.
 /// 
 /// ## Spawners
 /// 
 /// Any Tile::Wall with at least one Tile::Floor or Tile::Spawner to the north,
-/// east, south or west will be considered a candidate space. 
+/// east, south or west will be considered a candidate space. How often new
+/// Spawners are created, and how many there can be, depends on the Rules' 
+/// Difficulty. Spawners can be destroyed, dropping Resources as a reward, and
+/// their Tile reverts to a Tile::Wall.
 /// 
 /// If there are no candidate spaces found for the Enemy nothing happens.
 /// 
@@ -52,63 +91,136 @@ use crate::game::consts::{
 /// 
 /// The GameModel is also responsible for spawning enmies. For each spawning
 /// Tile in the Map, there is a constant chance of having an enemy spawn at 
-/// that location.
+/// that location while a Wave is Active. No enemies spawn during the Break
+/// between waves. Each Enemy chooses a Beacon to attack according to the 
+/// Rules' Targeting, and chooses again if its Beacon falls.
+/// 
+/// # Doors, Walls and Traps
+/// 
+/// Enemies choose again whenever the Map changes under them: when a Door is
+/// opened or closed, or when a Tile::DestructibleWall collapses. If no Beacon
+/// can be reached, an Enemy heads for the first Tile::DestructibleWall in its
+/// way and attacks it until it collapses. The traps the Player has built are
+/// kept in traps.
+/// 
+/// # Fog of War
+/// 
+/// While fog is on, only the Tiles the Player (and, with tower_vision, the
+/// Towers) can see are visible, and Enemies elsewhere are hidden. Tiles 
+/// which have been seen before stay explored. See Visibility.
+/// 
+/// # Collisions
+/// 
+/// Enemies are kept in enemy_grid and Resources in pickup_grid, so that 
+/// collision checks only look at those nearby. Enemies only move in 
+/// move_enemies(), which brings enemy_grid up to date once per tick.
+/// 
+/// # Events
+/// 
+/// Whatever happens to the game, such as an Enemy being killed or a Tower
+/// being built, is pushed onto events as a GameEvent. The GameController 
+/// clears the events at the start of each tick.
 pub struct GameModel {
     pub level: Level,
     pub player: Player,
-    pub beacon: Beacon,
-    pub enemies: Vec<Enemy>,
+    pub beacons: Vec<Beacon>,
+    pub world: World,
+    pub enemy_grid: SpatialHash,
+    pub pickup_grid: SpatialHash,
+    pub steering: bool,
+    pub visibility: Visibility,
+    pub fog: bool,
+    pub tower_vision: bool,
     pub max_enemies: usize,
-    pub spawners: Vec<MapIdx>,
-    pub resources: Vec<Resource>,
-    pub towers: Vec<Tower>,
+    pub spawners: Vec<Spawner>,
+    pub traps: Vec<MapIdx>,
+    pub waves: Waves,
+    pub rules: Rules,
+    pub elapsed: f64,
+    pub escort_path: Vec<Point2>,
+    pub kills: u32,
+    pub spawners_destroyed: u32,
+    pub events: Vec<GameEvent>,
     rng: RNG,
 }
 
 impl GameModel {
     
-    /// Creates a new GameModel. idx_to_point is a function pointer which
-    /// will convert a MapIdx into a Point2. This is required for creating the
-    /// Player position since find_player_spawn() returns a MapIdx instead of a
-    /// Point2. 
+    /// Creates a new GameModel on a generated Level, played by the default
+    /// Rules.
     pub fn new(seed: Seed) -> Option<Self> {
-        let level = Level::new(seed);
-        let mut rng = from_seed(seed);
-        if let Some(beacon_spawn) = GameModel::find_beacon_spawn(&level, &mut rng){
-            
-            let beacon = Beacon::new(beacon_spawn);
-            if let Some(player_spawn) = GameModel::find_player_spawn(&level, &beacon, &mut rng) {
-
-                let player = Player::new( map_idx_to_point2(player_spawn));
-                let enemies: Vec<Enemy> = Vec::new();
-                let spawners: Vec<MapIdx> = Vec::new();
-                let resources: Vec<Resource> = Vec::new();
-                let towers: Vec<Tower> = Vec::new();
-                let mut model = Self {
-                    level: level,
-                    player: player,
-                    beacon: beacon,
-                    enemies: enemies,
-                    max_enemies: 15,
-                    spawners: spawners,
-                    resources: resources,
-                    towers: towers,
-                    rng: rng
-                };
+        GameModel::with_rules(Level::new(seed), seed, Rules::default())
+    }
 
-                model.create_spawner();
-                model.create_spawner();
+    /// Creates a new GameModel on the input Level, played by the input Rules.
+    /// Beacons are placed wherever the Level marks them, otherwise 
+    /// rules.beacons Beacons are placed in open spaces. The Player starts 
+    /// wherever the Level marks, otherwise near the first Beacon. Any 
+    /// Tile::Spawner already in the Level is used, otherwise the GameModel 
+    /// starts with 2 spawners created. Returns None if the Beacons, the Player
+    /// or (when Escorting) the Escort's destination can't be placed.
+    pub fn with_rules(level: Level, seed: Seed, rules: Rules) -> Option<Self> {
+        let mut rng = from_seed(seed);
+        let beacon_spawns = if level.beacon_spawns.is_empty() {
+            GameModel::find_beacon_spawns(&level, rules.beacons, &mut rng)
+        } else {
+            level.beacon_spawns.clone()
+        };
+        if beacon_spawns.is_empty() {
+            return None;
+        }
+        let beacons: Vec<Beacon> = beacon_spawns.into_iter().map(Beacon::new).collect();
 
-                Some(model)
+        let player_spawn = match level.player_spawn {
+            Some(idx) => idx,
+            None => GameModel::find_player_spawn(&level, &beacons[0], &mut rng)?,
+        };
+        let escort_path = match rules.objective {
+            Objective::Escort => GameModel::find_escort_path(&level, beacons[0].idx)?,
+            _ => Vec::new(),
+        };
 
-            } else {
-                None
+        let mut spawners: Vec<Spawner> = Vec::new();
+        for h in 0..level.height {
+            for w in 0..level.width {
+                if let Some(Tile{variant: TileVariant::Spawner, ..}) = level.map.get(&MapIdx::new(w, h)) {
+                    spawners.push(Spawner::new(MapIdx::new(w, h), rules.difficulty.spawner_health()));
+                }
             }
-            
-        } else {
-            None
         }
-        
+
+        let player = Player::new(map_idx_to_point2(player_spawn));
+        let mut model = Self {
+            level,
+            player,
+            beacons,
+            world: World::new(),
+            enemy_grid: SpatialHash::new(),
+            pickup_grid: SpatialHash::new(),
+            steering: true,
+            visibility: Visibility::new(),
+            fog: true,
+            tower_vision: true,
+            max_enemies: 15,
+            spawners,
+            traps: Vec::new(),
+            waves: Waves::new(),
+            rules,
+            elapsed: 0.0,
+            escort_path,
+            kills: 0,
+            spawners_destroyed: 0,
+            events: Vec::new(),
+            rng
+        };
+
+        if model.spawners.is_empty() {
+            model.create_spawner();
+            model.create_spawner();
+        }
+        model.update_visibility();
+
+        Some(model)
     }
 
     /// Chooses a spawn point randomly from any Tile::Floor spaces surrounding
@@ -116,8 +228,8 @@ impl GameModel {
     fn find_player_spawn(level: &Level, beacon: &Beacon, rng: &mut RNG) -> Option<MapIdx> {
 
         let mut spawnable_spaces: Vec<MapIdx> = Vec::new();
-        for h in beacon.idx.x-10..beacon.idx.y+11 {
-            for w in beacon.idx.x-10..beacon.idx.y+11 {
+        for h in beacon.idx.y-10..beacon.idx.y+11 {
+            for w in beacon.idx.x-10..beacon.idx.x+11 {
                 if let Some(tile) = level.map.get(&MapIdx::new(w,h)) {
                     match tile.variant {
                         TileVariant::Floor => spawnable_spaces.push(MapIdx::new(w,h)),
@@ -138,9 +250,29 @@ impl GameModel {
 
     }
 
-    /// Finds an open space to spawn the beacon. To be sufficiently open there 
-    /// must be at least threshold more Floors than Walls in a surrounding area.
-    fn find_beacon_spawn(level: &Level, rng: &mut RNG) -> Option<MapIdx> {
+    /// Finds up to count open spaces to spawn Beacons, each at least 
+    /// BEACON_SEPARATION Tiles from the others. Fewer spaces are returned if
+    /// there isn't room for count Beacons.
+    fn find_beacon_spawns(level: &Level, count: usize, rng: &mut RNG) -> Vec<MapIdx> {
+
+        let mut candidates = GameModel::beacon_candidates(level);
+        let mut spawns: Vec<MapIdx> = Vec::new();
+        while spawns.len() < count {
+            candidates.retain(|c| spawns.iter().all(|s| (c.x - s.x).abs() + (c.y - s.y).abs() >= BEACON_SEPARATION));
+            if candidates.is_empty() {
+                break;
+            }
+            let idx = next_u32(rng) as usize % candidates.len();
+            spawns.push(candidates.remove(idx));
+        }
+        spawns
+
+    }
+
+    /// Finds the open spaces a Beacon can spawn in. To be sufficiently open 
+    /// there must be at least threshold more Floors than Walls in a 
+    /// surrounding area.
+    fn beacon_candidates(level: &Level) -> Vec<MapIdx> {
         
         let mut spawnable_spaces: Vec<MapIdx> = Vec::new();
         let threshold = 30;
@@ -166,22 +298,43 @@ impl GameModel {
             }
         }
 
-        if spawnable_spaces.len() == 0 {
-            None
-        } else {
-            let idx = next_u32(rng) as usize % spawnable_spaces.len();
-            let idx = spawnable_spaces.remove(idx);
-            Some(idx)
-        }
+        spawnable_spaces
 
-        
     } 
 
-    /// Has a chance of creating a new spawner
-    pub fn chanced_create_spawner(&mut self, chance: u32) {
+    /// Finds the path the Escorted Beacon follows, from start to the 
+    /// reachable Tile::Floor furthest from it. The path is made up of the
+    /// positions of the Beacon's center along the way.
+    fn find_escort_path(level: &Level, start: MapIdx) -> Option<Vec<Point2>> {
+
+        let mut candidates: Vec<MapIdx> = level.map.iter()
+            .filter(|(_, tile)| matches!(tile.variant, TileVariant::Floor))
+            .map(|(idx, _)| *idx)
+            .collect();
+        candidates.sort_by_key(|c| (-((c.x - start.x).abs() + (c.y - start.y).abs()), *c));
 
-        let rand = next_u32(&mut self.rng);
-        if rand % chance == 0 {
+        for goal in candidates {
+            if let Some((path, _cost)) = pathfind(&level.map, &start, &goal) {
+                let center = Point2{x: BEACON_SIZE / 2.0, y: BEACON_SIZE / 2.0};
+                return Some(path.into_iter().skip(1).map(|idx| map_idx_to_point2(idx) + center).collect());
+            }
+        }
+        None
+
+    }
+
+    /// Has a chance of creating a new spawner each tick, so that on average a
+    /// new spawner is created every Difficulty::spawner_interval() seconds. 
+    /// No spawners are created while Clearing Spawners, or once the 
+    /// Difficulty's maximum number of spawners has been reached.
+    pub fn chanced_create_spawner(&mut self, dt: f64) {
+
+        let difficulty = self.rules.difficulty;
+        if self.rules.objective == Objective::ClearSpawners || self.spawners.len() >= difficulty.max_spawners() {
+            return;
+        }
+        let rand = (next_u32(&mut self.rng) % 1_000_000) as f64 / 1_000_000.0;
+        if rand < dt / difficulty.spawner_interval(self.waves.number) {
             self.create_spawner();
         }
 
@@ -230,43 +383,437 @@ impl GameModel {
             let pos = canditate_spaces[idx];
             self.level.map.remove(&pos);
             self.level.map.insert(pos, Tile::new(TileVariant::Spawner, pos));
-            self.spawners.push(pos);
+            self.spawners.push(Spawner::new(pos, self.rules.difficulty.spawner_health()));
+            self.events.push(GameEvent::SpawnerCreated{idx: pos});
         }
 
     }
 
-    /// Creates a new enemy if a path can be found from the randomly generated
-    /// spawn point to the Beacon. 
-    /// 
-    /// Requires a function to convert a MapIdx to a Point2. See GameView's 
-    /// map_idx_to_point2 function.
+    /// Creates a new enemy at each spawner (with a constant chance) if a 
+    /// path can be found from the spawner to a Beacon which hasn't fallen.
     pub fn spawn_enemies(&mut self) {
         
-        for spawner in self.spawners.iter() {
+        if self.waves.is_break() {
+            return;
+        }
+        for i in 0..self.spawners.len() {
+            let spawner = self.spawners[i].idx;
             let r = next_u32(&mut self.rng);
-            if r % 50 == 0 && self.enemies.len() < self.max_enemies {
-                let target = &self.beacon.idx;
-                let mut enemy = Enemy::new(map_idx_to_point2(*spawner));
-                
-                if let Some(path) = pathfind(&self.level.map,&spawner, target) {
-                    let mut enemy_path: Vec<Point2> = Vec::new();
-                    for idx in path.0 {
-                        enemy_path.push(map_idx_to_point2(idx));
+            if r % 50 == 0 && self.world.enemies.len() < self.max_enemies {
+                if let Some((target, path, breach)) = self.choose_target(spawner) {
+                    let position = map_idx_to_point2(spawner);
+                    let id = spawn_enemy(&mut self.world, position);
+                    // So it can be found before it first moves
+                    self.enemy_grid.insert_shape(id.index(), &self.world.shapes[id]);
+                    let ai = &mut self.world.enemies[id];
+                    ai.target = target;
+                    ai.path = path;
+                    ai.breach = breach;
+                    self.events.push(GameEvent::EnemySpawned{id, position});
+                }
+            }
+        }
+    }
+
+    /// Chooses which Beacon an Enemy at start should attack, according to the
+    /// Rules' Targeting. Returns the index of the Beacon, the path to it, and
+    /// the Tile::DestructibleWall the Enemy needs to break through first (if 
+    /// any), or None if no Beacon which hasn't fallen can be reached.
+    /// 
+    /// Only if no Beacon can be reached are paths through 
+    /// Tile::DestructibleWalls considered. Those paths stop in front of the 
+    /// first wall in the way.
+    fn choose_target(&mut self, start: MapIdx) -> Option<(usize, Vec<Point2>, Option<MapIdx>)> {
+
+        let mut paths: Vec<(usize, Vec<MapIdx>, u32)> = Vec::new();
+        for finder in [pathfind, pathfind_breaching].iter() {
+            for (i, beacon) in self.beacons.iter().enumerate() {
+                if beacon.is_fallen() {
+                    continue;
+                }
+                if let Some((path, cost)) = finder(&self.level.map, &start, &beacon.idx) {
+                    paths.push((i, path, cost));
+                }
+            }
+            if !paths.is_empty() {
+                break;
+            }
+        }
+
+        let choice = match self.rules.targeting {
+            Targeting::Nearest => {
+                (0..paths.len()).min_by_key(|j| paths[*j].2)
+            },
+            Targeting::Weighted => {
+                // Each Beacon is weighted by the inverse of its path cost.
+                let weights: Vec<f64> = paths.iter().map(|(_, _, cost)| 1.0 / (*cost as f64 + 1.0)).collect();
+                let total: f64 = weights.iter().sum();
+                let mut r = next_f64(&mut self.rng) * total;
+                weights.iter().position(|weight| {
+                    if r < *weight {
+                        true
+                    } else {
+                        r -= weight;
+                        false
+                    }
+                }).or_else(|| weights.len().checked_sub(1))
+            },
+        };
+
+        choice.map(|j| {
+            let (target, mut path, _cost) = paths.swap_remove(j);
+            let map = &self.level.map;
+            let breach = path.iter().position(|idx| matches!(map[idx].variant, TileVariant::DestructibleWall{..}));
+            let breach = breach.map(|k| {
+                let wall = path[k];
+                path.truncate(k);
+                wall
+            });
+            (target, smooth_path(map, &path, ENEMY_SIZE), breach)
+        })
+
+    }
+
+    /// Gives each Enemy heading for the input Beacon a new target, from where
+    /// the Enemy is now. Used when a Beacon falls or moves.
+    pub fn retarget_enemies(&mut self, beacon: usize) {
+
+        for id in self.world.enemies.ids() {
+            if self.world.enemies[id].target != beacon {
+                continue;
+            }
+            let start = point2_to_map_idx(self.world.shapes[id].center_point());
+            if let Some((target, path, breach)) = self.choose_target(start) {
+                let ai = &mut self.world.enemies[id];
+                ai.target = target;
+                ai.path = path;
+                ai.breach = breach;
+            }
+        }
+
+    }
+
+    /// Gives every Enemy a new target and path from where it is now. Used 
+    /// whenever the Map changes in a way which could open or close off paths.
+    /// Enemies which can no longer reach any Beacon stop where they are.
+    pub fn reroute_enemies(&mut self) {
+
+        for id in self.world.enemies.ids() {
+            let start = point2_to_map_idx(self.world.shapes[id].center_point());
+            let enemy_target = self.choose_target(start);
+            let ai = &mut self.world.enemies[id];
+            match enemy_target {
+                Some((target, path, breach)) => {
+                    ai.target = target;
+                    ai.path = path;
+                    ai.breach = breach;
+                },
+                None => {
+                    ai.path.clear();
+                    ai.breach = None;
+                },
+            }
+        }
+
+    }
+
+    /// Damages the Tile::DestructibleWall at idx. A wall which runs out of 
+    /// health collapses into Tile::Rubble, and every Enemy is rerouted. 
+    /// Returns true if the wall collapsed.
+    pub fn damage_wall(&mut self, idx: MapIdx, amount: i32) -> bool {
+
+        let collapsed = match self.level.map.get_mut(&idx) {
+            Some(Tile{variant: TileVariant::DestructibleWall{health}, ..}) => {
+                *health -= amount;
+                *health <= 0
+            },
+            _ => return false,
+        };
+        if collapsed {
+            self.level.map.insert(idx, Tile::new(TileVariant::Rubble, idx));
+            self.reroute_enemies();
+            self.events.push(GameEvent::WallCollapsed{idx});
+        }
+        collapsed
+
+    }
+
+    /// Has each Enemy breaking through a wall attack it, every 
+    /// ENEMY_WALL_ATTACK_INTERVAL seconds while the Enemy is next to it. If 
+    /// the wall has gone by some other means, the Enemy is rerouted.
+    pub fn tick_breaches(&mut self, dt: f64) {
+
+        let mut reroute = false;
+        let mut attacks: Vec<MapIdx> = Vec::new();
+        let World { shapes, enemies, .. } = &mut self.world;
+        for (id, ai) in enemies.iter_mut() {
+            let wall = match ai.breach {
+                Some(wall) => wall,
+                None => continue,
+            };
+            if !matches!(self.level.map.get(&wall), Some(Tile{variant: TileVariant::DestructibleWall{..}, ..})) {
+                reroute = true;
+                continue;
+            }
+            // The Enemy can reach the wall from any Tile beside it.
+            let reach = ENEMY_RADIUS + TILE_SIZE / 2.0;
+            if circle_rect_contact(shapes[id].center_point(), reach, &tile_corners(wall)).is_none() {
+                continue;
+            }
+            ai.wall_attack_timer -= dt;
+            if ai.wall_attack_timer <= 0.0 {
+                ai.wall_attack_timer = ENEMY_WALL_ATTACK_INTERVAL;
+                attacks.push(wall);
+            }
+        }
+
+        for wall in attacks {
+            if self.damage_wall(wall, ENEMY_WALL_DAMAGE) {
+                reroute = false;
+            }
+        }
+        if reroute {
+            self.reroute_enemies();
+        }
+
+    }
+
+    /// Opens or closes the Door nearest the Player, within DOOR_RANGE of 
+    /// them. A Door can't be closed on the Player or an Enemy. Every Enemy is
+    /// rerouted once the Door has changed. Returns true if a Door was opened
+    /// or closed.
+    pub fn toggle_door(&mut self) -> bool {
+
+        let center = self.player.shape.center_point();
+        let reach = Point2{x: DOOR_RANGE, y: DOOR_RANGE};
+        let (lo, hi) = (point2_to_map_idx(center - reach), point2_to_map_idx(center + reach));
+        let mut nearest: Option<(MapIdx, bool, f64)> = None;
+        for h in lo.y..=hi.y {
+            for w in lo.x..=hi.x {
+                let idx = MapIdx::new(w, h);
+                if let Some(Tile{variant: TileVariant::Door{open}, shape}) = self.level.map.get(&idx) {
+                    let distance = Vec2::new_from_point(shape.center_point() - center).length();
+                    if distance <= DOOR_RANGE && nearest.is_none_or(|(_, _, d)| distance < d) {
+                        nearest = Some((idx, *open, distance));
                     }
-                    enemy.path = enemy_path;
-                    self.enemies.push(enemy);
                 }
             }
         }
+
+        let (idx, open) = match nearest {
+            Some((idx, open, _)) => (idx, open),
+            None => return false,
+        };
+        if open {
+            let door = self.level.map[&idx].shape;
+            if contact(door, self.player.shape).is_some() || self.world.enemies.iter().any(|(id, _)| contact(door, self.world.shapes[id]).is_some()) {
+                return false;
+            }
+        }
+        self.level.map.insert(idx, Tile::new(TileVariant::Door{open: !open}, idx));
+        self.reroute_enemies();
+        self.events.push(GameEvent::DoorToggled{idx, open: !open});
+        true
+
+    }
+
+    /// Builds a trap on the Tile::Floor under the Player, costing TRAP_COST
+    /// resources. Returns true if the trap was built.
+    pub fn build_trap(&mut self, kind: TrapKind) -> bool {
+
+        let idx = point2_to_map_idx(self.player.shape.center_point());
+        let on_floor = matches!(self.level.map.get(&idx), Some(Tile{variant: TileVariant::Floor, ..}));
+        if !on_floor || self.player.resources < TRAP_COST {
+            return false;
+        }
+        self.player.resources -= TRAP_COST;
+        self.level.map.insert(idx, Tile::new(kind.variant(), idx));
+        self.traps.push(idx);
+        self.events.push(GameEvent::TrapBuilt{idx, kind});
+        true
+
+    }
+
+    /// Ticks each trap. Once its cooldown has run out, a Tile::SpikeTrap 
+    /// damages every Enemy standing on it and starts cooling down again. 
+    /// Enemies killed by a trap may drop a Resource. Tile::SlowTraps need no
+    /// ticking, since they only slow down whatever crosses them.
+    pub fn tick_traps(&mut self, dt: f64) {
+
+        let (map, world, grid) = (&mut self.level.map, &mut self.world, &self.enemy_grid);
+        let mut killed: Vec<EntityId> = Vec::new();
+        for idx in self.traps.iter() {
+            let cooldown = match map.get_mut(idx) {
+                Some(Tile{variant: TileVariant::SpikeTrap{cooldown}, ..}) => cooldown,
+                _ => continue,
+            };
+            *cooldown = (*cooldown - dt).max(0.0);
+            if *cooldown > 0.0 {
+                continue;
+            }
+            let corners = tile_corners(*idx);
+            let standing: Vec<EntityId> = grid.query_aabb(corners[0], corners[3]).into_iter()
+                .filter_map(|i| world.enemies.id_at(i))
+                .filter(|id| !world.is_despawning(*id) && point2_to_map_idx(world.shapes[*id].center_point()) == *idx)
+                .collect();
+            if standing.is_empty() {
+                continue;
+            }
+            *cooldown = SPIKE_TRAP_COOLDOWN;
+            for id in standing {
+                self.events.push(GameEvent::EnemyHit{id, position: world.shapes[id].center_point()});
+                if hit_enemy(world, id, SPIKE_TRAP_DAMAGE, Vec2::new(0.0, 0.0)) {
+                    killed.push(id);
+                }
+            }
+        }
+
+        for id in killed {
+            self.kill_enemy(id);
+        }
+
+    }
+
+    /// Returns the Beacon closest to point which hasn't fallen.
+    pub fn nearest_beacon(&self, point: Point2) -> Option<&Beacon> {
+
+        self.beacons.iter()
+            .filter(|beacon| !beacon.is_fallen())
+            .min_by(|a, b| {
+                let a = Vec2::new_from_point(a.shape.center_point() - point).length();
+                let b = Vec2::new_from_point(b.shape.center_point() - point).length();
+                a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+            })
+
+    }
+
+    /// Damages the input Beacon. If the Beacon falls, every Enemy heading for
+    /// it chooses a new target.
+    pub fn damage_beacon(&mut self, beacon: usize, amount: i32) -> DamageOutcome {
+
+        let outcome = self.beacons[beacon].damage(amount);
+        self.events.push(GameEvent::BeaconDamaged{beacon, outcome});
+        if outcome == DamageOutcome::Killed {
+            self.retarget_enemies(beacon);
+            self.events.push(GameEvent::BeaconFallen{beacon});
+        }
+        outcome
+
+    }
+
+    /// Returns the Score of the game so far. Only whole seconds count towards
+    /// the Score.
+    pub fn score(&self) -> Score {
+
+        Score {
+            kills: self.kills,
+            beacon_health: self.beacons.iter().map(|beacon| beacon.health.current).sum(),
+            resources: self.player.resources,
+            spawners: self.spawners_destroyed,
+            seconds: self.elapsed as u32,
+        }
+
     }
 
-    /// Function to spawn a new resource at the location of the Enemy which was
-    /// killed. There is a roughly 33% chance of spawning a resource. 
-    pub fn spawn_resource(&mut self, enemy: &Enemy) {
+    /// Checks whether the game has been won or lost. The game is lost if the
+    /// Player dies, if too many Beacons fall, or if the Escorted Beacon 
+    /// falls. It's won once the Rules' Objective is complete.
+    pub fn check_objective(&self) -> Option<Outcome> {
+
+        let fallen = self.beacons.iter().filter(|beacon| beacon.is_fallen()).count();
+        let allowed = self.rules.beacon_losses.unwrap_or(self.beacons.len()).clamp(1, self.beacons.len());
+        let escort_fallen = self.rules.objective == Objective::Escort && self.beacons[0].is_fallen();
+        if self.player.health.is_dead() || fallen >= allowed || escort_fallen {
+            return Some(Outcome::Lost);
+        }
+
+        let won = match self.rules.objective {
+            Objective::Defend => false,
+            Objective::SurviveWaves(waves) => self.waves.number > waves || (self.waves.number == waves && self.waves.is_break()),
+            Objective::Hold(seconds) => self.elapsed >= seconds,
+            Objective::Escort => self.escort_path.is_empty(),
+            Objective::ClearSpawners => self.spawners.is_empty(),
+        };
+        if won {
+            Some(Outcome::Won)
+        } else {
+            None
+        }
+
+    }
+
+    /// Function to spawn a new resource at the location of an Enemy which was
+    /// killed. There is a roughly 33% chance of spawning a resource, which is
+    /// equally likely to be a Tower or an Ammo resource.
+    pub fn drop_resource(&mut self, position: Point2) {
 
         let r = next_u32(&mut self.rng);
         if r % 3 == 0 {
-            self.resources.push(Resource::new(enemy.shape.center_point()));
+            let variant = match next_u32(&mut self.rng) % 2 {
+                0 => ResourceVariant::Tower,
+                _ => ResourceVariant::Ammo,
+            };
+            let id = spawn_resource(&mut self.world, position, variant);
+            self.events.push(GameEvent::ResourceDropped{id, variant});
+        }
+
+    }
+
+    /// Queues an Enemy killed by the Player, a trap or a Beacon's Pulse to be
+    /// despawned, counts the kill and may drop a Resource where it died. An
+    /// Enemy which is already despawning can't be killed again, so it's only
+    /// counted once. Returns true if the Enemy was killed.
+    pub fn kill_enemy(&mut self, id: EntityId) -> bool {
+
+        let position = match self.world.shapes.get(id) {
+            Some(shape) if self.world.enemies.contains(id) => shape.center_point(),
+            _ => return false,
+        };
+        if !self.world.queue_despawn(id) {
+            return false;
+        }
+        self.kills += 1;
+        self.events.push(GameEvent::EnemyKilled{id, position});
+        self.drop_resource(position);
+        true
+
+    }
+
+    /// Damages the input spawner. A spawner which runs out of health is 
+    /// destroyed: its Tile collapses into Tile::Rubble, and it drops 
+    /// SPAWNER_TOWER_DROPS Tower resources and SPAWNER_AMMO_DROPS Ammo 
+    /// resources. Returns true if the spawner was destroyed.
+    pub fn damage_spawner(&mut self, spawner: usize, amount: i32) -> bool {
+
+        if !self.spawners[spawner].hit(amount) {
+            return false;
+        }
+
+        let spawner = self.spawners.remove(spawner);
+        self.level.map.insert(spawner.idx, Tile::new(TileVariant::Rubble, spawner.idx));
+        self.spawners_destroyed += 1;
+        self.events.push(GameEvent::SpawnerDestroyed{idx: spawner.idx});
+
+        // Spread the drops out along the face of the spawner.
+        let center = spawner.shape.center_point();
+        let drops = std::iter::repeat_n(ResourceVariant::Tower, SPAWNER_TOWER_DROPS)
+            .chain(std::iter::repeat_n(ResourceVariant::Ammo, SPAWNER_AMMO_DROPS));
+        for (i, variant) in drops.enumerate() {
+            let offset = Point2{x: (i as f64 - 1.0) * DROP_SIZE, y: 0.0};
+            let id = spawn_resource(&mut self.world, center + offset, variant);
+            self.events.push(GameEvent::ResourceDropped{id, variant});
+        }
+        true
+
+    }
+
+    /// Fires a bullet from the Player towards the cursor, if the Player has
+    /// ammo and their weapon is ready.
+    pub fn fire_projectile(&mut self) {
+
+        if let Some(bullet) = self.player.fire() {
+            let id = spawn_projectile(&mut self.world, bullet);
+            self.events.push(GameEvent::ProjectileFired{id});
         }
 
     }
@@ -276,91 +823,262 @@ impl GameModel {
 
         if self.player.resources != 0 {
             self.player.resources -= 1;
-            self.towers.push(Tower::new(self.player.shape.get_position()));
+            let position = self.player.shape.get_position();
+            let id = spawn_tower(&mut self.world, position);
+            self.events.push(GameEvent::TowerBuilt{id, position});
         }
 
     }
 
-    /// Updates each tower in the tower list. If any enemies are close enough,
-    /// visible, and are within tower range the towers switch to Attacking, (if
-    /// not already attacking).
-    pub fn tick_towers(&mut self, dt: f64){
+    /// Buys the next level of a Beacon upgrade with the Player's resources.
+    /// The Player must be standing near a Beacon which hasn't fallen. Returns
+    /// true if the upgrade was bought.
+    pub fn upgrade_beacon(&mut self, upgrade: BeaconUpgrade) -> bool {
 
-        for tower in self.towers.iter_mut() {
-            let mut new_dir = Point2{x: 0.0, y: 0.0};
-            let mut min_dist = INF;
-            for enemy in self.enemies.iter() {
+        let position = self.player.shape.center_point();
+        let resources = &mut self.player.resources;
+        match self.beacons.iter_mut().find(|beacon| !beacon.is_fallen() && beacon.within(position, BEACON_UPGRADE_RANGE)) {
+            Some(beacon) => beacon.upgrade(upgrade, resources),
+            None => false,
+        }
 
-                let dir = enemy.shape.center_point() - tower.base_shape.center_point();
-                let slope = dir.y / dir.x;
-                let vertical_offset = tower.base_shape.center_point().y;
-                let p0 = point2_to_map_idx(tower.base_shape.center_point());
-                let pn = point2_to_map_idx(enemy.shape.center_point());
+    }
 
-                let mut wall_hit = false;
-                let mut previous = point2_to_map_idx(tower.base_shape.get_position());
-                for x in p0.x..pn.x {
-                    let next = point2_to_map_idx(Point2{x: 0.0, y: slope * x as f64 + vertical_offset});
-                    if next.y != previous.y {
-                        if let Some(tile) = self.level.map.get(&MapIdx::new(previous.x, next.y)) {
-                            match tile.variant {
-                                TileVariant::Wall => {
-                                    wall_hit = true;
-                                    continue;
-                                },
-                                _ => (),
-                            }
-                        }
-                    }
-                    
-                    if let Some(tile) = self.level.map.get(&MapIdx::new(x, next.y)) {
-                        match tile.variant {
-                            TileVariant::Wall => {
-                                wall_hit = true;
-                                continue;
-                            },
-                            _ => (),
-                        }
-                    } 
-                    previous = MapIdx::new(x, next.y);
-                }
-                if wall_hit {
-                    continue;
-                }
+    /// Advances the Waves and the game clock, moves the Escorted Beacon, and
+    /// ticks each Beacon which hasn't fallen along with its upgrades. Beacons
+    /// only regenerate between waves. An Aura heals the Player while they 
+    /// stand inside it.
+    pub fn tick_beacons(&mut self, dt: f64) {
 
-                let dist = dir.x.abs() + dir.y.abs();
-                if dist < min_dist {
-                    min_dist = dist;
-                    new_dir = dir; 
-                }
+        self.waves.tick(dt);
+        self.elapsed += dt;
+        self.tick_escort(dt);
 
+        let regenerating = self.waves.is_break();
+        for i in 0..self.beacons.len() {
+            let beacon = &mut self.beacons[i];
+            if beacon.is_fallen() {
+                continue;
             }
+            beacon.set_regenerating(regenerating);
+            beacon.tick(dt);
 
-            if min_dist < tower.range {
-                
-                let mut rad = new_dir.y / new_dir.x;
-                rad = rad.atan();
-                
-                match [new_dir.x < 0.0, new_dir.y < 0.0] {
-                    [true, true] => rad = PI * 2.0 - rad,
-                    [true, false] => rad = rad * -1.0,
-                    [false, true] => rad = PI + rad * -1.0,
-                    [false, false] => rad = PI - rad
-                }
+            let heal = beacon.aura_heal(dt, self.player.shape.center_point());
+            if heal > 0 {
+                self.player.health.heal(heal);
+            }
+            self.tick_pulse(i);
+        }
+
+    }
+
+    /// Moves the Escorted Beacon along its path while the Player is close to
+    /// it. Enemies heading for the Beacon follow it as it moves.
+    fn tick_escort(&mut self, dt: f64) {
+
+        if self.rules.objective != Objective::Escort || self.escort_path.is_empty() {
+            return;
+        }
+        let beacon = &mut self.beacons[0];
+        if beacon.is_fallen() || !beacon.within(self.player.shape.center_point(), BEACON_ESCORT_RANGE) {
+            return;
+        }
+
+        let remaining = self.escort_path[0] - beacon.shape.center_point();
+        let distance = Vec2::new_from_point(remaining).length();
+        let step = BEACON_ESCORT_SPEED * dt;
+        let delta = if distance <= step {
+            self.escort_path.remove(0);
+            remaining
+        } else {
+            remaining * (step / distance)
+        };
+        if beacon.translate(delta) {
+            self.retarget_enemies(0);
+        }
+
+    }
+
+    /// Fires the input Beacon's Pulse as soon as it's ready and an enemy 
+    /// comes within range, damaging and knocking back every enemy inside it.
+    /// Enemies killed by the Pulse may drop a Resource.
+    fn tick_pulse(&mut self, i: usize) {
+
+        let beacon = &mut self.beacons[i];
+        let radius = beacon.pulse_radius();
+        let center = beacon.shape.center_point();
+        let world = &self.world;
+        let inside: Vec<EntityId> = world.enemies.ids().into_iter()
+            .filter(|id| !world.is_despawning(*id) && beacon.within(world.shapes[*id].center_point(), radius))
+            .collect();
+        if !beacon.pulse_ready() || inside.is_empty() {
+            return;
+        }
+        beacon.fire_pulse();
+
+        let damage = beacon.pulse_damage();
+        let mut killed: Vec<EntityId> = Vec::new();
+        for id in inside {
+            let position = self.world.shapes[id].center_point();
+            self.events.push(GameEvent::EnemyHit{id, position});
+            let away = Vec2::new_unit_from_point(position - center);
+            let knockback = Vec2::new(away.x * BEACON_PULSE_KNOCKBACK, away.y * BEACON_PULSE_KNOCKBACK);
+            if hit_enemy(&mut self.world, id, damage, knockback) {
+                killed.push(id);
+            }
+        }
+
+        for id in killed {
+            self.kill_enemy(id);
+        }
+
+    }
+
+    /// Remembers where everything which moves is at the start of a tick, so
+    /// the GameView can draw it part way between ticks. See 
+    /// GenericShape::interpolate().
+    pub fn save_transforms(&mut self) {
+        self.player.shape.save_transform();
+        self.player.attack.shape.save_transform();
+        for beacon in self.beacons.iter_mut() {
+            beacon.shape.save_transform();
+        }
+        for (_, shape) in self.world.shapes.iter_mut() {
+            shape.save_transform();
+        }
+        for (_, tower) in self.world.towers.iter_mut() {
+            tower.cannon_shape.save_transform();
+            tower.bullet.shape.save_transform();
+        }
+    }
+
+    /// Works out which Tiles can be seen this tick: those within 
+    /// PLAYER_VISION_RADIUS Tiles of the Player, and (with tower_vision) 
+    /// TOWER_VISION_RADIUS Tiles of each Tower, which aren't hidden behind
+    /// walls. Nothing is worked out while fog is off.
+    pub fn update_visibility(&mut self) {
+
+        if !self.fog {
+            return;
+        }
+        self.visibility.clear_visible();
+        let map = &self.level.map;
+        self.visibility.reveal(map, point2_to_map_idx(self.player.shape.center_point()), PLAYER_VISION_RADIUS);
+        if self.tower_vision {
+            for (_, tower) in self.world.towers.iter() {
+                self.visibility.reveal(map, point2_to_map_idx(tower.base_shape.center_point()), TOWER_VISION_RADIUS);
+            }
+        }
+
+    }
+
+    /// Returns true if point can be seen by the Player. Everything can be 
+    /// seen while fog is off.
+    pub fn can_see(&self, point: Point2) -> bool {
+        !self.fog || self.visibility.point_visible(point)
+    }
 
-                rad = PI - rad;
-                tower.set_rotation(rad);
+    /// Brings the enemy grid up to date with the Enemies, by the index of 
+    /// their EntityIds. Only the Enemies which have moved into other cells 
+    /// are registered again, see systems::index().
+    pub fn index_enemies(&mut self) {
+        systems::index(&self.world, &self.world.enemies, &mut self.enemy_grid);
+    }
 
-                match tower.state {
-                    TowerState::Ready => tower.change_state(TowerState::Attacking),
-                    _ => (),
+    /// Moves each Enemy along its path, along with everything else with a 
+    /// Velocity, then indexes the Enemies where they've moved to. Nothing 
+    /// else moves Enemies, so the enemy grid is only indexed here, once per 
+    /// tick.
+    pub fn move_enemies(&mut self, dt: f64) {
+        systems::follow_paths(&mut self.world, &self.level.map);
+        systems::movement(&mut self.world, &self.level.map, dt);
+        self.index_enemies();
+    }
+
+    /// Returns the ids of the Enemies found in the enemy grid near shape, 
+    /// which might be touching it. See index_enemies().
+    pub fn enemies_near(&self, shape: &GenericShape) -> Vec<EntityId> {
+        self.enemy_grid.query_shape(shape).into_iter()
+            .filter_map(|i| self.world.enemies.id_at(i))
+            .collect()
+    }
+
+    /// Works out the steering force of each Enemy for its next tick. Enemies
+    /// are pushed apart from any neighbours inside their personal space, and
+    /// away from walls they're touching or which their feelers (three short 
+    /// rays cast ahead of them) run into. The closer the neighbour or wall, 
+    /// the stronger the push. While steering is turned off, Enemies just 
+    /// follow their paths. Neighbours are found in the enemy grid, where 
+    /// move_enemies() last left them.
+    pub fn steer_enemies(&mut self) {
+
+        if !self.steering {
+            for (_, ai) in self.world.enemies.iter_mut() {
+                ai.steering = None;
+            }
+            return;
+        }
+
+        let (shapes, enemies) = (&self.world.shapes, &self.world.enemies);
+        let mut forces: Vec<(EntityId, Vec2)> = Vec::with_capacity(enemies.len());
+        for (id, _) in enemies.iter() {
+            let i = id.index();
+            let center = shapes[id].center_point();
+            let direction = self.world.velocities.get(id).map_or(Vec2::new(0.0, 0.0), |velocity| velocity.direction);
+            let mut force = Point2{x: 0.0, y: 0.0};
+
+            // Separation
+            for j in self.enemy_grid.query_radius(center, ENEMY_RADIUS + ENEMY_PERSONAL_SPACE) {
+                if j == i {
+                    continue;
+                }
+                let neighbour = match enemies.id_at(j) {
+                    Some(other) => shapes[other].center_point(),
+                    None => continue,
+                };
+                if let Some(contact) = circle_circle_contact(neighbour, ENEMY_RADIUS, center, ENEMY_PERSONAL_SPACE) {
+                    // Enemies on top of each other are split apart by index.
+                    let mut away = Point2{x: contact.normal.x, y: contact.normal.y};
+                    if neighbour.x == center.x && neighbour.y == center.y && i < j {
+                        away = away * -1.0;
+                    }
+                    force = force + away * (contact.depth / (ENEMY_RADIUS + ENEMY_PERSONAL_SPACE) * ENEMY_SEPARATION_WEIGHT);
+                }
+            }
+
+            // Obstacle avoidance
+            let reach = Point2{x: ENEMY_FEELER_LENGTH, y: ENEMY_FEELER_LENGTH};
+            let walls = solid_tiles(&self.level.map, center - reach, center + reach);
+            for idx in walls.iter() {
+                if let Some(contact) = circle_rect_contact(center, ENEMY_RADIUS, &tile_corners(*idx)) {
+                    let into = Point2{x: contact.normal.x, y: contact.normal.y};
+                    force = force - into * (contact.depth / ENEMY_RADIUS * ENEMY_AVOIDANCE_WEIGHT);
+                }
+            }
+            if direction.length() > 0.0 {
+                for angle in [-ENEMY_FEELER_ANGLE, 0.0, ENEMY_FEELER_ANGLE].iter() {
+                    let (sin, cos) = angle.sin_cos();
+                    let feeler = Vec2::new(
+                        direction.x * cos - direction.y * sin,
+                        direction.x * sin + direction.y * cos
+                    );
+                    let nearest = walls.iter()
+                        .filter_map(|idx| ray_rect(center, feeler, ENEMY_FEELER_LENGTH, &tile_corners(*idx)))
+                        .min_by(|a, b| a.distance.total_cmp(&b.distance));
+                    if let Some(hit) = nearest {
+                        let push = (1.0 - hit.distance / ENEMY_FEELER_LENGTH) * ENEMY_AVOIDANCE_WEIGHT;
+                        force = force + Point2{x: hit.normal.x, y: hit.normal.y} * push;
+                    }
                 }
             }
 
-            tower.tick(dt);
+            forces.push((id, Vec2::new_from_point(force)));
+        }
 
+        for (id, force) in forces {
+            self.world.enemies[id].steering = Some(force);
         }
 
     }
 
-}
\ No newline at end of file
+}
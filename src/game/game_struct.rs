@@ -1,17 +1,26 @@
-use crate::game::GameController;
+use crate::game::{Bindings, GameController, GameResults, LoadTexture, TextureAtlas, ViewTextures};
+use crate::levels::Level;
 use crate::math::random;
 use crate::input;
+use crate::render::SoftwareGraphics;
+use crate::entity::player::MovementMode;
 use crate::game::consts::{
+    BINDINGS_PATH,
+    FONT_PATH,
     OPEN_GL_VERSION,
+    SPRITE_SHEET_PATH,
+    UPDATES_PER_SECOND,
     WINDOW_HEIGHT,
     WINDOW_WIDTH,
 };
 
 use piston::WindowSettings;
-use piston::event_loop::{Events, EventSettings};
+use piston::event_loop::{EventLoop, Events, EventSettings};
 use piston::input::RenderEvent;
 use glutin_window::GlutinWindow;
-use opengl_graphics::{OpenGL, GlGraphics};
+use opengl_graphics::{GlyphCache, OpenGL, GlGraphics, TextureSettings};
+use std::io;
+use std::path::PathBuf;
 
 /// Game 
 /// 
@@ -22,6 +31,8 @@ pub struct Game {
     opengl: OpenGL,
     window_settings: WindowSettings,
     controller: GameController,
+    sprites: bool,
+    screenshot: Option<String>,
 }
 
 impl Game {
@@ -30,16 +41,31 @@ impl Game {
         
         // Parse command line for input commands
         let config = input::handle_init_input();
-        // Create the seed used for the run
-        let mut seed = random::create_seed(config.debug);
+        // Create the seed used for the run. Screenshots use the seed they're
+        // given, and reroll by counting up from it so they can be repeated.
+        let mut seed = match config.screenshot {
+            Some((n, _)) => random::seed_from_u64(n),
+            None => random::create_seed(config.debug),
+        };
+        let mut rerolls = 0;
         let mut controller: GameController;
         loop {
 
-            if let Some(c) = GameController::new(seed) {
+            let level = match &config.map {
+                Some(path) => Level::load(path, seed).expect("Couldn't load map file!"),
+                None => Level::new(seed),
+            };
+            if let Some(c) = GameController::with_rules(level, seed, config.rules) {
                 controller = c;
                 break;
             } else if config.debug {
                 panic!("Failed to create game controller with debug flag");
+            } else if config.map.is_some() {
+                panic!("Failed to create game controller from map file");
+            } else if let Some((n, _)) = config.screenshot {
+                rerolls += 1;
+                seed = random::seed_from_u64(n.wrapping_add(rerolls));
+                println!("Had to reroll seed");
             } else {
                 seed = random::create_seed(false);
                 println!("Had to reroll seed");
@@ -47,19 +73,60 @@ impl Game {
 
         }
 
+        // Without a bindings file, any Bindings rebound in an earlier game 
+        // are loaded, and rebinding saves them there.
+        let bindings = PathBuf::from(config.bindings.as_deref().unwrap_or(BINDINGS_PATH));
+        if config.bindings.is_some() || bindings.exists() {
+            match Bindings::load(&bindings) {
+                Ok(loaded) => controller.bindings = loaded,
+                Err(error) => println!("Couldn't load bindings from {}, using the defaults: {}", bindings.display(), error),
+            }
+        }
+        controller.bindings_path = Some(bindings);
+        if config.follow_cursor {
+            controller.model.player.movement_mode = MovementMode::CursorFollow;
+        }
+        controller.model.steering = config.steering;
+        controller.model.fog = config.fog;
+        controller.model.tower_vision = config.tower_vision;
+        controller.model.update_visibility();
+
         Self {
             opengl: OPEN_GL_VERSION,
             window_settings: WindowSettings::new("Rust Game", [WINDOW_WIDTH, WINDOW_HEIGHT]).graphics_api(OPEN_GL_VERSION).exit_on_esc(true),
             controller: controller,
+            sprites: config.sprites,
+            screenshot: config.screenshot.map(|(_, path)| path),
         }
 
     }
 
-    /// A function to start the game loop.
-    pub fn run(&mut self) {
+    /// A function to start the game loop. Returns the results of the game, or
+    /// None if the window was closed before the game finished. When taking a
+    /// screenshot, the screenshot is saved instead and None is returned.
+    pub fn run(&mut self) -> Option<GameResults> {
+        if let Some(path) = self.screenshot.clone() {
+            match self.save_screenshot(&path) {
+                Ok(()) => println!("Saved a screenshot to {}", path),
+                Err(error) => println!("Couldn't save a screenshot to {}: {}", path, error),
+            }
+            return None;
+        }
+
         let mut window: GlutinWindow = self.window_settings.build().expect("Couldn't create window!");
-        let mut events = Events::new(EventSettings::new());
+        // The game is updated in fixed steps, and drawn in between them
+        let mut events = Events::new(EventSettings::new().ups(UPDATES_PER_SECOND));
         let mut gl = GlGraphics::new(self.opengl);
+        // The sprites and the font can only be loaded once there's an OpenGL
+        // context. 
+        let mut textures = ViewTextures::new(self.load_sprites());
+        let mut glyphs = match GlyphCache::new(FONT_PATH, (), TextureSettings::new()) {
+            Ok(glyphs) => Some(glyphs),
+            Err(error) => {
+                println!("Couldn't load the font, rebinding won't be shown: {}", error);
+                None
+            },
+        };
 
         while let Some(e) = events.next(&mut window) {
             if !self.controller.check_state() {
@@ -68,14 +135,49 @@ impl Game {
             self.controller.handle_event(&e);
 
             if let Some(args) = e.render_args() {
+                let alpha = (args.ext_dt * UPDATES_PER_SECOND as f64).min(1.0);
                 gl.draw(args.viewport(), |c, g| {
                     use graphics::{clear};
                     
                     clear([1.0; 4], g);
-                    self.controller.view.draw(&self.controller.model, &c, g);
+                    self.controller.view.draw(&self.controller.model, &mut textures, alpha, &c, g);
+                    if self.controller.is_paused() {
+                        self.controller.view.draw_paused(&c, g);
+                        if let (Some(action), Some(glyphs)) = (self.controller.rebinding(), glyphs.as_mut()) {
+                            self.controller.view.draw_rebinding(action, &self.controller.bindings, glyphs, &c, g);
+                        }
+                    }
                 })
             }
         }
+        self.controller.results
     }
-}
 
+    /// Loads the sprites, unless they've been turned off. Without them, 
+    /// everything is drawn as coloured shapes.
+    fn load_sprites<T: LoadTexture>(&self) -> Option<TextureAtlas<T>> {
+        if !self.sprites {
+            return None;
+        }
+        match TextureAtlas::load(SPRITE_SHEET_PATH) {
+            Ok(atlas) => Some(atlas),
+            Err(error) => {
+                println!("Couldn't load sprites, drawing shapes instead: {}", error);
+                None
+            },
+        }
+    }
+
+    /// Draws the game as it would appear in the window, without opening one,
+    /// and saves it to path.
+    fn save_screenshot(&mut self, path: &str) -> io::Result<()> {
+        let mut textures = ViewTextures::new(self.load_sprites());
+        let mut image = SoftwareGraphics::new(WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32);
+        let controller = &mut self.controller;
+        image.draw(|c, g| {
+            graphics::clear([1.0; 4], g);
+            controller.view.draw(&controller.model, &mut textures, 1.0, &c, g);
+        });
+        image.save(path)
+    }
+}
@@ -0,0 +1,129 @@
+use crate::math::Point2;
+use crate::game::consts::{WINDOW_WIDTH, WINDOW_HEIGHT};
+
+/// Which part of the Level is on screen. The Camera keeps whatever it follows
+/// (the Player) in the middle of the window, without showing anything past
+/// the edges of the Level, so a Level which fits in the window never moves.
+///
+/// The Camera can also be panned somewhere else, such as from the Minimap.
+/// It stays there until whatever it follows moves, and then goes back to
+/// following it.
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    position: Point2,
+    anchor: Option<Point2>,
+}
+
+impl Camera {
+
+    /// Creates a Camera showing the top left corner of the Level.
+    pub fn new() -> Self {
+        Self {
+            position: Point2{x: 0.0, y: 0.0},
+            anchor: None,
+        }
+    }
+
+    /// Returns the point of the Level in the top left corner of the screen.
+    pub fn position(&self) -> Point2 {
+        self.position
+    }
+
+    /// Returns the width and height of the part of the Level on screen.
+    pub fn size(&self) -> Point2 {
+        Point2{x: WINDOW_WIDTH, y: WINDOW_HEIGHT}
+    }
+
+    /// Centers the Camera on target, unless it has been panned and target
+    /// hasn't moved since. bounds is the width and height of the Level.
+    pub fn follow(&mut self, target: Point2, bounds: Point2) {
+        if let Some(anchor) = self.anchor {
+            if anchor.x == target.x && anchor.y == target.y {
+                return;
+            }
+            self.anchor = None;
+        }
+        self.center_on(target, bounds);
+    }
+
+    /// Centers the Camera on point until target moves. bounds is the width
+    /// and height of the Level.
+    pub fn pan(&mut self, point: Point2, target: Point2, bounds: Point2) {
+        self.center_on(point, bounds);
+        self.anchor = Some(target);
+    }
+
+    /// Returns true if the Camera has been panned away from what it follows.
+    pub fn is_panned(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// Returns the point of the Level under a point on the screen.
+    pub fn to_world(&self, screen: Point2) -> Point2 {
+        screen + self.position
+    }
+
+    /// Moves the Camera so point is in the middle of the screen, or as close
+    /// as it can be without going past the edges of the Level.
+    fn center_on(&mut self, point: Point2, bounds: Point2) {
+        let size = self.size();
+        let clamp = |value: f64, size: f64, bound: f64| {
+            (value - size / 2.0).min(bound - size).max(0.0)
+        };
+        self.position = Point2{
+            x: clamp(point.x, size.x, bounds.x),
+            y: clamp(point.y, size.y, bounds.y),
+        };
+    }
+
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod camera_tests {
+
+    use super::Camera;
+    use crate::math::Point2;
+    use crate::game::consts::{WINDOW_WIDTH, WINDOW_HEIGHT};
+
+    #[test]
+    fn test_camera_stays_in_level() {
+        let mut camera = Camera::new();
+        let bounds = Point2{x: WINDOW_WIDTH * 3.0, y: WINDOW_HEIGHT * 3.0};
+
+        camera.follow(Point2{x: WINDOW_WIDTH * 1.5, y: 10.0}, bounds);
+        let position = camera.position();
+        assert_eq!((WINDOW_WIDTH, 0.0), (position.x, position.y));
+
+        camera.follow(Point2{x: bounds.x, y: bounds.y}, bounds);
+        let position = camera.position();
+        assert_eq!((WINDOW_WIDTH * 2.0, WINDOW_HEIGHT * 2.0), (position.x, position.y));
+
+        // A Level smaller than the window is never moved
+        camera.follow(Point2{x: 300.0, y: 300.0}, Point2{x: 400.0, y: 400.0});
+        let position = camera.position();
+        assert_eq!((0.0, 0.0), (position.x, position.y));
+    }
+
+    #[test]
+    fn test_pan_until_target_moves() {
+        let mut camera = Camera::new();
+        let bounds = Point2{x: WINDOW_WIDTH * 3.0, y: WINDOW_HEIGHT * 3.0};
+        let target = Point2{x: 0.0, y: 0.0};
+
+        camera.pan(Point2{x: bounds.x, y: bounds.y}, target, bounds);
+        camera.follow(target, bounds);
+        assert!(camera.is_panned());
+        assert_eq!(WINDOW_WIDTH * 2.0, camera.position().x);
+
+        camera.follow(Point2{x: 1.0, y: 0.0}, bounds);
+        assert!(!camera.is_panned());
+        assert_eq!(0.0, camera.position().x);
+    }
+
+}
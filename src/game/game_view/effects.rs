@@ -0,0 +1,185 @@
+use super::particles::{Burst, ParticleSystem};
+use crate::ecs::EntityId;
+use crate::game::{GameEvent, GameModel};
+use crate::math::Point2;
+use crate::game::consts::{
+    MAX_PARTICLES,
+    SPARK_COLOR,
+    DUST_COLOR,
+    ENEMY_COLOR,
+    BEACON_COLOR,
+    SPAWNER_COLOR,
+    HIT_FLASH_DURATION,
+    SHAKE_MAX_OFFSET,
+    SHAKE_DECAY,
+    SHAKE_FREQUENCY,
+    SHAKE_PLAYER_HIT,
+    SHAKE_BEACON_HIT,
+    SHAKE_BEACON_FALLEN,
+    SHAKE_RUBBLE,
+    TILE_SIZE,
+    map_idx_to_point2,
+};
+use std::collections::HashMap;
+
+/// Sparks thrown out when an Enemy is hit.
+const SPARKS: Burst = Burst {
+    count: 4,
+    speed: 6.0 * TILE_SIZE,
+    lifetime: 0.2,
+    colors: (SPARK_COLOR, [SPARK_COLOR[0], SPARK_COLOR[1], SPARK_COLOR[2], 0.0]),
+    sizes: (3.0, 1.0),
+};
+
+/// The remains of a killed Enemy.
+const GIBS: Burst = Burst {
+    count: 12,
+    speed: 4.0 * TILE_SIZE,
+    lifetime: 0.5,
+    colors: (ENEMY_COLOR, [ENEMY_COLOR[0], ENEMY_COLOR[1], ENEMY_COLOR[2], 0.0]),
+    sizes: (5.0, 1.0),
+};
+
+/// Shards knocked off a damaged Beacon.
+const SHARDS: Burst = Burst {
+    count: 8,
+    speed: 5.0 * TILE_SIZE,
+    lifetime: 0.4,
+    colors: (BEACON_COLOR, [BEACON_COLOR[0], BEACON_COLOR[1], BEACON_COLOR[2], 0.0]),
+    sizes: (4.0, 1.0),
+};
+
+/// Dust thrown up when a spawner is destroyed or a wall collapses.
+const DUST: Burst = Burst {
+    count: 16,
+    speed: 3.0 * TILE_SIZE,
+    lifetime: 0.8,
+    colors: (SPAWNER_COLOR, [DUST_COLOR[0], DUST_COLOR[1], DUST_COLOR[2], 0.0]),
+    sizes: (4.0, 8.0),
+};
+
+/// Shakes the screen by an amount which builds up with each hit (its
+/// trauma, from 0 to 1) and wears off over time. The offset grows with the
+/// square of the trauma, so small hits barely move the screen while big ones
+/// throw it about.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScreenShake {
+    trauma: f64,
+    time: f64,
+}
+
+impl ScreenShake {
+
+    /// Adds trauma to the ScreenShake, up to a maximum of 1.
+    pub fn add(&mut self, trauma: f64) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+
+    /// Wears off the ScreenShake's trauma.
+    pub fn tick(&mut self, dt: f64) {
+        self.trauma = (self.trauma - SHAKE_DECAY * dt).max(0.0);
+        self.time += dt;
+    }
+
+    /// Returns how far the screen should be moved this frame.
+    pub fn offset(&self) -> Point2 {
+        let amount = SHAKE_MAX_OFFSET * self.trauma * self.trauma;
+        let phase = self.time * SHAKE_FREQUENCY;
+        Point2 {
+            x: amount * phase.sin(),
+            y: amount * (phase * 1.3 + 0.7).cos(),
+        }
+    }
+
+}
+
+/// The GameView's visual effects: particles, screen shake and the white
+/// flash of an Enemy which has just been hit. Effects are started by the
+/// GameEvents of each tick, and have no effect on the game itself.
+pub struct Effects {
+    pub particles: ParticleSystem,
+    pub shake: ScreenShake,
+    flashes: HashMap<EntityId, f64>,
+}
+
+impl Effects {
+
+    /// Creates Effects with nothing going on.
+    pub fn new() -> Self {
+        Self {
+            particles: ParticleSystem::new(MAX_PARTICLES),
+            shake: ScreenShake::default(),
+            flashes: HashMap::new(),
+        }
+    }
+
+    /// Starts the effects for each of the GameEvents of the last tick.
+    pub fn react(&mut self, model: &GameModel) {
+        for event in model.events.iter() {
+            match *event {
+                GameEvent::EnemyHit{id, position} => {
+                    self.flashes.insert(id, HIT_FLASH_DURATION);
+                    self.particles.emit(&SPARKS, position);
+                },
+                GameEvent::EnemyKilled{position, ..} => self.particles.emit(&GIBS, position),
+                GameEvent::BeaconDamaged{beacon, ..} => {
+                    self.shake.add(SHAKE_BEACON_HIT);
+                    self.particles.emit(&SHARDS, model.beacons[beacon].shape.center_point());
+                },
+                GameEvent::BeaconFallen{..} => self.shake.add(SHAKE_BEACON_FALLEN),
+                GameEvent::PlayerDamaged{..} => self.shake.add(SHAKE_PLAYER_HIT),
+                GameEvent::SpawnerDestroyed{idx} | GameEvent::WallCollapsed{idx} => {
+                    self.shake.add(SHAKE_RUBBLE);
+                    let center = Point2{x: TILE_SIZE / 2.0, y: TILE_SIZE / 2.0};
+                    self.particles.emit(&DUST, map_idx_to_point2(idx) + center);
+                },
+                _ => (),
+            }
+        }
+    }
+
+    /// Moves the effects on by dt seconds.
+    pub fn tick(&mut self, dt: f64) {
+        self.particles.tick(dt);
+        self.shake.tick(dt);
+        for remaining in self.flashes.values_mut() {
+            *remaining -= dt;
+        }
+        self.flashes.retain(|_, remaining| *remaining > 0.0);
+    }
+
+    /// Returns true if the entity was hit recently enough to be flashing.
+    pub fn is_flashing(&self, id: EntityId) -> bool {
+        self.flashes.contains_key(&id)
+    }
+
+}
+
+impl Default for Effects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod effects_tests {
+
+    use super::ScreenShake;
+
+    #[test]
+    fn test_shake_wears_off() {
+        let mut shake = ScreenShake::default();
+        shake.add(0.7);
+        shake.add(0.7);
+        assert_eq!(1.0, shake.trauma);
+
+        shake.tick(0.1);
+        let offset = shake.offset();
+        assert!(offset.x != 0.0 || offset.y != 0.0);
+
+        shake.tick(1.0);
+        let offset = shake.offset();
+        assert_eq!((0.0, 0.0), (offset.x, offset.y));
+    }
+
+}
@@ -0,0 +1,286 @@
+use super::sprites::{draw_sprite, tile_sprite, TextureAtlas};
+use crate::game::{GameEvent, GameModel};
+use crate::levels::map::MapIdx;
+use crate::game::consts::{LEVEL_CHUNK_SIZE, TILE_SIZE, map_idx_to_point2};
+use graphics::{Context, Graphics, ImageSize, BACK_END_MAX_VERTEX_COUNT};
+use graphics::math::Matrix2d;
+use graphics::triangulation::{rect_tri_list_uv, tx, ty};
+use graphics::types::Color;
+
+/// The most vertices handed to the Graphics backend at once: as many whole
+/// triangles as it can take.
+const BATCH_SIZE: usize = BACK_END_MAX_VERTEX_COUNT / 3 * 3;
+
+/// The Tiles of a square chunk of the Level, batched into a list of
+/// triangles for each colour and a list of textured triangles for the
+/// sprites. Animated sprites change every frame, so their Tiles are drawn
+/// one at a time instead.
+#[derive(Default)]
+struct Chunk {
+    shapes: Vec<(Color, Vec<[f32; 2]>)>,
+    sprites: Vec<[f32; 2]>,
+    uvs: Vec<[f32; 2]>,
+    animated: Vec<MapIdx>,
+    dirty: bool,
+}
+
+/// The Tiles of a Level, batched into a few lists of triangles for each
+/// chunk of LEVEL_CHUNK_SIZE by LEVEL_CHUNK_SIZE Tiles, so the Level can be
+/// drawn with a handful of draw calls rather than one for each Tile. A chunk
+/// is only rebuilt when one of its Tiles changes, and only the chunks on
+/// screen are drawn.
+///
+/// The triangles are kept in the Level's coordinates and moved onto the
+/// screen as they're drawn, so the Camera can move without anything being
+/// rebuilt.
+pub struct LevelBatch {
+    columns: i32,
+    rows: i32,
+    textured: bool,
+    chunks: Vec<Chunk>,
+}
+
+impl LevelBatch {
+
+    /// Creates a LevelBatch which will be built the first time it's updated.
+    pub fn new() -> Self {
+        Self {
+            columns: 0,
+            rows: 0,
+            textured: false,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Marks the chunks of any Tiles which changed during the GameModel's
+    /// last tick to be rebuilt. A wall's sprite depends on its neighbours,
+    /// so the chunks of its neighbours are rebuilt too.
+    pub fn react(&mut self, model: &GameModel) {
+        for event in model.events.iter() {
+            let idx = match *event {
+                GameEvent::TrapBuilt{idx, ..}
+                | GameEvent::SpawnerCreated{idx}
+                | GameEvent::SpawnerDestroyed{idx}
+                | GameEvent::WallCollapsed{idx}
+                | GameEvent::DoorToggled{idx, ..} => idx,
+                _ => continue,
+            };
+            for (dx, dy) in [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)].iter() {
+                if let Some(i) = self.chunk_index(idx.x + dx, idx.y + dy) {
+                    self.chunks[i].dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Rebuilds any chunks which need it, from the sprites if there are any.
+    /// Everything is rebuilt if the Level has changed size, or the sprites
+    /// have been turned on or off.
+    pub fn update<T: ImageSize>(&mut self, model: &GameModel, sprites: Option<&TextureAtlas<T>>) {
+        let level = &model.level;
+        let columns = (level.width + LEVEL_CHUNK_SIZE - 1) / LEVEL_CHUNK_SIZE;
+        let rows = (level.height + LEVEL_CHUNK_SIZE - 1) / LEVEL_CHUNK_SIZE;
+        if columns != self.columns || rows != self.rows || sprites.is_some() != self.textured {
+            self.columns = columns;
+            self.rows = rows;
+            self.textured = sprites.is_some();
+            self.chunks = (0..columns * rows).map(|_| Chunk{dirty: true, ..Chunk::default()}).collect();
+        }
+        for (i, chunk) in self.chunks.iter_mut().enumerate() {
+            if chunk.dirty {
+                let i = i as i32;
+                *chunk = build_chunk(model, sprites, i % columns, i / columns);
+            }
+        }
+    }
+
+    /// Draws the chunks overlapping view, a rectangle of the Level given as
+    /// [x, y, width, height]. Animated sprites are drawn time seconds into
+    /// their Animations.
+    pub fn draw<G: Graphics>(
+        &self,
+        model: &GameModel,
+        view: [f64; 4],
+        sprites: Option<&TextureAtlas<G::Texture>>,
+        time: f64,
+        c: &Context,
+        g: &mut G
+    ) {
+        for chunk in self.visible(view) {
+            for (color, vertices) in chunk.shapes.iter() {
+                draw_triangles(vertices, *color, c, g);
+            }
+            let atlas = match sprites {
+                Some(atlas) => atlas,
+                None => continue,
+            };
+            if !chunk.sprites.is_empty() {
+                draw_textured_triangles(&chunk.sprites, &chunk.uvs, &atlas.texture, c, g);
+            }
+            for idx in chunk.animated.iter() {
+                if let Some(tile) = model.level.map.get(idx) {
+                    let sprite = tile_sprite(&model.level.map, *idx, &tile.variant);
+                    draw_sprite(sprites, sprite, time, &tile.shape, c, g);
+                }
+            }
+        }
+    }
+
+    /// Returns how many chunks overlap view, a rectangle of the Level given
+    /// as [x, y, width, height].
+    pub fn visible_chunks(&self, view: [f64; 4]) -> usize {
+        self.visible(view).count()
+    }
+
+    /// Returns the chunks overlapping view.
+    fn visible(&self, view: [f64; 4]) -> impl Iterator<Item = &Chunk> {
+        let size = LEVEL_CHUNK_SIZE as f64 * TILE_SIZE;
+        let range = |start: f64, length: f64, count: i32| {
+            let first = (start / size).floor().max(0.0) as i32;
+            let last = ((start + length) / size).ceil().min(count as f64) as i32;
+            first..last.max(first)
+        };
+        let (columns, rows) = (range(view[0], view[2], self.columns), range(view[1], view[3], self.rows));
+        let stride = self.columns;
+        rows.flat_map(move |row| columns.clone().map(move |column| (row * stride + column) as usize))
+            .map(move |i| &self.chunks[i])
+    }
+
+    /// Returns the index of the chunk holding the Tile at x, y, if there is
+    /// one.
+    fn chunk_index(&self, x: i32, y: i32) -> Option<usize> {
+        let (column, row) = (x.div_euclid(LEVEL_CHUNK_SIZE), y.div_euclid(LEVEL_CHUNK_SIZE));
+        if x < 0 || y < 0 || column >= self.columns || row >= self.rows {
+            return None;
+        }
+        Some((row * self.columns + column) as usize)
+    }
+
+}
+
+impl Default for LevelBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Batches the Tiles of the chunk at column, row. Tiles are drawn with their
+/// sprites if there are any, falling back to their shapes.
+fn build_chunk<T: ImageSize>(model: &GameModel, sprites: Option<&TextureAtlas<T>>, column: i32, row: i32) -> Chunk {
+    let level = &model.level;
+    let mut chunk = Chunk::default();
+    let (start_x, start_y) = (column * LEVEL_CHUNK_SIZE, row * LEVEL_CHUNK_SIZE);
+    for y in start_y..(start_y + LEVEL_CHUNK_SIZE).min(level.height) {
+        for x in start_x..(start_x + LEVEL_CHUNK_SIZE).min(level.width) {
+            let idx = MapIdx::new(x, y);
+            let tile = match level.map.get(&idx) {
+                Some(tile) => tile,
+                None => continue,
+            };
+            let vertices = tile_vertices(idx);
+            if let Some(atlas) = sprites {
+                let sprite = tile_sprite(&level.map, idx, &tile.variant);
+                if atlas.sheet.is_animated(sprite) {
+                    chunk.animated.push(idx);
+                    continue;
+                }
+                if let Some(source) = atlas.sheet.frame(sprite, 0.0) {
+                    chunk.sprites.extend_from_slice(&vertices);
+                    chunk.uvs.extend_from_slice(&rect_tri_list_uv(&atlas.texture, source));
+                    continue;
+                }
+            }
+            let color = tile.shape.get_color();
+            match chunk.shapes.iter_mut().find(|(batch_color, _)| *batch_color == color) {
+                Some((_, batch)) => batch.extend_from_slice(&vertices),
+                None => chunk.shapes.push((color, vertices.to_vec())),
+            }
+        }
+    }
+    chunk
+}
+
+/// Returns the two triangles covering the Tile at idx, in the same order as
+/// a Rectangle's.
+pub fn tile_vertices(idx: MapIdx) -> [[f32; 2]; 6] {
+    let position = map_idx_to_point2(idx);
+    let (x, y) = (position.x as f32, position.y as f32);
+    let (x2, y2) = (x + TILE_SIZE as f32, y + TILE_SIZE as f32);
+    [[x, y], [x2, y], [x, y2], [x2, y], [x2, y2], [x, y2]]
+}
+
+/// Draws a list of triangles in one colour.
+pub fn draw_triangles<G: Graphics>(vertices: &[[f32; 2]], color: Color, c: &Context, g: &mut G) {
+    let mut buffer = [[0.0; 2]; BATCH_SIZE];
+    g.tri_list(&c.draw_state, &color, |f| {
+        for part in vertices.chunks(BATCH_SIZE) {
+            transform(part, c.transform, &mut buffer);
+            f(&buffer[..part.len()]);
+        }
+    });
+}
+
+/// Draws a list of triangles textured from texture, where uvs gives the
+/// point of the texture at each vertex.
+fn draw_textured_triangles<G: Graphics>(vertices: &[[f32; 2]], uvs: &[[f32; 2]], texture: &G::Texture, c: &Context, g: &mut G) {
+    let mut buffer = [[0.0; 2]; BATCH_SIZE];
+    g.tri_list_uv(&c.draw_state, &[1.0; 4], texture, |f| {
+        for (part, uvs) in vertices.chunks(BATCH_SIZE).zip(uvs.chunks(BATCH_SIZE)) {
+            transform(part, c.transform, &mut buffer);
+            f(&buffer[..part.len()], uvs);
+        }
+    });
+}
+
+/// Moves vertices onto the screen by the transform, into buffer.
+fn transform(vertices: &[[f32; 2]], m: Matrix2d, buffer: &mut [[f32; 2]]) {
+    for (vertex, out) in vertices.iter().zip(buffer.iter_mut()) {
+        let (x, y) = (vertex[0] as f64, vertex[1] as f64);
+        *out = [tx(m, x, y), ty(m, x, y)];
+    }
+}
+
+#[cfg(test)]
+mod level_batch_tests {
+
+    use super::LevelBatch;
+    use crate::game::{GameEvent, GameModel, Rules, TextureAtlas};
+    use crate::levels::Level;
+    use crate::levels::map::MapIdx;
+    use crate::math::random::create_seed;
+    use crate::render::SoftwareTexture;
+
+    #[test]
+    fn test_chunks_rebuilt_and_culled() {
+        let seed = create_seed(true);
+        let mut rows = vec!["#".repeat(40); 20];
+        rows[1] = format!("#B.P{}", ".".repeat(35));
+        let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+        let mut model = GameModel::with_rules(Level::from_ascii(&rows, seed), seed, Rules::default()).unwrap();
+
+        let mut batch = LevelBatch::new();
+        batch.update::<SoftwareTexture>(&model, None);
+        assert_eq!((3, 2), (batch.columns, batch.rows));
+        assert!(batch.chunks.iter().all(|chunk| !chunk.dirty));
+
+        // Only the chunks either side of the wall are rebuilt
+        model.events.clear();
+        model.events.push(GameEvent::WallCollapsed{idx: MapIdx::new(16, 5)});
+        batch.react(&model);
+        let dirty: Vec<bool> = batch.chunks.iter().map(|chunk| chunk.dirty).collect();
+        assert_eq!(vec![true, true, false, false, false, false], dirty);
+
+        // Chunks are 320 pixels across
+        assert_eq!(1, batch.visible_chunks([0.0, 0.0, 100.0, 100.0]));
+        assert_eq!(2, batch.visible_chunks([300.0, 0.0, 100.0, 100.0]));
+        assert_eq!(6, batch.visible_chunks([-50.0, -50.0, 2000.0, 2000.0]));
+        assert_eq!(0, batch.visible_chunks([1000.0, 0.0, 100.0, 100.0]));
+
+        // Turning on sprites rebuilds everything
+        let sprites = TextureAtlas::<SoftwareTexture>::load(crate::game::consts::SPRITE_SHEET_PATH).unwrap();
+        batch.update(&model, Some(&sprites));
+        assert!(batch.textured);
+        assert!(batch.chunks.iter().all(|chunk| !chunk.dirty && !chunk.sprites.is_empty()));
+    }
+
+}
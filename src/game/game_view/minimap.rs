@@ -0,0 +1,234 @@
+use super::camera::Camera;
+use crate::game::{GameEvent, GameModel};
+use crate::levels::map::MapIdx;
+use crate::math::Point2;
+use crate::traits::draw::{Draw, GenericShape, ShapeVariant};
+use crate::game::consts::{
+    MINIMAP_SIZE,
+    MINIMAP_DOT_SIZE,
+    MINIMAP_VIEW_COLOR,
+    MINIMAP_ENEMY_COLOR,
+    MINIMAP_SPAWNER_COLOR,
+    HUD_MARGIN,
+    HUD_BACKGROUND_COLOR,
+    WINDOW_WIDTH,
+    TILE_SIZE,
+    FOG_COLOR,
+    PLAYER_COLOR,
+    BEACON_COLOR,
+    BEACON_FALLEN_COLOR,
+    TOWER_COLOR,
+};
+use graphics::{Context, Graphics, Image, Rectangle};
+use graphics::types::Color;
+use image::{Rgba, RgbaImage};
+
+/// A small map of the whole Level in the top right corner of the screen,
+/// showing the Player, the Beacons, Towers, spawners and any Enemies which
+/// can be seen as dots, with the part of the Level on screen outlined.
+///
+/// The Level itself is drawn into an image with a pixel for each Tile, which
+/// is only redrawn when a Tile changes or more of the Level is explored. The
+/// image's revision goes up each time it's redrawn, so the texture it's
+/// drawn from only has to be updated when the revision changes.
+pub struct Minimap {
+    image: RgbaImage,
+    revision: u64,
+    dirty: bool,
+    explored: usize,
+    fog: bool,
+}
+
+impl Minimap {
+
+    /// Creates a Minimap which will be drawn the first time it's updated.
+    pub fn new() -> Self {
+        Self {
+            image: RgbaImage::new(0, 0),
+            revision: 0,
+            dirty: true,
+            explored: 0,
+            fog: false,
+        }
+    }
+
+    /// Marks the Minimap to be redrawn if any Tiles changed during the
+    /// GameModel's last tick.
+    pub fn react(&mut self, model: &GameModel) {
+        let changed = model.events.iter().any(|event| matches!(event,
+            GameEvent::TrapBuilt{..}
+            | GameEvent::SpawnerCreated{..}
+            | GameEvent::SpawnerDestroyed{..}
+            | GameEvent::WallCollapsed{..}
+            | GameEvent::DoorToggled{..}
+        ));
+        self.dirty |= changed;
+    }
+
+    /// Redraws the Minimap's image if any Tiles have changed or been
+    /// explored since it was last drawn.
+    pub fn update(&mut self, model: &GameModel) {
+        let explored = model.visibility.explored_count();
+        let level = &model.level;
+        let resized = self.image.dimensions() != (level.width as u32, level.height as u32);
+        if !(self.dirty || resized || explored != self.explored || model.fog != self.fog) {
+            return;
+        }
+
+        let mut image = RgbaImage::new(level.width as u32, level.height as u32);
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let idx = MapIdx::new(x as i32, y as i32);
+            let color = if model.fog && !model.visibility.is_explored(&idx) {
+                FOG_COLOR
+            } else {
+                match level.map.get(&idx) {
+                    Some(tile) => tile.shape.get_color(),
+                    None => [0.0; 4],
+                }
+            };
+            *pixel = to_rgba(color);
+        }
+        self.image = image;
+        self.revision += 1;
+        self.dirty = false;
+        self.explored = explored;
+        self.fog = model.fog;
+    }
+
+    /// Returns the image of the Level, with a pixel for each Tile.
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    /// Returns how many times the image has been redrawn.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns where the Minimap of the GameModel's Level is drawn on the
+    /// screen, as [x, y, width, height]. The longest side of the Level is
+    /// MINIMAP_SIZE across.
+    pub fn rect(&self, model: &GameModel) -> [f64; 4] {
+        let scale = self.scale(model);
+        let width = model.level.width as f64 * TILE_SIZE * scale;
+        let height = model.level.height as f64 * TILE_SIZE * scale;
+        [WINDOW_WIDTH - HUD_MARGIN - width, HUD_MARGIN, width, height]
+    }
+
+    /// Returns the point of the Level under a point on the screen, or None
+    /// if the point isn't on the Minimap.
+    pub fn to_world(&self, model: &GameModel, screen: Point2) -> Option<Point2> {
+        let [x, y, width, height] = self.rect(model);
+        if screen.x < x || screen.y < y || screen.x >= x + width || screen.y >= y + height {
+            return None;
+        }
+        Some((screen - Point2{x, y}) * (1.0 / self.scale(model)))
+    }
+
+    /// Draws the Minimap from texture, a copy of its image, with a dot for
+    /// everything of interest and an outline of what the Camera can see.
+    pub fn draw<G: Graphics>(&self, model: &GameModel, camera: &Camera, texture: &G::Texture, c: &Context, g: &mut G) {
+        let rect = self.rect(model);
+        let [x, y, width, height] = rect;
+        let border = 2.0;
+        Rectangle::new(HUD_BACKGROUND_COLOR)
+            .draw([x - border, y - border, width + border * 2.0, height + border * 2.0], &c.draw_state, c.transform, g);
+        Image::new().rect(rect).draw(texture, &c.draw_state, c.transform, g);
+
+        let scale = self.scale(model);
+        let dot = |point: Point2, color: Color, g: &mut G| {
+            let position = Point2{x, y} + point * scale - Point2{x: MINIMAP_DOT_SIZE / 2.0, y: MINIMAP_DOT_SIZE / 2.0};
+            GenericShape::new(
+                ShapeVariant::Rect{width: MINIMAP_DOT_SIZE, height: MINIMAP_DOT_SIZE},
+                color,
+                position
+            ).draw(c, g);
+        };
+        for spawner in model.spawners.iter() {
+            if !model.fog || model.visibility.is_explored(&spawner.idx) {
+                dot(spawner.shape.center_point(), MINIMAP_SPAWNER_COLOR, g);
+            }
+        }
+        for (_, tower) in model.world.towers.iter() {
+            dot(tower.base_shape.center_point(), TOWER_COLOR, g);
+        }
+        for beacon in model.beacons.iter() {
+            let color = if beacon.is_fallen() { BEACON_FALLEN_COLOR } else { BEACON_COLOR };
+            dot(beacon.shape.center_point(), color, g);
+        }
+        let world = &model.world;
+        for (id, _) in world.enemies.iter() {
+            match world.shapes.get(id) {
+                Some(shape) if model.can_see(shape.center_point()) => dot(shape.center_point(), MINIMAP_ENEMY_COLOR, g),
+                _ => (),
+            }
+        }
+        dot(model.player.shape.center_point(), PLAYER_COLOR, g);
+
+        // Outline the part of the Level on screen, cut down to the Minimap
+        let start = Point2{x, y} + camera.position() * scale;
+        let end = start + camera.size() * scale;
+        let (left, top) = (start.x.max(x), start.y.max(y));
+        let (right, bottom) = (end.x.min(x + width), end.y.min(y + height));
+        Rectangle::new_border(MINIMAP_VIEW_COLOR, 0.5)
+            .draw([left, top, right - left, bottom - top], &c.draw_state, c.transform, g);
+    }
+
+    /// Returns how many pixels of the Minimap there are to each pixel of the
+    /// GameModel's Level.
+    fn scale(&self, model: &GameModel) -> f64 {
+        let longest = model.level.width.max(model.level.height).max(1) as f64;
+        MINIMAP_SIZE / (longest * TILE_SIZE)
+    }
+
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converts a Color to the bytes of a pixel.
+fn to_rgba(color: Color) -> Rgba<u8> {
+    let byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Rgba([byte(color[0]), byte(color[1]), byte(color[2]), byte(color[3])])
+}
+
+#[cfg(test)]
+mod minimap_tests {
+
+    use super::Minimap;
+    use crate::game::{GameEvent, GameModel, Rules};
+    use crate::levels::Level;
+    use crate::levels::map::MapIdx;
+    use crate::math::Point2;
+    use crate::math::random::create_seed;
+
+    #[test]
+    fn test_minimap_redrawn_when_tiles_change() {
+        let seed = create_seed(true);
+        let rows = ["######", "#B.P.#", "#S...#", "######"];
+        let mut model = GameModel::with_rules(Level::from_ascii(&rows, seed), seed, Rules::default()).unwrap();
+        model.fog = false;
+        let mut minimap = Minimap::new();
+
+        minimap.update(&model);
+        assert_eq!((6, 4), minimap.image().dimensions());
+        assert_eq!(1, minimap.revision());
+        minimap.update(&model);
+        assert_eq!(1, minimap.revision());
+
+        model.events.push(GameEvent::WallCollapsed{idx: MapIdx::new(0, 0)});
+        minimap.react(&model);
+        minimap.update(&model);
+        assert_eq!(2, minimap.revision());
+
+        // The Level is 120 pixels across, so MINIMAP_SIZE across on screen
+        let [x, y, _, _] = minimap.rect(&model);
+        let point = minimap.to_world(&model, Point2{x: x + 50.0, y: y + 10.0}).unwrap();
+        assert!((point.x - 30.0).abs() < 1e-9 && (point.y - 6.0).abs() < 1e-9);
+        assert!(minimap.to_world(&model, Point2{x: x - 1.0, y}).is_none());
+    }
+
+}
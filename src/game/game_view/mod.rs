@@ -0,0 +1,462 @@
+pub use self::camera::Camera;
+pub use self::effects::Effects;
+pub use self::level_batch::LevelBatch;
+pub use self::minimap::Minimap;
+pub use self::sprites::{ImageTexture, LoadTexture, Sprite, SpriteSheet, TextureAtlas};
+mod camera;
+mod effects;
+mod level_batch;
+mod minimap;
+mod particles;
+mod sprites;
+
+use crate::game::{Action, Bindings, GameModel};
+use crate::entity::attack::AttackPhase;
+use crate::entity::beacon::Beacon;
+use crate::entity::abilities::AbilityKind;
+use crate::entity::player::PlayerState;
+use crate::entity::towers::tower::TowerState;
+use crate::math::Point2;
+use crate::traits::draw::{GenericShape, ShapeVariant};
+use crate::game::consts::{
+    WINDOW_WIDTH,
+    WINDOW_HEIGHT,
+    PLAYER_SIZE,
+    SHIELD_COLOR,
+    RECALL_COLOR,
+    HUD_MARGIN,
+    HUD_ICON_SIZE,
+    HUD_BACKGROUND_COLOR,
+    PAUSED_OVERLAY_COLOR,
+    PAUSE_ICON_COLOR,
+    PAUSE_ICON_SIZE,
+    REBIND_FONT_SIZE,
+    REBIND_TEXT_COLOR,
+    DASH_ICON_COLOR,
+    SHIELD_ICON_COLOR,
+    RECALL_ICON_COLOR,
+    BEACON_AURA_COLOR,
+    BEACON_SHIELD_COLOR,
+    BEACON_PULSE_COLOR,
+    BEACON_SIZE,
+    ESCORT_GOAL_COLOR,
+    FOG_COLOR,
+    FOG_EXPLORED_COLOR,
+    TILE_SIZE,
+    HIT_FLASH_COLOR,
+    SPRITE_PHASE_STEP,
+};
+use crate::ecs::systems;
+use self::level_batch::{draw_triangles, tile_vertices};
+use self::sprites::draw_sprite;
+use crate::levels::map::MapIdx;
+use crate::traits::draw::Draw;
+use graphics::{Context, Graphics, ImageSize, Transformed};
+use graphics::character::CharacterCache;
+
+/// The textures a GameView is drawn with, which belong to the Graphics 
+/// backend drawing it: the sprites, if there are any, and a copy of the
+/// Minimap's image, which is only updated when the Minimap is redrawn.
+pub struct ViewTextures<T> {
+    pub sprites: Option<TextureAtlas<T>>,
+    minimap: Option<(T, u64)>,
+}
+
+impl<T: ImageTexture + ImageSize> ViewTextures<T> {
+
+    /// Creates ViewTextures drawing with the sprites, or with shapes if 
+    /// there aren't any.
+    pub fn new(sprites: Option<TextureAtlas<T>>) -> Self {
+        Self {
+            sprites,
+            minimap: None,
+        }
+    }
+
+    /// Returns the texture of the Minimap's image, first bringing it up to
+    /// date if the Minimap has been redrawn since.
+    fn minimap(&mut self, minimap: &Minimap) -> &T {
+        let image = minimap.image();
+        let revision = minimap.revision();
+        match self.minimap {
+            Some((ref mut texture, ref mut current)) if texture.get_size() == image.dimensions() => {
+                if *current != revision {
+                    texture.update_image(image);
+                    *current = revision;
+                }
+            },
+            _ => self.minimap = Some((T::from_image(image), revision)),
+        }
+        &self.minimap.as_ref().unwrap().0
+    }
+
+}
+
+/// A structure responsible for drawing the GameModel, along with the
+/// Effects started by its GameEvents.
+///
+/// The Level, the Player, Enemies and Beacons are drawn with sprites from a
+/// TextureAtlas when there is one, and as coloured shapes otherwise (or when
+/// the TextureAtlas is missing a sprite). Anything flashing is drawn as its
+/// shape, so the flash shows.
+///
+/// The Camera follows the Player around Levels too big for the window, and
+/// the Minimap shows the whole Level. Clicking the Minimap pans the Camera.
+/// The Level's Tiles are drawn from a LevelBatch.
+pub struct GameView {
+    pub effects: Effects,
+    pub camera: Camera,
+    pub minimap: Minimap,
+    level_batch: LevelBatch,
+    time: f64,
+}
+
+impl GameView {
+    
+    /// Creates a new GameView
+    pub fn new() -> Self {
+        Self {
+            effects: Effects::new(),
+            camera: Camera::new(),
+            minimap: Minimap::new(),
+            level_batch: LevelBatch::new(),
+            time: 0.0,
+        }
+    }
+
+    /// Starts the Effects of the GameModel's last tick, and moves every
+    /// Effect and Animation on by dt seconds. The Minimap and LevelBatch
+    /// note any Tiles which changed.
+    pub fn tick(&mut self, dt: f64, model: &GameModel) {
+        self.time += dt;
+        self.effects.react(model);
+        self.effects.tick(dt);
+        self.minimap.react(model);
+        self.level_batch.react(model);
+    }
+
+    /// Pans the Camera to the part of the Level under a point on the screen,
+    /// if the point is on the Minimap. Returns true if the Camera was panned.
+    pub fn pan_minimap(&mut self, model: &GameModel, screen: Point2) -> bool {
+        match self.minimap.to_world(model, screen) {
+            Some(point) => {
+                self.camera.pan(point, model.player.shape.center_point(), level_size(model));
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Draws the GameModel by first drawing the level, then the beacons, the
+    /// resources and towers, the enemies and projectiles, the player, and
+    /// the particles over the top, followed by the HUD and the Minimap. 
+    /// Everything but the HUD and the Minimap is moved by the Camera and the
+    /// screen shake.
+    ///
+    /// Anything which moves is drawn part way between where it was at the 
+    /// start of the last tick and where it is now, alpha being how far 
+    /// through the next tick the frame falls (from 0 to 1). The Camera 
+    /// follows the Player as drawn, so it moves smoothly too.
+    pub fn draw<G: Graphics>(
+        &mut self, 
+        model: &GameModel,
+        textures: &mut ViewTextures<G::Texture>,
+        alpha: f64,
+        c: &Context, 
+        g: &mut G
+    ) where G::Texture: ImageTexture {
+        
+        let player = model.player.shape.interpolate(alpha).center_point();
+        self.camera.follow(player, level_size(model));
+        let shake = self.effects.shake.offset() - self.camera.position();
+        let shaken = &c.trans(shake.x, shake.y);
+        let sprites = textures.sprites.as_ref();
+        let size = self.camera.size();
+        let view = [-shake.x, -shake.y, size.x, size.y];
+        self.level_batch.update(model, sprites);
+        self.draw_level(model, view, sprites, shaken, g);
+        self.draw_spawners(model, shaken, g);
+        self.draw_beacons(model, sprites, alpha, shaken, g);
+        self.draw_resources(model, alpha, shaken, g);
+        self.draw_towers(model, alpha, shaken, g);
+        self.draw_enemies(model, sprites, alpha, shaken, g);
+        self.draw_projectiles(model, alpha, shaken, g);
+        self.draw_player(model, sprites, alpha, shaken, g);
+        self.effects.particles.draw(|position| model.can_see(position), shaken, g);
+        self.draw_hud(model, c, g);
+
+        self.minimap.update(model);
+        let texture = textures.minimap(&self.minimap);
+        self.minimap.draw(model, &self.camera, texture, c, g);
+        
+    }
+
+    /// Draws the part of the GameModel's Level inside view, a rectangle of
+    /// the Level given as [x, y, width, height], from the LevelBatch. While 
+    /// fog is on, Tiles which have been explored but can't be seen are 
+    /// dimmed, and Tiles which have never been seen are covered. The fog
+    /// changes every tick, so it's batched again every frame.
+    fn draw_level<G: Graphics>(&self, model: &GameModel, view: [f64; 4], sprites: Option<&TextureAtlas<G::Texture>>, c: &Context, g: &mut G) {
+        self.level_batch.draw(model, view, sprites, self.time, c, g);
+        if !model.fog {
+            return;
+        }
+
+        let (mut unexplored, mut unseen) = (Vec::new(), Vec::new());
+        let range = |start: f64, length: f64, count: i32| {
+            let first = (start / TILE_SIZE).floor().max(0.0) as i32;
+            let last = ((start + length) / TILE_SIZE).ceil().min(count as f64) as i32;
+            first..last
+        };
+        for h in range(view[1], view[3], model.level.height) {
+            for w in range(view[0], view[2], model.level.width) {
+                let idx = MapIdx::new(w, h);
+                let visibility = &model.visibility;
+                if !visibility.is_explored(&idx) {
+                    unexplored.extend_from_slice(&tile_vertices(idx));
+                } else if !visibility.is_visible(&idx) {
+                    unseen.extend_from_slice(&tile_vertices(idx));
+                }
+            }
+        }
+        draw_triangles(&unexplored, FOG_COLOR, c, g);
+        draw_triangles(&unseen, FOG_EXPLORED_COLOR, c, g);
+
+    }
+
+    /// Draws the Player of the GameModel. If the player is attacking, the 
+    /// Player's weapon is drawn as well. An active Shield or Recall is drawn
+    /// as a ring around the Player.
+    fn draw_player<G: Graphics>(&self, model: &GameModel, sprites: Option<&TextureAtlas<G::Texture>>, alpha: f64, c: &Context, g: &mut G) {
+        // Draw the weapon where it's swung to, and everything else where the
+        // Player has got to
+        let weapon = c;
+        let lag = lag(&model.player.shape, alpha);
+        let c = &c.trans(lag.x, lag.y);
+        let ring = |color| {
+            let size = PLAYER_SIZE * 1.6;
+            GenericShape::new(
+                ShapeVariant::Circle{size, radius: size / 2.0},
+                color,
+                model.player.shape.center_point() - Point2{x: size / 2.0, y: size / 2.0}
+            )
+        };
+        if model.player.is_shielded() {
+            ring(SHIELD_COLOR).draw(c, g);
+        }
+        if let PlayerState::Channelling = model.player.state {
+            ring(RECALL_COLOR).draw(c, g);
+        }
+
+        // Draw the player
+        let sprites = sprites.filter(|_| !model.player.health.is_flashing());
+        draw_sprite(sprites, "player", self.time, &model.player.shape, c, g);
+        model.player.health_bar.draw(c, g);
+        // Draw the player's weapon while a Swing is underway. 
+        if model.player.attack.phase != AttackPhase::Ready {
+            model.player.attack.shape.interpolate(alpha).draw(weapon, g);
+        }
+
+    }
+
+    /// Draws each of the GameModel's Beacons, and the destination of the 
+    /// Escorted Beacon. Fallen Beacons are drawn greyed out, without a 
+    /// HealthBar.
+    fn draw_beacons<G: Graphics>(&self, model: &GameModel, sprites: Option<&TextureAtlas<G::Texture>>, alpha: f64, c: &Context, g: &mut G) {
+        if let Some(goal) = model.escort_path.last() {
+            let size = BEACON_SIZE * 1.5;
+            GenericShape::new(
+                ShapeVariant::Rect{width: size, height: size},
+                ESCORT_GOAL_COLOR,
+                *goal - Point2{x: size / 2.0, y: size / 2.0}
+            ).draw(c, g);
+        }
+        for beacon in model.beacons.iter() {
+            let lag = lag(&beacon.shape, alpha);
+            let c = &c.trans(lag.x, lag.y);
+            if beacon.is_fallen() {
+                beacon.shape.draw(c, g);
+            } else {
+                self.draw_beacon(beacon, sprites, c, g);
+            }
+        }
+    }
+
+    /// Draws a Beacon. The Beacon's upgrades are drawn as rings around it: 
+    /// the Aura's healing radius, a charged Shield, and the Pulse for a 
+    /// moment after it fires.
+    fn draw_beacon<G: Graphics>(&self, beacon: &Beacon, sprites: Option<&TextureAtlas<G::Texture>>, c: &Context, g: &mut G) {
+        let ring = |radius: f64, color| {
+            GenericShape::new(
+                ShapeVariant::Circle{size: radius * 2.0, radius},
+                color,
+                beacon.shape.center_point() - Point2{x: radius, y: radius}
+            )
+        };
+        if beacon.upgrades.aura > 0 {
+            ring(beacon.aura_radius(), BEACON_AURA_COLOR).draw(c, g);
+        }
+        if beacon.is_pulsing() {
+            ring(beacon.pulse_radius(), BEACON_PULSE_COLOR).draw(c, g);
+        }
+        if beacon.shield_hits > 0 {
+            ring(BEACON_SIZE, BEACON_SHIELD_COLOR).draw(c, g);
+        }
+
+        let sprites = sprites.filter(|_| !beacon.health.is_flashing());
+        draw_sprite(sprites, "beacon", self.time, &beacon.shape, c, g);
+        beacon.health_bar.draw(c, g);
+    }
+
+    /// Draws the HealthBar of each spawner which has been damaged. The 
+    /// spawners themselves are drawn as part of the Level.
+    fn draw_spawners<G: Graphics>(&self, model: &GameModel, c: &Context, g: &mut G) {
+        for spawner in model.spawners.iter() {
+            if spawner.health_bar.fraction() < 1.0 {
+                spawner.health_bar.draw(c, g);
+            }
+        }
+    }
+
+    /// Draws each Enemy in the World which the Player can see. Enemies which
+    /// have just been hit flash white. Each Enemy's Animation is a little
+    /// ahead of the last, so they don't all move in step.
+    fn draw_enemies<G: Graphics>(&self, model: &GameModel, sprites: Option<&TextureAtlas<G::Texture>>, alpha: f64, c: &Context, g: &mut G) {
+        let world = &model.world;
+        for (id, _) in world.enemies.iter() {
+            let mut shape = match world.shapes.get(id) {
+                Some(shape) if model.can_see(shape.center_point()) => shape.interpolate(alpha),
+                _ => continue,
+            };
+            if self.effects.is_flashing(id) {
+                shape.set_color(HIT_FLASH_COLOR);
+                shape.draw(c, g);
+            } else {
+                let time = self.time + id.index() as f64 * SPRITE_PHASE_STEP;
+                draw_sprite(sprites, "enemy", time, &shape, c, g);
+            }
+        }
+    }
+
+    /// Draws each of the Player's projectiles
+    fn draw_projectiles<G: Graphics>(&self, model: &GameModel, alpha: f64, c: &Context, g: &mut G) {
+        let world = &model.world;
+        systems::render(&world.shapes, &world.projectiles, |_| true, alpha, c, g);
+    }
+
+    /// Draws each Resource in the World
+    fn draw_resources<G: Graphics>(&self, model: &GameModel, alpha: f64, c: &Context, g: &mut G) {
+
+        let world = &model.world;
+        systems::render(&world.shapes, &world.pickups, |_| true, alpha, c, g);
+
+    }
+
+    /// Draws the HUD in the bottom left corner of the screen. Each of the 
+    /// Player's Abilities is drawn as an icon which fills up as the Ability's
+    /// cooldown passes.
+    fn draw_hud<G: Graphics>(&self, model: &GameModel, c: &Context, g: &mut G) {
+        for (i, ability) in model.player.abilities.iter().enumerate() {
+            let position = Point2{
+                x: HUD_MARGIN + i as f64 * (HUD_ICON_SIZE + HUD_MARGIN),
+                y: WINDOW_HEIGHT - HUD_MARGIN - HUD_ICON_SIZE,
+            };
+            GenericShape::new(
+                ShapeVariant::Rect{width: HUD_ICON_SIZE, height: HUD_ICON_SIZE},
+                HUD_BACKGROUND_COLOR,
+                position
+            ).draw(c, g);
+
+            let color = match ability.kind {
+                AbilityKind::Dash => DASH_ICON_COLOR,
+                AbilityKind::Shield => SHIELD_ICON_COLOR,
+                AbilityKind::Recall => RECALL_ICON_COLOR,
+            };
+            let fill = HUD_ICON_SIZE * ability.readiness();
+            GenericShape::new(
+                ShapeVariant::Rect{width: HUD_ICON_SIZE, height: fill},
+                color,
+                position + Point2{x: 0.0, y: HUD_ICON_SIZE - fill}
+            ).draw(c, g);
+        }
+    }
+
+    /// Dims the whole screen and draws a pause symbol in the middle of it,
+    /// over whatever was drawn last.
+    pub fn draw_paused<G: Graphics>(&self, c: &Context, g: &mut G) {
+        GenericShape::new(
+            ShapeVariant::Rect{width: WINDOW_WIDTH, height: WINDOW_HEIGHT},
+            PAUSED_OVERLAY_COLOR,
+            Point2{x: 0.0, y: 0.0}
+        ).draw(c, g);
+
+        let bar = PAUSE_ICON_SIZE / 3.0;
+        let corner = Point2{x: WINDOW_WIDTH / 2.0, y: WINDOW_HEIGHT / 2.0} - Point2{x: PAUSE_ICON_SIZE / 2.0, y: PAUSE_ICON_SIZE / 2.0};
+        for x in [0.0, bar * 2.0].iter() {
+            GenericShape::new(
+                ShapeVariant::Rect{width: bar, height: PAUSE_ICON_SIZE},
+                PAUSE_ICON_COLOR,
+                corner + Point2{x: *x, y: 0.0}
+            ).draw(c, g);
+        }
+    }
+
+    /// Writes which Action is being rebound and its current Bindings under 
+    /// the pause symbol, along with how to bind it or move on to the next
+    /// Action. Drawn over draw_paused() with the glyphs of a font.
+    pub fn draw_rebinding<C, G>(&self, action: Action, bindings: &Bindings, glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache,
+        G: Graphics<Texture = C::Texture>,
+    {
+        let current = match bindings.names(action) {
+            names if names.is_empty() => "nothing".to_string(),
+            names => names,
+        };
+        // Rebind is the last Action, and its own Bindings are bound to it
+        // too while it's chosen.
+        let hint = match action {
+            Action::Rebind => "Press a key or mouse button to bind it".to_string(),
+            _ => format!("Press a key or mouse button to bind it, or {} for the next action", bindings.names(Action::Rebind)),
+        };
+        let lines = [format!("Rebinding {}, bound to {}", action.name(), current), hint];
+        let size = REBIND_FONT_SIZE as f64;
+        let mut y = WINDOW_HEIGHT / 2.0 + PAUSE_ICON_SIZE + size;
+        for line in lines.iter() {
+            let width = glyphs.width(REBIND_FONT_SIZE, line).unwrap_or(0.0);
+            let transform = c.transform.trans((WINDOW_WIDTH - width) / 2.0, y);
+            // A glyph which can't be drawn leaves a gap, rather than 
+            // hiding the rest of the pause screen.
+            graphics::text(REBIND_TEXT_COLOR, REBIND_FONT_SIZE, line, glyphs, transform, g).ok();
+            y += size * 1.5;
+        }
+    }
+
+    /// Draws each Tower in the World, and the bullet of each Tower which is
+    /// attacking.
+    fn draw_towers<G: Graphics>(&self, model: &GameModel, alpha: f64, c: &Context, g: &mut G){
+
+        for (_, tower) in model.world.towers.iter() {
+            tower.base_shape.draw(c, g);
+            tower.cannon_shape.interpolate(alpha).draw(c, g);
+            if let TowerState::Attacking = tower.state {
+                tower.bullet.shape.interpolate(alpha).draw(c, g);
+            }
+        }
+
+    }
+}
+
+/// Returns the width and height of the GameModel's Level.
+fn level_size(model: &GameModel) -> Point2 {
+    Point2{
+        x: model.level.width as f64 * TILE_SIZE,
+        y: model.level.height as f64 * TILE_SIZE,
+    }
+}
+
+/// Returns how far behind where a shape is now it's drawn, alpha of the way
+/// between ticks. Anything attached to the shape is moved by the same amount.
+fn lag(shape: &GenericShape, alpha: f64) -> Point2 {
+    shape.interpolate(alpha).get_position() - shape.get_position()
+}
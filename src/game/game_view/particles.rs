@@ -0,0 +1,244 @@
+use crate::math::{Point2, Vec2};
+use crate::math::random::{from_seed, next_u32, RNG};
+use crate::game::consts::{PARTICLE_DRAG, PI};
+use graphics::{Context, Graphics, Rectangle};
+use graphics::types::Color;
+
+/// A short lived dot drawn by the GameView. Over its lifetime a Particle
+/// slows down, fades from its start colour to its end colour, and shrinks
+/// (or grows) from its start size to its end size. The size changes quickly
+/// at first and then settles, so Particles pop before they fade out.
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+    pub position: Point2,
+    pub velocity: Vec2,
+    pub age: f64,
+    pub lifetime: f64,
+    pub colors: (Color, Color),
+    pub sizes: (f64, f64),
+}
+
+impl Particle {
+
+    /// Returns how far through its lifetime the Particle is, from 0 to 1.
+    fn progress(&self) -> f64 {
+        (self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+
+    /// Returns the Particle's colour at its current age.
+    pub fn color(&self) -> Color {
+        let t = self.progress() as f32;
+        let (start, end) = self.colors;
+        [
+            start[0] + (end[0] - start[0]) * t,
+            start[1] + (end[1] - start[1]) * t,
+            start[2] + (end[2] - start[2]) * t,
+            start[3] + (end[3] - start[3]) * t,
+        ]
+    }
+
+    /// Returns the Particle's size at its current age.
+    pub fn size(&self) -> f64 {
+        let t = self.progress();
+        let (start, end) = self.sizes;
+        start + (end - start) * t * (2.0 - t)
+    }
+
+}
+
+/// Describes a burst of Particles thrown out in every direction from a
+/// point. Each Particle's speed is between half and all of speed.
+#[derive(Clone, Copy, Debug)]
+pub struct Burst {
+    pub count: usize,
+    pub speed: f64,
+    pub lifetime: f64,
+    pub colors: (Color, Color),
+    pub sizes: (f64, f64),
+}
+
+/// A pool of up to capacity Particles. Live Particles are kept at the front
+/// of the pool; when one dies it's swapped with the last live Particle, and
+/// its slot is reused by the next Particle emitted, so the pool stops
+/// allocating once it's full. Particles emitted while every slot is live are
+/// dropped.
+///
+/// The ParticleSystem has its own random number generator, so effects don't
+/// change the course of the game.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    live: usize,
+    capacity: usize,
+    rng: RNG,
+}
+
+impl ParticleSystem {
+
+    /// Creates an empty ParticleSystem which holds up to capacity Particles.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            particles: Vec::with_capacity(capacity),
+            live: 0,
+            capacity,
+            rng: from_seed([7; 32]),
+        }
+    }
+
+    /// Adds the Particle to the pool. Returns false if the pool is full.
+    pub fn spawn(&mut self, particle: Particle) -> bool {
+        if self.live < self.particles.len() {
+            self.particles[self.live] = particle;
+        } else if self.particles.len() < self.capacity {
+            self.particles.push(particle);
+        } else {
+            return false;
+        }
+        self.live += 1;
+        true
+    }
+
+    /// Throws out a Burst of Particles from position. The Particles are
+    /// spread evenly around a circle, with a little randomness in their
+    /// directions and speeds.
+    pub fn emit(&mut self, burst: &Burst, position: Point2) {
+        for i in 0..burst.count {
+            let jitter = self.random();
+            let angle = 2.0 * PI * (i as f64 + jitter) / burst.count as f64;
+            let speed = burst.speed * (0.5 + self.random() / 2.0);
+            self.spawn(Particle {
+                position,
+                velocity: Vec2::new(angle.cos() * speed, angle.sin() * speed),
+                age: 0.0,
+                lifetime: burst.lifetime,
+                colors: burst.colors,
+                sizes: burst.sizes,
+            });
+        }
+    }
+
+    /// Returns a random number from 0 up to 1.
+    fn random(&mut self) -> f64 {
+        (next_u32(&mut self.rng) % 1_000_000) as f64 / 1_000_000.0
+    }
+
+    /// Ages and moves every live Particle, and frees the slots of those
+    /// which have lived out their lifetimes.
+    pub fn tick(&mut self, dt: f64) {
+        let drag = (1.0 - PARTICLE_DRAG * dt).max(0.0);
+        let mut i = 0;
+        while i < self.live {
+            let particle = &mut self.particles[i];
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                self.live -= 1;
+                self.particles.swap(i, self.live);
+                continue;
+            }
+            let velocity = particle.velocity;
+            particle.position = particle.position + Point2{x: velocity.x * dt, y: velocity.y * dt};
+            particle.velocity = Vec2::new(velocity.x * drag, velocity.y * drag);
+            i += 1;
+        }
+    }
+
+    /// Returns the live Particles.
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles[..self.live]
+    }
+
+    /// Returns the number of live Particles.
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    /// Returns true if there are no live Particles.
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// Draws each live Particle, for which visible returns true, as a dot
+    /// centered on its position.
+    pub fn draw<G: Graphics, F: Fn(Point2) -> bool>(&self, visible: F, c: &Context, g: &mut G) {
+        for particle in self.particles().iter().filter(|particle| visible(particle.position)) {
+            let size = particle.size();
+            Rectangle::new_round(particle.color(), size / 2.0).draw(
+                [particle.position.x - size / 2.0, particle.position.y - size / 2.0, size, size],
+                &c.draw_state,
+                c.transform,
+                g
+            );
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod particle_tests {
+
+    use super::{Burst, Particle, ParticleSystem};
+    use crate::math::{Point2, Vec2};
+
+    const BURST: Burst = Burst {
+        count: 4,
+        speed: 10.0,
+        lifetime: 1.0,
+        colors: ([1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 0.0]),
+        sizes: (4.0, 0.0),
+    };
+
+    #[test]
+    fn test_pool_reuses_slots() {
+        let mut particles = ParticleSystem::new(6);
+        particles.emit(&BURST, Point2{x: 0.0, y: 0.0});
+        particles.emit(&BURST, Point2{x: 0.0, y: 0.0});
+        // The pool is full, so the last two Particles are dropped.
+        assert_eq!(6, particles.len());
+
+        particles.tick(1.0);
+        assert!(particles.is_empty());
+        particles.emit(&BURST, Point2{x: 0.0, y: 0.0});
+        assert_eq!(4, particles.len());
+        assert_eq!(6, particles.particles.len());
+    }
+
+    #[test]
+    fn test_particles_fade_and_shrink() {
+        let mut particle = Particle {
+            position: Point2{x: 0.0, y: 0.0},
+            velocity: Vec2::new(10.0, 0.0),
+            age: 0.0,
+            lifetime: 1.0,
+            colors: BURST.colors,
+            sizes: BURST.sizes,
+        };
+        assert_eq!([1.0, 0.0, 0.0, 1.0], particle.color());
+        assert_eq!(4.0, particle.size());
+
+        particle.age = 0.5;
+        assert_eq!([0.5, 0.0, 0.5, 0.5], particle.color());
+        // The size changes faster at the start.
+        assert!(particle.size() < 2.0);
+
+        particle.age = 1.0;
+        assert_eq!(0.0, particle.color()[3]);
+        assert_eq!(0.0, particle.size());
+    }
+
+    #[test]
+    fn test_particles_move_and_slow_down() {
+        let mut particles = ParticleSystem::new(1);
+        particles.spawn(Particle {
+            position: Point2{x: 0.0, y: 0.0},
+            velocity: Vec2::new(10.0, 0.0),
+            age: 0.0,
+            lifetime: 1.0,
+            colors: BURST.colors,
+            sizes: BURST.sizes,
+        });
+        particles.tick(0.1);
+        let particle = particles.particles()[0];
+        assert_eq!(1.0, particle.position.x);
+        assert!(particle.velocity.x < 10.0);
+    }
+
+}
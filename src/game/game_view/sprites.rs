@@ -0,0 +1,371 @@
+use crate::entity::tile::TileVariant;
+use crate::levels::map::{Map, MapIdx, wall_mask};
+use crate::math::Point2;
+use crate::traits::draw::{Draw, GenericShape, ShapeVariant};
+use graphics::{Context, Graphics, Image, Transformed};
+use graphics::types::SourceRectangle;
+use image::RgbaImage;
+use crate::render::SoftwareTexture;
+use opengl_graphics::{Filter, Texture, TextureSettings};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The names of the wall sprites, indexed by the wall's mask. See wall_mask().
+const WALL_SPRITES: [&str; 16] = [
+    "wall_0", "wall_1", "wall_2", "wall_3",
+    "wall_4", "wall_5", "wall_6", "wall_7",
+    "wall_8", "wall_9", "wall_10", "wall_11",
+    "wall_12", "wall_13", "wall_14", "wall_15",
+];
+
+/// A looping sequence of frames, each shown for frame_time seconds.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub frames: Vec<SourceRectangle>,
+    pub frame_time: f64,
+}
+
+impl Animation {
+
+    /// Returns the frame shown time seconds into the Animation.
+    pub fn frame(&self, time: f64) -> SourceRectangle {
+        let i = (time.max(0.0) / self.frame_time) as usize;
+        self.frames[i % self.frames.len()]
+    }
+
+}
+
+/// Describes where each sprite is in an atlas image. A frame is a rectangle
+/// of the image, and an Animation loops through a list of frames.
+///
+/// # Sprite Sheet Files
+///
+/// A sprite sheet is a TOML file giving the path of the atlas image 
+/// (relative to the sprite sheet), a [frames] table giving each frame as 
+/// [x, y, width, height] in pixels, and an [animations.<name>] table for each
+/// Animation.
+///
+/// ```toml
+/// image = "sprites.png"
+///
+/// [frames]
+/// enemy_0 = [0, 0, 16, 16]
+/// enemy_1 = [16, 0, 16, 16]
+///
+/// [animations.enemy]
+/// frames = ["enemy_0", "enemy_1"]
+/// frame_time = 0.25
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpriteSheet {
+    pub image: PathBuf,
+    frames: HashMap<String, SourceRectangle>,
+    animations: HashMap<String, Animation>,
+}
+
+/// A sprite sheet file as it's written, before its Animations' frames have
+/// been looked up.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SheetFile {
+    image: String,
+    #[serde(default)]
+    frames: HashMap<String, SourceRectangle>,
+    #[serde(default)]
+    animations: HashMap<String, AnimationFile>,
+}
+
+/// An [animations.<name>] table of a sprite sheet file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnimationFile {
+    frames: Vec<String>,
+    frame_time: f64,
+}
+
+impl SpriteSheet {
+
+    /// Parses a sprite sheet. See the Sprite Sheet Files section for the
+    /// format. The image path is left as it's written.
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let file: SheetFile = toml::from_str(contents)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let frames = file.frames;
+
+        let animations = file.animations.into_iter().map(|(name, animation)| {
+            let error = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("animation {}: {}", name, message));
+            if animation.frame_time <= 0.0 {
+                return Err(error("frame_time must be positive"));
+            }
+            if animation.frames.is_empty() {
+                return Err(error("missing frames"));
+            }
+            let sources = animation.frames.iter()
+                .map(|frame| frames.get(frame).copied().ok_or_else(|| error(&format!("unknown frame {}", frame))))
+                .collect::<io::Result<Vec<SourceRectangle>>>()?;
+            Ok((name, Animation{frames: sources, frame_time: animation.frame_time}))
+        }).collect::<io::Result<HashMap<String, Animation>>>()?;
+
+        Ok(Self {
+            image: PathBuf::from(file.image),
+            frames,
+            animations,
+        })
+    }
+
+    /// Loads a sprite sheet file. The image path is made relative to the
+    /// directory the file is in.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut sheet = SpriteSheet::parse(&fs::read_to_string(path)?)?;
+        if let Some(dir) = path.parent() {
+            sheet.image = dir.join(&sheet.image);
+        }
+        Ok(sheet)
+    }
+
+    /// Returns the frame of the sprite called name, time seconds into its
+    /// Animation. Sprites without an Animation always show the frame of the
+    /// same name.
+    pub fn frame(&self, name: &str, time: f64) -> Option<SourceRectangle> {
+        match self.animations.get(name) {
+            Some(animation) => Some(animation.frame(time)),
+            None => self.frames.get(name).copied(),
+        }
+    }
+
+    /// Returns true if the sprite called name has an Animation with more
+    /// than one frame, so it changes over time.
+    pub fn is_animated(&self, name: &str) -> bool {
+        self.animations.get(name).is_some_and(|animation| animation.frames.len() > 1)
+    }
+
+}
+
+/// A texture holding every sprite, and the SpriteSheet saying where each
+/// one is. The texture's type depends on the Graphics backend drawing it.
+pub struct TextureAtlas<T> {
+    pub texture: T,
+    pub sheet: SpriteSheet,
+}
+
+impl<T> TextureAtlas<T> {
+
+    /// Creates a TextureAtlas from an already loaded texture.
+    pub fn new(texture: T, sheet: SpriteSheet) -> Self {
+        Self { texture, sheet }
+    }
+
+    /// Returns the sprite called name, time seconds into its Animation,
+    /// covering shape. Returns None if there's no such sprite.
+    pub fn sprite(&self, name: &str, time: f64, shape: &GenericShape) -> Option<Sprite> {
+        self.sheet.frame(name, time).map(|source| Sprite::new(source, shape))
+    }
+
+}
+
+impl<T: LoadTexture> TextureAtlas<T> {
+
+    /// Loads a sprite sheet file and its image.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let sheet = SpriteSheet::load(path)?;
+        let texture = T::load_texture(&sheet.image)?;
+        Ok(Self::new(texture, sheet))
+    }
+
+}
+
+/// A texture which can be loaded from an image file, for a TextureAtlas.
+pub trait LoadTexture: Sized {
+    fn load_texture(path: &Path) -> io::Result<Self>;
+}
+
+impl LoadTexture for Texture {
+    /// Loads the image as an OpenGL texture. Sprites are scaled up without
+    /// smoothing, to keep the pixels sharp. Can only be called once there's
+    /// an OpenGL context.
+    fn load_texture(path: &Path) -> io::Result<Self> {
+        let settings = TextureSettings::new().filter(Filter::Nearest);
+        Texture::from_path(path, &settings).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl LoadTexture for SoftwareTexture {
+    fn load_texture(path: &Path) -> io::Result<Self> {
+        SoftwareTexture::from_path(path)
+    }
+}
+
+/// A texture which can be made from an image in memory, and updated as the
+/// image changes, such as the Minimap's.
+pub trait ImageTexture: Sized {
+    fn from_image(image: &RgbaImage) -> Self;
+    fn update_image(&mut self, image: &RgbaImage);
+}
+
+impl ImageTexture for Texture {
+    /// Uploads the image as an OpenGL texture, scaled up without smoothing.
+    /// Can only be called once there's an OpenGL context.
+    fn from_image(image: &RgbaImage) -> Self {
+        Texture::from_image(image, &TextureSettings::new().filter(Filter::Nearest))
+    }
+
+    fn update_image(&mut self, image: &RgbaImage) {
+        self.update(image);
+    }
+}
+
+impl ImageTexture for SoftwareTexture {
+    fn from_image(image: &RgbaImage) -> Self {
+        SoftwareTexture::from_image(image)
+    }
+
+    fn update_image(&mut self, image: &RgbaImage) {
+        *self = SoftwareTexture::from_image(image);
+    }
+}
+
+/// A frame of a TextureAtlas drawn in place of a GenericShape. The frame is
+/// stretched over the shape, and moved, rotated and offset the same way.
+#[derive(Clone, Copy, Debug)]
+pub struct Sprite {
+    pub source: SourceRectangle,
+    pub width: f64,
+    pub height: f64,
+    pub position: Point2,
+    pub rotation: Option<f64>,
+    pub offset: Option<Point2>,
+}
+
+impl Sprite {
+
+    /// Creates a Sprite of the source frame covering shape.
+    pub fn new(source: SourceRectangle, shape: &GenericShape) -> Self {
+        let (width, height) = match shape.shape {
+            ShapeVariant::Rect{width, height} => (width, height),
+            ShapeVariant::Circle{size, ..} => (size, size),
+        };
+        Self {
+            source,
+            width,
+            height,
+            position: shape.get_position(),
+            rotation: shape.get_rotation(),
+            offset: shape.get_offset(),
+        }
+    }
+
+    /// Draws the Sprite's frame of texture.
+    pub fn draw<G: Graphics>(&self, texture: &G::Texture, c: &Context, g: &mut G) {
+        let mut transform = c.transform.trans(self.position.x, self.position.y);
+        if let Some(rad) = self.rotation {
+            transform = transform.rot_rad(rad);
+        }
+        if let Some(offset) = self.offset {
+            transform = transform.trans(offset.x, offset.y);
+        }
+        Image::new()
+            .rect([0.0, 0.0, self.width, self.height])
+            .src_rect(self.source)
+            .draw(texture, &c.draw_state, transform, g);
+    }
+
+}
+
+/// Draws the sprite called name over shape, or shape itself if there's no
+/// TextureAtlas or no such sprite in it.
+pub fn draw_sprite<G: Graphics>(
+    atlas: Option<&TextureAtlas<G::Texture>>,
+    name: &str,
+    time: f64,
+    shape: &GenericShape,
+    c: &Context,
+    g: &mut G
+) {
+    match atlas.and_then(|atlas| atlas.sprite(name, time, shape).map(|sprite| (atlas, sprite))) {
+        Some((atlas, sprite)) => sprite.draw(&atlas.texture, c, g),
+        None => shape.draw(c, g),
+    }
+}
+
+/// Returns the name of the sprite of the Tile at idx. Walls are autotiled:
+/// their sprite depends on which of their neighbours are walls too.
+pub fn tile_sprite(map: &Map, idx: MapIdx, variant: &TileVariant) -> &'static str {
+    match variant {
+        TileVariant::Floor => "floor",
+        TileVariant::Wall => WALL_SPRITES[wall_mask(map, idx) as usize],
+        TileVariant::Spawner => "spawner",
+        TileVariant::Mud => "mud",
+        TileVariant::Water => "water",
+        TileVariant::Rubble => "rubble",
+        TileVariant::DestructibleWall{..} => "destructible_wall",
+        TileVariant::Door{open: true} => "door_open",
+        TileVariant::Door{open: false} => "door_closed",
+        TileVariant::SpikeTrap{..} => "spike_trap",
+        TileVariant::SlowTrap => "slow_trap",
+    }
+}
+
+#[cfg(test)]
+mod sprite_tests {
+
+    use super::{SpriteSheet, WALL_SPRITES};
+    use crate::game::consts::SPRITE_SHEET_PATH;
+
+    const SHEET: &str = r#"
+        image = "sprites.png" # The atlas
+
+        [frames]
+        still = [0, 0, 16, 16]
+        walk_0 = [16, 0, 16, 16]
+        walk_1 = [32, 0, 16, 16,]
+
+        [animations.walk]
+        frames = ["walk_0", "walk_1"]
+        frame_time = 0.5
+    "#;
+
+    #[test]
+    fn test_parse_sprite_sheet() {
+        let sheet = SpriteSheet::parse(SHEET).unwrap();
+        assert_eq!("sprites.png", sheet.image.to_str().unwrap());
+        assert_eq!(Some([0.0, 0.0, 16.0, 16.0]), sheet.frame("still", 3.0));
+        assert_eq!(Some([16.0, 0.0, 16.0, 16.0]), sheet.frame("walk", 0.2));
+        assert_eq!(Some([32.0, 0.0, 16.0, 16.0]), sheet.frame("walk", 0.7));
+        // Animations loop
+        assert_eq!(Some([16.0, 0.0, 16.0, 16.0]), sheet.frame("walk", 1.2));
+        assert_eq!(None, sheet.frame("missing", 0.0));
+    }
+
+    #[test]
+    fn test_invalid_sprite_sheets() {
+        let errors = [
+            "image = \"sprites.png\"\n[frames]\nstill = [0, 0, 16]",
+            "image = \"sprites.png\"\n[frames]\nstill = [0, 0, 16, 16]\n[animations.walk]\nframes = [\"run\"]\nframe_time = 1",
+            "image = \"sprites.png\"\n[animations.walk]\nframe_time = 1",
+            "[frames]\nstill = [0, 0, 16, 16]",
+            "image = sprites.png",
+            "[sounds]",
+        ];
+        for sheet in errors.iter() {
+            assert!(SpriteSheet::parse(sheet).is_err(), "{}", sheet);
+        }
+    }
+
+    #[test]
+    fn test_bundled_sprites() {
+        let sheet = SpriteSheet::load(SPRITE_SHEET_PATH).unwrap();
+        assert!(sheet.image.exists());
+        let tiles = [
+            "floor", "spawner", "mud", "water", "rubble", "destructible_wall",
+            "door_open", "door_closed", "spike_trap", "slow_trap",
+        ];
+        for name in tiles.iter().chain(WALL_SPRITES.iter()).chain(["player", "enemy", "beacon"].iter()) {
+            assert!(sheet.frame(name, 0.0).is_some(), "{}", name);
+        }
+    }
+
+}
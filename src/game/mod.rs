@@ -1,9 +1,21 @@
 pub use self::game_struct::Game;
 pub use self::game_model::GameModel;
 pub use self::game_controller::{GameController, GameState};
-pub use self::game_view::GameView;
+pub use self::game_view::{GameView, Camera, Effects, ImageTexture, LevelBatch, LoadTexture, Minimap, Sprite, SpriteSheet, TextureAtlas, ViewTextures};
+pub use self::waves::{Waves, WavePhase};
+pub use self::objective::{Objective, Outcome, Rules, Targeting};
+pub use self::results::{GameResults, Score};
+pub use self::difficulty::Difficulty;
+pub use self::events::GameEvent;
+pub use self::bindings::{Action, Binding, Bindings};
 mod game_struct;
 mod game_model;
 mod game_controller;
 mod game_view;
+mod waves;
+mod objective;
+mod results;
+mod difficulty;
+mod events;
+mod bindings;
 pub mod consts;
\ No newline at end of file
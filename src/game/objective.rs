@@ -0,0 +1,118 @@
+use crate::game::Difficulty;
+
+/// The goal of a game.
+///
+/// While Defending, the game carries on until too many Beacons have fallen.
+/// The other objectives can also be won: by surviving a number of waves, by
+/// holding out for a number of seconds, by Escorting the first Beacon to the
+/// far side of the Level, or by clearing every spawner from the Level. The 
+/// Escorted Beacon only moves while the Player is close to it. While Clearing
+/// Spawners, no new spawners are created, so every spawner destroyed brings 
+/// the Player closer to winning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    Defend,
+    SurviveWaves(u32),
+    Hold(f64),
+    Escort,
+    ClearSpawners,
+}
+
+impl Objective {
+
+    /// Parses an Objective from a command line argument. Valid arguments are
+    /// "defend", "survive:<waves>", "hold:<seconds>", "escort" and "clear".
+    pub fn parse(arg: &str) -> Option<Self> {
+        let mut parts = arg.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some("defend"), None) => Some(Objective::Defend),
+            (Some("escort"), None) => Some(Objective::Escort),
+            (Some("clear"), None) => Some(Objective::ClearSpawners),
+            (Some("survive"), Some(waves)) => waves.parse().ok().map(Objective::SurviveWaves),
+            (Some("hold"), Some(seconds)) => seconds.parse().ok().map(Objective::Hold),
+            _ => None,
+        }
+    }
+
+}
+
+/// How enemies choose which Beacon to attack. Nearest always targets the
+/// Beacon with the shortest path. Weighted picks a Beacon at random, with
+/// closer Beacons being more likely to be chosen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Targeting {
+    Nearest,
+    Weighted,
+}
+
+impl Targeting {
+
+    /// Parses a Targeting from a command line argument. Valid arguments are
+    /// "nearest" and "weighted".
+    pub fn parse(arg: &str) -> Option<Self> {
+        match arg {
+            "nearest" => Some(Targeting::Nearest),
+            "weighted" => Some(Targeting::Weighted),
+            _ => None,
+        }
+    }
+
+}
+
+/// How a game ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Won,
+    Lost,
+}
+
+/// The rules a game is played by. The number of Beacons is only used for
+/// generated Levels, since Levels loaded from a map file place their own
+/// Beacons. The game is lost once beacon_losses Beacons have fallen, or
+/// once every Beacon has fallen if beacon_losses is None.
+#[derive(Clone, Copy, Debug)]
+pub struct Rules {
+    pub objective: Objective,
+    pub beacons: usize,
+    pub beacon_losses: Option<usize>,
+    pub targeting: Targeting,
+    pub difficulty: Difficulty,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            objective: Objective::Defend,
+            beacons: 1,
+            beacon_losses: None,
+            targeting: Targeting::Nearest,
+            difficulty: Difficulty::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod objective_tests {
+
+    use super::{Objective, Targeting};
+
+    #[test]
+    fn test_parse_objective() {
+        assert_eq!(Some(Objective::Defend), Objective::parse("defend"));
+        assert_eq!(Some(Objective::Escort), Objective::parse("escort"));
+        assert_eq!(Some(Objective::ClearSpawners), Objective::parse("clear"));
+        assert_eq!(Some(Objective::SurviveWaves(5)), Objective::parse("survive:5"));
+        assert_eq!(Some(Objective::Hold(90.0)), Objective::parse("hold:90"));
+        assert_eq!(None, Objective::parse("survive"));
+        assert_eq!(None, Objective::parse("hold:forever"));
+        assert_eq!(None, Objective::parse("escort:1"));
+    }
+
+    #[test]
+    fn test_parse_targeting() {
+        assert_eq!(Some(Targeting::Nearest), Targeting::parse("nearest"));
+        assert_eq!(Some(Targeting::Weighted), Targeting::parse("weighted"));
+        assert_eq!(None, Targeting::parse("random"));
+    }
+
+}
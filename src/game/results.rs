@@ -0,0 +1,103 @@
+use crate::game::Outcome;
+use crate::game::consts::{
+    KILL_SCORE,
+    BEACON_HEALTH_SCORE,
+    RESOURCE_SCORE,
+    SPAWNER_SCORE,
+    TIME_SCORE,
+    WIN_SCORE,
+};
+use std::fmt;
+
+/// The tallies a game is scored on. Only whole numbers are kept, so that
+/// replaying the same game always gives the same score.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Score {
+    pub kills: u32,
+    pub beacon_health: i32,
+    pub resources: i32,
+    pub spawners: u32,
+    pub seconds: u32,
+}
+
+impl Score {
+
+    /// Returns the total score. Each tally is worth a fixed number of points,
+    /// and winning is worth a bonus on top.
+    pub fn total(&self, outcome: Outcome) -> i64 {
+        let bonus = match outcome {
+            Outcome::Won => WIN_SCORE,
+            Outcome::Lost => 0,
+        };
+        self.kills as i64 * KILL_SCORE
+            + self.beacon_health.max(0) as i64 * BEACON_HEALTH_SCORE
+            + self.resources.max(0) as i64 * RESOURCE_SCORE
+            + self.spawners as i64 * SPAWNER_SCORE
+            + self.seconds as i64 * TIME_SCORE
+            + bonus
+    }
+
+}
+
+/// A summary of a finished game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameResults {
+    pub outcome: Outcome,
+    pub waves: u32,
+    pub score: Score,
+}
+
+impl GameResults {
+
+    /// Returns the total score of the game.
+    pub fn total(&self) -> i64 {
+        self.score.total(self.outcome)
+    }
+
+}
+
+impl fmt::Display for GameResults {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.outcome {
+            Outcome::Won => writeln!(f, "You Win!")?,
+            Outcome::Lost => writeln!(f, "Game Over!")?,
+        }
+        writeln!(f, "Wave: {}", self.waves)?;
+        writeln!(f, "Kills: {}", self.score.kills)?;
+        writeln!(f, "Beacon Health: {}", self.score.beacon_health)?;
+        writeln!(f, "Resources: {}", self.score.resources)?;
+        writeln!(f, "Spawners Destroyed: {}", self.score.spawners)?;
+        writeln!(f, "Time: {}s", self.score.seconds)?;
+        write!(f, "Score: {}", self.total())
+    }
+}
+
+#[cfg(test)]
+mod results_tests {
+
+    use super::Score;
+    use crate::game::Outcome;
+    use crate::game::consts::{
+        KILL_SCORE,
+        BEACON_HEALTH_SCORE,
+        RESOURCE_SCORE,
+        SPAWNER_SCORE,
+        TIME_SCORE,
+        WIN_SCORE,
+    };
+
+    #[test]
+    fn test_score_total() {
+        let score = Score {
+            kills: 3,
+            beacon_health: 4,
+            resources: 2,
+            spawners: 1,
+            seconds: 60,
+        };
+        let total = 3 * KILL_SCORE + 4 * BEACON_HEALTH_SCORE + 2 * RESOURCE_SCORE + SPAWNER_SCORE + 60 * TIME_SCORE;
+        assert_eq!(total, score.total(Outcome::Lost));
+        assert_eq!(total + WIN_SCORE, score.total(Outcome::Won));
+    }
+
+}
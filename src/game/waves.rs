@@ -0,0 +1,94 @@
+use crate::game::consts::{
+    WAVE_DURATION,
+    WAVE_BREAK_DURATION,
+};
+
+/// The phases of a Wave. Enemies only spawn while a Wave is Active. During a
+/// Break the Player has time to recover and build.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WavePhase {
+    Active,
+    Break,
+}
+
+/// A structure to keep track of the waves of enemies. Each Wave is Active for
+/// WAVE_DURATION seconds, followed by a Break of WAVE_BREAK_DURATION seconds
+/// before the next Wave starts.
+pub struct Waves {
+    pub number: u32,
+    pub phase: WavePhase,
+    timer: f64,
+}
+
+impl Waves {
+
+    /// Creates a new Waves starting at the first Active wave.
+    pub fn new() -> Self {
+        Self {
+            number: 1,
+            phase: WavePhase::Active,
+            timer: 0.0,
+        }
+    }
+
+    /// Returns true while between waves.
+    pub fn is_break(&self) -> bool {
+        self.phase == WavePhase::Break
+    }
+
+    /// Returns the number of seconds left in the current phase.
+    pub fn remaining(&self) -> f64 {
+        let duration = match self.phase {
+            WavePhase::Active => WAVE_DURATION,
+            WavePhase::Break => WAVE_BREAK_DURATION,
+        };
+        (duration - self.timer).max(0.0)
+    }
+
+    /// Advances the wave timer by dt seconds. Returns the new phase if the
+    /// phase changed during this tick.
+    pub fn tick(&mut self, dt: f64) -> Option<WavePhase> {
+        self.timer += dt;
+        if self.remaining() > 0.0 {
+            return None;
+        }
+
+        self.timer = 0.0;
+        match self.phase {
+            WavePhase::Active => {
+                self.phase = WavePhase::Break;
+            },
+            WavePhase::Break => {
+                self.phase = WavePhase::Active;
+                self.number += 1;
+            }
+        }
+        Some(self.phase)
+    }
+
+}
+
+impl Default for Waves {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod wave_tests {
+
+    use super::{Waves, WavePhase};
+    use crate::game::consts::{WAVE_DURATION, WAVE_BREAK_DURATION};
+
+    #[test]
+    fn test_wave_cycle() {
+        let mut waves = Waves::new();
+        assert_eq!(1, waves.number);
+        assert_eq!(None, waves.tick(WAVE_DURATION / 2.0));
+        assert_eq!(Some(WavePhase::Break), waves.tick(WAVE_DURATION / 2.0));
+        assert!(waves.is_break());
+        assert_eq!(Some(WavePhase::Active), waves.tick(WAVE_BREAK_DURATION));
+        assert_eq!(2, waves.number);
+    }
+
+}
@@ -1,14 +1,39 @@
+use crate::game::{Difficulty, Objective, Rules, Targeting};
 use std::env;
 
 /// Structure to contain all the initial configurations
 pub struct InitConfig {
     pub debug: bool,
+    pub follow_cursor: bool,
+    pub map: Option<String>,
+    pub bindings: Option<String>,
+    pub rules: Rules,
+    pub steering: bool,
+    pub fog: bool,
+    pub tower_vision: bool,
+    pub sprites: bool,
+    pub screenshot: Option<(u64, String)>,
 }
 
 impl InitConfig {
-    /// Returns a new InitConfig. The debug value defaults to false.
+    /// Returns a new InitConfig. The debug and follow_cursor values default
+    /// to false, no map or bindings file is used, the game is played by the default 
+    /// Rules, Enemies steer around each other, the fog of war is on with
+    /// Towers able to see through it, sprites are drawn if they can be 
+    /// loaded, and the game is played in a window rather than screenshotted.
     fn new() -> Self {
-        Self {debug: false}
+        Self {
+            debug: false,
+            follow_cursor: false,
+            map: None,
+            bindings: None,
+            rules: Rules::default(),
+            steering: true,
+            fog: true,
+            tower_vision: true,
+            sprites: true,
+            screenshot: None,
+        }
     }
 
 }
@@ -17,28 +42,102 @@ impl InitConfig {
 /// 
 /// The following arguments are valid arguments:
 /// 1. -d or --debug: Use a constant known seed 
+/// 2. -f or --follow-cursor: Move the Player towards the cursor instead of
+///    strafing with the movement keys.
+/// 3. -m or --map <path>: Load the Level from a map file.
+/// 4. -b or --bindings <path>: Load the Bindings from a bindings file, and 
+///    save them there when they're rebound. Defaults to bindings.txt, if
+///    it's there. See Bindings for the format.
+/// 5. --beacons <count>: The number of Beacons placed in generated Levels.
+/// 6. --beacon-losses <count>: The number of Beacons which can fall before 
+///    the game is lost. Defaults to every Beacon.
+/// 7. --objective <defend|survive:<waves>|hold:<seconds>|escort|clear>: The
+///    Objective of the game.
+/// 8. --targeting <nearest|weighted>: How enemies choose a Beacon.
+/// 9. --difficulty <easy|normal|hard>: How often spawners are created, how
+///    many there can be and how tough they are.
+/// 10. --no-steering: Enemies follow their paths without steering around each
+///     other or the walls.
+/// 11. --no-fog: The whole Level and every Enemy can always be seen.
+/// 12. --no-tower-vision: Only the Player can see through the fog of war.
+/// 13. --no-sprites: Draw everything as coloured shapes.
+/// 14. --screenshot <seed> <path>: Instead of playing, draw the start of the
+///     game with the numbered seed into an image file (such as a PNG) 
+///     without opening a window.
+/// 
+/// Invalid values are reported and ignored.
 pub fn handle_init_input() -> InitConfig {
         let mut config = InitConfig::new();
-        let args: Vec<String> = env::args().collect();
+        // Skip the name of the program
+        let mut args = env::args().skip(1);
 
         // Argument parsing
         // cargo run -- *arguments go here*
-
-        // Arguments: 
-        //      -d | --debug: Use a constant known seed
-        match args.len() {
-            len if len > 1 => {
-                for i in 1..args.len() {
-                    match &args[i] {
-                        string if *string == String::from("-d") || *string == String::from("--debug") => {
-                            config.debug = true;
-                        },
-                        _ => (),
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-d" | "--debug" => {
+                    config.debug = true;
+                },
+                "-f" | "--follow-cursor" => {
+                    config.follow_cursor = true;
+                },
+                "-m" | "--map" => {
+                    config.map = args.next();
+                },
+                "-b" | "--bindings" => {
+                    config.bindings = args.next();
+                },
+                "--beacons" => {
+                    match args.next().and_then(|count| count.parse().ok()) {
+                        Some(count) if count > 0 => config.rules.beacons = count,
+                        _ => println!("--beacons expects a positive number"),
                     }
-                }
-            },
-            _ => (),
-
+                },
+                "--beacon-losses" => {
+                    match args.next().and_then(|count| count.parse().ok()) {
+                        Some(count) if count > 0 => config.rules.beacon_losses = Some(count),
+                        _ => println!("--beacon-losses expects a positive number"),
+                    }
+                },
+                "--objective" => {
+                    match args.next().as_ref().and_then(|objective| Objective::parse(objective)) {
+                        Some(objective) => config.rules.objective = objective,
+                        None => println!("--objective expects defend, survive:<waves>, hold:<seconds>, escort or clear"),
+                    }
+                },
+                "--targeting" => {
+                    match args.next().as_ref().and_then(|targeting| Targeting::parse(targeting)) {
+                        Some(targeting) => config.rules.targeting = targeting,
+                        None => println!("--targeting expects nearest or weighted"),
+                    }
+                },
+                "--difficulty" => {
+                    match args.next().as_ref().and_then(|difficulty| Difficulty::parse(difficulty)) {
+                        Some(difficulty) => config.rules.difficulty = difficulty,
+                        None => println!("--difficulty expects easy, normal or hard"),
+                    }
+                },
+                "--no-steering" => {
+                    config.steering = false;
+                },
+                "--no-fog" => {
+                    config.fog = false;
+                },
+                "--no-tower-vision" => {
+                    config.tower_vision = false;
+                },
+                "--no-sprites" => {
+                    config.sprites = false;
+                },
+                "--screenshot" => {
+                    let seed = args.next().and_then(|seed| seed.parse().ok());
+                    match (seed, args.next()) {
+                        (Some(seed), Some(path)) => config.screenshot = Some((seed, path)),
+                        _ => println!("--screenshot expects a number and a path"),
+                    }
+                },
+                _ => (),
+            }
         }
         config
     }
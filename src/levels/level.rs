@@ -3,12 +3,23 @@ use crate::entity::tile::{Tile, TileVariant};
 use crate::math::random::{Seed,RNG,from_seed, next_u32};
 use crate::levels::map::{Map, MapIdx};
 use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
 use crate::game::consts::{
     LEVEL_WIDTH,
     LEVEL_HEIGHT,
     LEVEL_GEN_ITERS,
+    TERRAIN_PATCHES,
+    TERRAIN_PATCH_SIZE,
+    DESTRUCTIBLE_WALL_HEALTH,
 };
 
+/// Marks which connected area of the Map a Tile::Floor belongs to while 
+/// filling untraversable space with walls.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Region(i32);
+
 /// A structure to fully describe the game board. A Map is used to store the 
 /// Tiles representing the game board. Width and height are provided for easy
 /// traversal. A random number generator is included to make random selections
@@ -37,11 +48,28 @@ use crate::game::consts::{
 /// filled with Tile::Wall. As such this ensures that any Tile::Floor is 
 /// reachable from all other Tile::Floor in the Map.
 /// 
-/// Finally, to ensure all entities remain within the map, the outer rim of the
-/// Map is turned into Tile::Wall. 
+/// To ensure all entities remain within the map, the outer rim of the Map is
+/// turned into Tile::Wall. 
+/// 
+/// Finally, patches of terrain are scattered over the Floor. Each patch 
+/// starts on a random Tile::Floor and wanders randomly, turning the Floor it
+/// crosses into Tile::Mud, Tile::Water or Tile::Rubble. Since terrain can be
+/// crossed, every Tile which could be reached before still can be.
+/// 
+/// # Map Files
+/// Levels can also be loaded from text files (or rows of text) with one 
+/// character per Tile. '#' is a Tile::Wall, 'S' is a Tile::Spawner, '%' is a
+/// Tile::Mud, '~' is a Tile::Water, ':' is a Tile::Rubble, '+' is a 
+/// Tile::DestructibleWall, 'D' is a closed Tile::Door, and any other 
+/// character is a Tile::Floor. A 'B' marks where a Beacon should be
+/// placed and a 'P' marks where the Player should start. Rows shorter than 
+/// the longest row are padded with Tile::Wall, and the outer rim is always
+/// turned into Tile::Wall.
 /// 
 /// # Path Finding
-/// To navigate the Map, an A* algorithm is used. 
+/// To navigate the Map, an A* algorithm is used. Enemies which can't find a
+/// path fall back to pathfind_breaching(), which allows paths through 
+/// Tile::DestructibleWall so they can break their way through.
 /// 
 /// # Example
 /// 
@@ -74,7 +102,9 @@ pub struct Level {
     pub map: Map,
     pub width: i32,
     pub height: i32,
-    pub rng: RNG
+    pub rng: RNG,
+    pub beacon_spawns: Vec<MapIdx>,
+    pub player_spawn: Option<MapIdx>,
 }
 
 impl Level {
@@ -115,10 +145,72 @@ impl Level {
         map = Level::fill_edge(map, LEVEL_WIDTH, LEVEL_HEIGHT);
         // Fill untraversable space with walls
         map = Level::fill_walls(map, LEVEL_WIDTH, LEVEL_HEIGHT);
-        Level {map: map, width: LEVEL_WIDTH, height: LEVEL_HEIGHT, rng: rng}
+        Level::scatter_terrain(&mut map, &mut rng);
+        Level {
+            map,
+            width: LEVEL_WIDTH,
+            height: LEVEL_HEIGHT,
+            rng,
+            beacon_spawns: Vec::new(),
+            player_spawn: None,
+        }
 
     }
 
+    /// Returns a new level built from rows of characters. See the Map Files
+    /// section for the meaning of each character. The seed is only used for
+    /// the Level's random number generator.
+    pub fn from_ascii(rows: &[&str], init: Seed) -> Self {
+        let height = rows.len() as i32;
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0) as i32;
+        let mut map: Map = Map::new();
+        let mut beacon_spawns: Vec<MapIdx> = Vec::new();
+        let mut player_spawn = None;
+
+        for h in 0..height {
+            let row: Vec<char> = rows[h as usize].chars().collect();
+            for w in 0..width {
+                let idx = MapIdx::new(w, h);
+                let variant = match row.get(w as usize) {
+                    None | Some('#') => TileVariant::Wall,
+                    Some('S') => TileVariant::Spawner,
+                    Some('%') => TileVariant::Mud,
+                    Some('~') => TileVariant::Water,
+                    Some(':') => TileVariant::Rubble,
+                    Some('+') => TileVariant::DestructibleWall{health: DESTRUCTIBLE_WALL_HEALTH},
+                    Some('D') => TileVariant::Door{open: false},
+                    Some('B') => {
+                        beacon_spawns.push(idx);
+                        TileVariant::Floor
+                    },
+                    Some('P') => {
+                        player_spawn = Some(idx);
+                        TileVariant::Floor
+                    },
+                    Some(_) => TileVariant::Floor,
+                };
+                map.insert(idx, Tile::new(variant, idx));
+            }
+        }
+
+        map = Level::fill_edge(map, width, height);
+        Level {
+            map,
+            width,
+            height,
+            rng: from_seed(init),
+            beacon_spawns,
+            player_spawn,
+        }
+    }
+
+    /// Loads a level from a map file. See Level::from_ascii().
+    pub fn load<P: AsRef<Path>>(path: P, init: Seed) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let rows: Vec<&str> = contents.lines().filter(|row| !row.trim().is_empty()).collect();
+        Ok(Level::from_ascii(&rows, init))
+    }
+
     /// Returns a list of indicies surrounding the input index. Differs from
     /// MapIdx.neighbours because it looks in all 8 surrounding locations. 
     fn neighbours(map_idx: &MapIdx) -> Vec<MapIdx> {
@@ -174,86 +266,108 @@ impl Level {
     /// converted into a Tile::Wall. 
     /// 
     /// Filling the walls follows a 3 step process:
-    ///     1. Traverse all tiles in the map and flood_fill() each Floor:Tile. 
+    ///     1. Traverse all tiles in the map and flood_fill() each Floor:Tile
+    ///        which hasn't been given a Region yet. 
     ///     2. Find the largest traversable area.
-    ///     3. Traverse all tiles in the map again, and convert the largest 
-    ///        traversable area into Tile::Floor and everything else into
-    ///        Tile::Wall.
+    ///     3. Traverse all tiles in the map again, and convert everything 
+    ///        outside of the largest traversable area into Tile::Wall.
     fn fill_walls(mut map: Map, width: i32, height:i32) -> Map {
 
-        /// Convertes all reachable Tile::Floor from start to Tile::Cust(new_val)
-        fn flood_fill(mut map: &mut Map, start: &MapIdx, new_val: &i32, sets: &mut HashMap<i32, i32>) {
+        /// Marks all reachable Tile::Floor from start as part of region.
+        fn flood_fill(map: &Map, regions: &mut HashMap<MapIdx, Region>, start: &MapIdx, region: Region, sets: &mut HashMap<Region, i32>) {
+            if regions.contains_key(start) {
+                return;
+            }
             if let Some(tile) = map.get(start) {
-                match tile.variant {
-                    TileVariant::Floor => {
-                        map.remove(start);
-                        map.insert(MapIdx::new(start.x,start.y), Tile::new(TileVariant::Cust(*new_val), *start));
-                        *sets.entry(*new_val).or_insert(1) += 1;
-                        flood_fill(&mut map, &MapIdx::new(start.x, start.y-1), new_val, sets);
-                        flood_fill(&mut map, &MapIdx::new(start.x, start.y+1), new_val, sets);
-                        flood_fill(&mut map, &MapIdx::new(start.x-1, start.y), new_val, sets);
-                        flood_fill(&mut map, &MapIdx::new(start.x+1, start.y), new_val, sets);
-                    },
-                    _ => (),
+                if let TileVariant::Floor = tile.variant {
+                    regions.insert(*start, region);
+                    *sets.entry(region).or_insert(1) += 1;
+                    flood_fill(map, regions, &MapIdx::new(start.x, start.y-1), region, sets);
+                    flood_fill(map, regions, &MapIdx::new(start.x, start.y+1), region, sets);
+                    flood_fill(map, regions, &MapIdx::new(start.x-1, start.y), region, sets);
+                    flood_fill(map, regions, &MapIdx::new(start.x+1, start.y), region, sets);
                 }
             }
         }
 
         // Used to track the number of different regions in the Map.
-        let mut region = 0;
+        let mut region = Region(0);
+        // The region each Tile::Floor belongs to.
+        let mut regions: HashMap<MapIdx, Region> = HashMap::new();
         // Used to count the sizes of each different region.
         // Key: region number & Value: region count.
-        let mut sets: HashMap<i32, i32> = HashMap::new();
+        let mut sets: HashMap<Region, i32> = HashMap::new();
         
         // Traverse map and flood_fill each Tile::Floor.
         for h in 0..height {
             for w in 0..width {
-
-                if let Some(tile) = map.get(&MapIdx::new(w,h)) {
-                    match tile.variant {
-                        TileVariant::Floor => {
-                            flood_fill(&mut map, &MapIdx::new(w,h), &region, &mut sets);
-                            // increment region counter.
-                            region += 1;    
-                        },
-                        _ => ()
+                let idx = MapIdx::new(w,h);
+                if regions.contains_key(&idx) {
+                    continue;
+                }
+                if let Some(tile) = map.get(&idx) {
+                    if let TileVariant::Floor = tile.variant {
+                        flood_fill(&map, &mut regions, &idx, region, &mut sets);
+                        // increment region counter.
+                        region = Region(region.0 + 1);
                     }
                 }
-
             }
         }
 
         // Find the region in sets with the largest number of traversable spaces.
-        let mut max = (-1,-1);
+        let mut max = (Region(-1),-1);
         for (region, count) in sets {
             if count > max.1 {
                 max = (region, count);
             }
         }
 
-        // Convert back to Tile::Floor and Tile::Wall
-        for h in 0..height {
-            for w in 0..width {
-                
-                if let Some(tile) = map.get(&MapIdx::new(w,h)) {
-                    match tile.variant {
-                        TileVariant::Cust(max_val) if max_val == max.0 => {
-                            let idx = MapIdx::new(w, h);
-                            map.remove(&idx);
-                            map.insert(idx, Tile::new(TileVariant::Floor, idx));
-                        },
-                        TileVariant::Cust(_val) => {
-                            let idx = MapIdx::new(w, h);
-                            map.remove(&idx);
-                            map.insert(idx, Tile::new(TileVariant::Wall, idx));
-                        },
-                        _ => (),
+        // Convert every other region to Tile::Wall
+        for (idx, region) in regions {
+            if region != max.0 {
+                map.insert(idx, Tile::new(TileVariant::Wall, idx));
+            }
+        }
+
+        map
+    }
+
+    /// Scatters patches of Mud, Water and Rubble over the Floor of the Map.
+    /// See the Level Generation section.
+    fn scatter_terrain(map: &mut Map, rng: &mut RNG) {
+
+        let mut floor: Vec<MapIdx> = map.iter()
+            .filter(|(_, tile)| matches!(tile.variant, TileVariant::Floor))
+            .map(|(idx, _)| *idx)
+            .collect();
+        if floor.is_empty() {
+            return;
+        }
+        // Sorted so the same seed always gives the same Level.
+        floor.sort();
+
+        for _ in 0..TERRAIN_PATCHES {
+            let terrain = next_u32(rng) % 3;
+            let mut idx = floor[next_u32(rng) as usize % floor.len()];
+            for _ in 0..TERRAIN_PATCH_SIZE {
+                if let Some(tile) = map.get(&idx) {
+                    if matches!(tile.variant, TileVariant::Floor) {
+                        let variant = match terrain {
+                            0 => TileVariant::Mud,
+                            1 => TileVariant::Water,
+                            _ => TileVariant::Rubble,
+                        };
+                        map.insert(idx, Tile::new(variant, idx));
                     }
                 }
+                let step = idx.neighbours()[next_u32(rng) as usize % 4];
+                if map.get(&step).is_some_and(|tile| !tile.variant.is_solid()) {
+                    idx = step;
+                }
             }
         }
 
-        map
     }
 
     /// Fills in the edges of the Map with Walls, to prevent anyone from exiting
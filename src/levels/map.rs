@@ -1,4 +1,7 @@
-use crate::entity::tile::{Tile,TileVariant};
+use crate::entity::tile::{Tile, TileVariant};
+use crate::math::{Point2, Vec2};
+use crate::math::collision::{Manifold, circle_rect_contact};
+use crate::game::consts::{TILE_SIZE, PATH_STEP_COST, PATH_DIAGONAL_COST, map_idx_to_point2, point2_to_map_idx};
 use pathfinding::prelude::{absdiff, astar};
 use std::collections::HashMap;
 
@@ -34,62 +37,252 @@ impl MapIdx {
         Self {x: x, y: y}
     }
 
-    /// Calculates the straight line distance between two MapIdx points.
-    /// Used as a heuristic for A* pathfinding in a Map.
+    /// Calculates the octile distance between two MapIdx points: the cost of
+    /// the shortest path between them on an empty Map, moving diagonally as
+    /// much as possible. Used as a heuristic for A* pathfinding in a Map, and
+    /// never more than the real cost since no Tile is cheaper than Floor.
     fn distance(&self, other: &MapIdx) -> u32 {
-        (absdiff(self.x, other.x) + absdiff(self.y, other.y)) as u32
+        let dx = absdiff(self.x, other.x) as u32;
+        let dy = absdiff(self.y, other.y) as u32;
+        PATH_STEP_COST * dx.max(dy) + (PATH_DIAGONAL_COST - PATH_STEP_COST) * dx.min(dy)
     }
 
     /// Returns the positions surrounding a MapIdx to the north, south, east, 
-    /// and west. Used to navigate a Map using A*. 
+    /// and west.
     pub fn neighbours(&self) -> Vec<MapIdx> {
         vec![MapIdx::new(self.x-1, self.y), MapIdx::new(self.x, self.y-1),
              MapIdx::new(self.x+1, self.y), MapIdx::new(self.x, self.y+1)]
     }
 
-    /// Returns a the positions surronding a MapIdx which are traversable in the 
-    /// input Map and a cost. If the space is traversable, the cost is 1. Only
-    /// Tile::Floor and Tile::Spawner variants are traversable. Any other Tile
-    /// variant surrounding the MapIdx will be counted as impassable.
-    fn successors(&self, map: &Map) -> Vec<(MapIdx, u32)> {
-        // Find surrounding spaces
-        let mut neighbours = self.neighbours();
-        // A list of indicies to remove 
-        let mut remove: Vec<usize> = Vec::new();
-        // Traverse the neibhbours backwards, so that removing by index doesn't
-        // cause any issues
-        for (i, idx) in neighbours.iter().enumerate().rev() {
-            // If map.get(idx) contains a Tile::Floor do nothing, otherwise mark
-            // the tile for removal
-            if let Some(tile) = map.get(idx) {
-                match tile.variant {
-                    TileVariant::Floor => (),
-                    TileVariant::Spawner => (),
-                    _ => {
-                        remove.push(i);
-                    }
+    /// Returns the positions surrounding a MapIdx which can be walked to in 
+    /// the input Map, and the cost of each step. Used to navigate a Map using
+    /// A*.
+    /// 
+    /// All 8 surrounding positions are considered. A step costs the cost of
+    /// the Tile being stepped onto, scaled up for diagonal steps. Diagonal 
+    /// steps can't cut corners, so both Tiles beside the step must also be
+    /// walkable. When breaching, DestructibleWalls can be stepped onto.
+    fn successors(&self, map: &Map, breaching: bool) -> Vec<(MapIdx, u32)> {
+        let cost = |x: i32, y: i32| map.get(&MapIdx::new(x, y)).and_then(|tile| tile.variant.cost(breaching));
+        let mut successors: Vec<(MapIdx, u32)> = Vec::new();
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let (x, y) = (self.x + dx, self.y + dy);
+                let step = match cost(x, y) {
+                    Some(step) => step,
+                    None => continue,
+                };
+                if dx == 0 || dy == 0 {
+                    successors.push((MapIdx::new(x, y), step));
+                } else if cost(x, self.y).is_some() && cost(self.x, y).is_some() {
+                    let diagonal = (step * PATH_DIAGONAL_COST + PATH_STEP_COST / 2) / PATH_STEP_COST;
+                    successors.push((MapIdx::new(x, y), diagonal));
                 }
-            } else {
-                remove.push(i);
             }
         }
-        // Remove all marked tiles from neighbour list
-        for i in remove {
-            neighbours.remove(i);
-        }
-        // return the traversable tiles mapped with a traversal cost of 1
-        neighbours.into_iter().map(|p| (p, 1)).collect()
-
+        successors
     }
 }
 
 /// Returns a list of MapIdx and a total cost if there exists a path from
-/// start to target, otherwise returns None.
+/// start to target, otherwise returns None. A step onto a Floor Tile costs 
+/// PATH_STEP_COST.
 pub fn pathfind(map: &Map, start: &MapIdx, target: &MapIdx) -> Option<(Vec<MapIdx>, u32)> {
 
-    astar(start, |p| p.successors(map), |p| p.distance(&target) / 3,|p| *p == *target)
+    astar(start, |p| p.successors(map, false), |p| p.distance(target), |p| *p == *target)
+
+}
+
+/// Like pathfind(), but the path may go through DestructibleWalls, each of 
+/// which costs PATH_BREACH_COST to step onto. Used by Enemies which have no
+/// other way to reach their target, so the path found breaks through as few
+/// walls as it reasonably can.
+pub fn pathfind_breaching(map: &Map, start: &MapIdx, target: &MapIdx) -> Option<(Vec<MapIdx>, u32)> {
+
+    astar(start, |p| p.successors(map, true), |p| p.distance(target), |p| *p == *target)
+
+}
 
+/// Returns true if a square of side length size can slide in a straight line
+/// from start to end (both the square's top left corner) without touching 
+/// any Tile which is solid or costs more than max_cost to cross.
+fn clear_line(map: &Map, start: Point2, end: Point2, size: f64, max_cost: u32) -> bool {
+    let delta = end - start;
+    let steps = (delta.x.abs().max(delta.y.abs()) / (TILE_SIZE / 4.0)).ceil().max(1.0) as usize;
+    (0..=steps).all(|i| {
+        let p = start + delta * (i as f64 / steps as f64);
+        let (lo, hi) = (point2_to_map_idx(p), point2_to_map_idx(p + Point2{x: size, y: size}));
+        (lo.y..=hi.y).all(|h| (lo.x..=hi.x).all(|w| {
+            match map.get(&MapIdx::new(w, h)).and_then(|tile| tile.variant.cost(false)) {
+                Some(cost) => cost <= max_cost,
+                None => false,
+            }
+        }))
+    })
+}
+
+/// Straightens a path found by pathfind() for something of side length size,
+/// returning the positions (of the top left corner) it should head for. 
+/// 
+/// Points along the path are dropped by string-pulling: whenever there is a
+/// clear straight line from the last point kept to the point after next, the
+/// next point isn't needed. A line is only clear if it avoids walls and 
+/// doesn't cross anything more costly than the part of the path it replaces,
+/// so smoothing never drags a path through Mud or Water that it went around.
+pub fn smooth_path(map: &Map, path: &[MapIdx], size: f64) -> Vec<Point2> {
+    let cost = |idx: &MapIdx| map.get(idx).and_then(|tile| tile.variant.cost(false)).unwrap_or(0);
+    let mut smoothed: Vec<Point2> = Vec::new();
+    let mut anchor = 0;
+    for i in 0..path.len() {
+        let keep = i == 0 || i + 1 == path.len() || {
+            let max_cost = path[anchor..=i+1].iter().map(cost).max().unwrap_or(0);
+            !clear_line(map, map_idx_to_point2(path[anchor]), map_idx_to_point2(path[i+1]), size, max_cost)
+        };
+        if keep {
+            smoothed.push(map_idx_to_point2(path[i]));
+            anchor = i;
+        }
+    }
+    smoothed
+}
+
+/// Returns the fraction of full speed that something at point moves at, 
+/// depending on the Tile under it.
+pub fn speed_at(map: &Map, point: Point2) -> f64 {
+    map.get(&point2_to_map_idx(point)).map_or(1.0, |tile| tile.variant.speed())
 }
 
 /// A HashMap mapping MapIdxs to Tiles. Used to represent the game board.
-pub type Map = HashMap<MapIdx, Tile>;
\ No newline at end of file
+pub type Map = HashMap<MapIdx, Tile>;
+
+/// The distance kept between a swept shape and the wall it was stopped by.
+const SWEEP_SKIN: f64 = 0.01;
+
+/// How many times a swept circle is pushed out of the Map's walls after each
+/// step.
+const SWEEP_ITERATIONS: usize = 4;
+
+/// The result of sweeping a shape through a Map. delta is the distance the 
+/// shape can actually travel, and blocked_x and blocked_y record whether a 
+/// wall stopped the movement along either axis. normal is the direction into
+/// the last wall which stopped the movement.
+#[derive(Clone, Copy, Debug)]
+pub struct Sweep {
+    pub delta: Point2,
+    pub blocked_x: bool,
+    pub blocked_y: bool,
+    pub normal: Option<Vec2>,
+}
+
+/// Returns the corners of the Tile at idx, in the order given by 
+/// GenericShape::get_corners().
+pub fn tile_corners(idx: MapIdx) -> [Point2; 4] {
+    let corner = map_idx_to_point2(idx);
+    [
+        corner,
+        corner + Point2{x: TILE_SIZE, y: 0.0},
+        corner + Point2{x: 0.0, y: TILE_SIZE},
+        corner + Point2{x: TILE_SIZE, y: TILE_SIZE},
+    ]
+}
+
+/// Returns which of the Tiles to the north, east, south and west of idx are
+/// Tile::Walls, as the bits 1, 2, 4 and 8 of a mask. Positions outside of the
+/// Map are counted as walls. Used to pick the sprite of a wall, so walls join
+/// up with their neighbours.
+pub fn wall_mask(map: &Map, idx: MapIdx) -> u8 {
+    let is_wall = |x: i32, y: i32| match map.get(&MapIdx::new(x, y)) {
+        Some(tile) => matches!(tile.variant, TileVariant::Wall),
+        None => true,
+    };
+    let neighbours = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+    neighbours.iter().enumerate()
+        .filter(|(_, (dx, dy))| is_wall(idx.x + dx, idx.y + dy))
+        .fold(0, |mask, (bit, _)| mask | 1 << bit)
+}
+
+/// Returns the index of every solid Tile overlapping the box from min to max.
+/// Positions outside of the Map are counted as solid.
+pub fn solid_tiles(map: &Map, min: Point2, max: Point2) -> Vec<MapIdx> {
+    let min_idx = point2_to_map_idx(min);
+    let max_idx = point2_to_map_idx(max);
+    let mut solid: Vec<MapIdx> = Vec::new();
+    for h in min_idx.y..max_idx.y+1 {
+        for w in min_idx.x..max_idx.x+1 {
+            let idx = MapIdx::new(w, h);
+            match map.get(&idx) {
+                Some(tile) if !tile.variant.is_solid() => (),
+                _ => solid.push(idx),
+            }
+        }
+    }
+    solid
+}
+
+/// Returns the deepest contact between a circle and the solid Tiles in the 
+/// Map.
+fn deepest_contact(map: &Map, center: Point2, radius: f64) -> Option<Manifold> {
+    let extent = Point2{x: radius, y: radius};
+    let mut deepest: Option<Manifold> = None;
+    for idx in solid_tiles(map, center - extent, center + extent) {
+        if let Some(contact) = circle_rect_contact(center, radius, &tile_corners(idx)) {
+            if deepest.as_ref().is_none_or(|d| contact.depth > d.depth) {
+                deepest = Some(contact);
+            }
+        }
+    }
+    deepest
+}
+
+/// Moves a circle of the input radius from center by up to delta, pushing it
+/// out of any solid Tile in the Map.
+/// 
+/// The movement is split into steps no longer than a quarter of a Tile, so a
+/// large delta (from a long frame) can't carry the circle through a wall. 
+/// After each step the circle is pushed out of the Tile it overlaps most, 
+/// along the contact normal, a few times over in case it is wedged between
+/// Tiles. The part of the remaining movement heading into the
+/// Tile is removed, so the circle slides along walls and rounds corners 
+/// instead of catching on them. blocked_x or blocked_y is set depending on 
+/// which axis the push was mostly along.
+pub fn sweep_circle(map: &Map, center: Point2, radius: f64, delta: Point2) -> Sweep {
+    let max_step = TILE_SIZE / 4.0;
+    let steps = (delta.x.abs().max(delta.y.abs()) / max_step).ceil().max(1.0);
+    let mut step = delta * (1.0 / steps);
+
+    let mut pos = center;
+    let mut blocked_x = false;
+    let mut blocked_y = false;
+    let mut normal = None;
+    for _ in 0..steps as usize {
+        pos = pos + step;
+        for _ in 0..SWEEP_ITERATIONS {
+            let contact = match deepest_contact(map, pos, radius) {
+                Some(contact) => contact,
+                None => break,
+            };
+            let n = Point2{x: contact.normal.x, y: contact.normal.y};
+            pos = pos - n * (contact.depth + SWEEP_SKIN);
+            let into = step.x * n.x + step.y * n.y;
+            if into > 0.0 {
+                step = step - n * into;
+            }
+            if n.x.abs() >= n.y.abs() {
+                blocked_x = true;
+            } else {
+                blocked_y = true;
+            }
+            normal = Some(contact.normal);
+        }
+    }
+
+    Sweep {
+        delta: pos - center,
+        blocked_x,
+        blocked_y,
+        normal,
+    }
+}
\ No newline at end of file
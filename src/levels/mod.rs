@@ -1,3 +1,5 @@
 pub use self::level::Level;
 mod level;
-pub mod map;
\ No newline at end of file
+pub mod map;
+pub mod spatial_hash;
+pub mod visibility;
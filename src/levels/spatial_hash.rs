@@ -0,0 +1,271 @@
+use crate::levels::map::MapIdx;
+use crate::traits::draw::GenericShape;
+use crate::math::{Point2, Vec2};
+use crate::game::consts::{TILE_SIZE, point2_to_map_idx};
+use std::collections::HashMap;
+
+/// A uniform grid used as a broad-phase for collision checks. The grid is
+/// made up of the Tiles of the Map, so each cell is keyed by a MapIdx.
+///
+/// Entities are registered by an id (usually their index in whichever list
+/// they're stored in) into every cell their bounding box overlaps. Queries
+/// return the ids of the entities which might be touching the queried area,
+/// which should then be checked properly with check_collision(). 
+/// 
+/// The SpatialHash remembers which cells each id is in, so inserting an id 
+/// again moves it, and only touches the cells if it has moved into others.
+/// That way it can be kept up to date by inserting every entity again after
+/// they move and removing those which are gone, rather than being refilled.
+/// Since a slot is kept for every id up to the largest, ids should be small.
+///
+/// # Example
+///
+/// ```
+/// extern crate rust_game;
+/// use rust_game::levels::spatial_hash::SpatialHash;
+/// use rust_game::math::Point2;
+///
+/// fn main() {
+///     let mut grid = SpatialHash::new();
+///     grid.insert(0, Point2{x: 5.0, y: 5.0}, Point2{x: 15.0, y: 15.0});
+///     grid.insert(1, Point2{x: 205.0, y: 5.0}, Point2{x: 215.0, y: 15.0});
+///
+///     assert_eq!(vec![0], grid.query_radius(Point2{x: 0.0, y: 0.0}, 30.0));
+/// }
+/// ```
+#[derive(Default)]
+pub struct SpatialHash {
+    cells: HashMap<MapIdx, Vec<usize>>,
+    spans: Vec<Option<(MapIdx, MapIdx)>>,
+}
+
+impl SpatialHash {
+
+    /// Creates a new empty SpatialHash.
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Removes every entity from the SpatialHash. The memory used by each
+    /// cell is kept, so refilling the SpatialHash is cheap.
+    pub fn clear(&mut self) {
+        for ids in self.cells.values_mut() {
+            ids.clear();
+        }
+        self.spans.clear();
+    }
+
+    /// Registers id into every cell overlapped by the box from min to max. 
+    /// If id is already registered it's moved, which does nothing if the box
+    /// overlaps the same cells as before.
+    pub fn insert(&mut self, id: usize, min: Point2, max: Point2) {
+        let span = (point2_to_map_idx(min), point2_to_map_idx(max));
+        if id >= self.spans.len() {
+            self.spans.resize(id + 1, None);
+        }
+        if self.spans[id] == Some(span) {
+            return;
+        }
+        self.remove(id);
+        self.spans[id] = Some(span);
+        let (lo, hi) = span;
+        for y in lo.y..=hi.y {
+            for x in lo.x..=hi.x {
+                self.cells.entry(MapIdx::new(x, y)).or_default().push(id);
+            }
+        }
+    }
+
+    /// Removes id from every cell it was registered in.
+    pub fn remove(&mut self, id: usize) {
+        let (lo, hi) = match self.spans.get_mut(id).and_then(|span| span.take()) {
+            Some(span) => span,
+            None => return,
+        };
+        for y in lo.y..=hi.y {
+            for x in lo.x..=hi.x {
+                if let Some(ids) = self.cells.get_mut(&MapIdx::new(x, y)) {
+                    ids.retain(|other| *other != id);
+                }
+            }
+        }
+    }
+
+    /// Removes every registered id for which keep returns false.
+    pub fn retain<F: FnMut(usize) -> bool>(&mut self, mut keep: F) {
+        for id in 0..self.spans.len() {
+            if self.spans[id].is_some() && !keep(id) {
+                self.remove(id);
+            }
+        }
+    }
+
+    /// Registers id into every cell overlapped by the bounding box of shape.
+    pub fn insert_shape(&mut self, id: usize, shape: &GenericShape) {
+        let (min, max) = shape.bounds();
+        self.insert(id, min, max);
+    }
+
+    /// Returns the ids in the cell at idx.
+    fn cell(&self, idx: &MapIdx) -> &[usize] {
+        self.cells.get(idx).map_or(&[], |ids| ids.as_slice())
+    }
+
+    /// Returns the ids of every entity which might overlap the box from min
+    /// to max, in ascending order without duplicates.
+    pub fn query_aabb(&self, min: Point2, max: Point2) -> Vec<usize> {
+        let (lo, hi) = (point2_to_map_idx(min), point2_to_map_idx(max));
+        let mut ids: Vec<usize> = Vec::new();
+        for y in lo.y..=hi.y {
+            for x in lo.x..=hi.x {
+                ids.extend_from_slice(self.cell(&MapIdx::new(x, y)));
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns the ids of every entity which might overlap the bounding box
+    /// of shape.
+    pub fn query_shape(&self, shape: &GenericShape) -> Vec<usize> {
+        let (min, max) = shape.bounds();
+        self.query_aabb(min, max)
+    }
+
+    /// Returns the ids of every entity which might be within radius of
+    /// center.
+    pub fn query_radius(&self, center: Point2, radius: f64) -> Vec<usize> {
+        let extent = Point2{x: radius, y: radius};
+        self.query_aabb(center - extent, center + extent)
+    }
+
+    /// Returns the ids of every entity in the cells crossed by the ray from
+    /// origin, travelling length along direction. Ids are returned in the
+    /// order their cells are reached, without duplicates.
+    ///
+    /// The cells are walked one at a time using the method from "A Fast
+    /// Voxel Traversal Algorithm for Ray Tracing" by Amanatides and Woo.
+    pub fn query_ray(&self, origin: Point2, direction: Vec2, length: f64) -> Vec<usize> {
+        let mut ids: Vec<usize> = Vec::new();
+        let mut add = |cell: &[usize]| {
+            for id in cell {
+                if !ids.contains(id) {
+                    ids.push(*id);
+                }
+            }
+        };
+
+        let mut idx = point2_to_map_idx(origin);
+        add(self.cell(&idx));
+        let norm = direction.length();
+        if norm == 0.0 || length <= 0.0 {
+            return ids;
+        }
+        let dir = Vec2::new(direction.x / norm, direction.y / norm);
+
+        // The distance along the ray to the first cell boundary on each axis,
+        // and the distance between boundaries on each axis.
+        let axis = |position: f64, cell: i32, d: f64| -> (i32, f64, f64) {
+            if d > 0.0 {
+                (1, ((cell + 1) as f64 * TILE_SIZE - position) / d, TILE_SIZE / d)
+            } else if d < 0.0 {
+                (-1, (cell as f64 * TILE_SIZE - position) / d, -TILE_SIZE / d)
+            } else {
+                (0, f64::INFINITY, f64::INFINITY)
+            }
+        };
+        let (step_x, mut next_x, delta_x) = axis(origin.x, idx.x, dir.x);
+        let (step_y, mut next_y, delta_y) = axis(origin.y, idx.y, dir.y);
+
+        loop {
+            if next_x < next_y {
+                if next_x > length {
+                    break;
+                }
+                idx.x += step_x;
+                next_x += delta_x;
+            } else {
+                if next_y > length {
+                    break;
+                }
+                idx.y += step_y;
+                next_y += delta_y;
+            }
+            add(self.cell(&idx));
+        }
+        ids
+    }
+
+}
+
+#[cfg(test)]
+mod spatial_hash_tests {
+
+    use super::SpatialHash;
+    use crate::math::{Point2, Vec2};
+
+    fn setup() -> SpatialHash {
+        let mut grid = SpatialHash::new();
+        // A box spanning four cells.
+        grid.insert(0, Point2{x: 15.0, y: 15.0}, Point2{x: 25.0, y: 25.0});
+        grid.insert(1, Point2{x: 105.0, y: 5.0}, Point2{x: 110.0, y: 10.0});
+        grid.insert(2, Point2{x: 5.0, y: 105.0}, Point2{x: 10.0, y: 110.0});
+        grid
+    }
+
+    #[test]
+    fn test_query_aabb() {
+        let grid = setup();
+        assert_eq!(vec![0], grid.query_aabb(Point2{x: 21.0, y: 21.0}, Point2{x: 22.0, y: 22.0}));
+        assert_eq!(vec![0, 1], grid.query_aabb(Point2{x: 0.0, y: 0.0}, Point2{x: 119.0, y: 19.0}));
+        assert!(grid.query_aabb(Point2{x: 60.0, y: 60.0}, Point2{x: 70.0, y: 70.0}).is_empty());
+    }
+
+    #[test]
+    fn test_query_radius() {
+        let grid = setup();
+        assert_eq!(vec![0, 2], grid.query_radius(Point2{x: 10.0, y: 60.0}, 45.0));
+    }
+
+    #[test]
+    fn test_query_ray() {
+        let grid = setup();
+        let origin = Point2{x: 1.0, y: 1.0};
+        // Along the top row only the first two boxes are crossed, in order.
+        assert_eq!(vec![0, 1], grid.query_ray(origin, Vec2::new(1.0, 0.0), 200.0));
+        assert_eq!(vec![0], grid.query_ray(origin, Vec2::new(1.0, 0.0), 50.0));
+        // Diagonally, the ray passes through the box spanning four cells.
+        assert_eq!(vec![0], grid.query_ray(origin, Vec2::new(1.0, 1.0), 200.0));
+        assert_eq!(vec![0, 2], grid.query_ray(origin, Vec2::new(0.0, 1.0), 200.0));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut grid = setup();
+        grid.clear();
+        assert!(grid.query_radius(Point2{x: 20.0, y: 20.0}, 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_move_and_remove() {
+        let mut grid = setup();
+        // Moving within the same cells changes nothing, moving out of them
+        // leaves nothing behind.
+        grid.insert(1, Point2{x: 101.0, y: 1.0}, Point2{x: 102.0, y: 2.0});
+        assert_eq!(vec![1], grid.query_radius(Point2{x: 105.0, y: 5.0}, 5.0));
+        grid.insert(1, Point2{x: 25.0, y: 25.0}, Point2{x: 30.0, y: 30.0});
+        assert!(grid.query_radius(Point2{x: 105.0, y: 5.0}, 5.0).is_empty());
+        assert_eq!(vec![0, 1], grid.query_radius(Point2{x: 25.0, y: 25.0}, 1.0));
+
+        grid.remove(0);
+        assert_eq!(vec![1], grid.query_radius(Point2{x: 25.0, y: 25.0}, 1.0));
+        grid.retain(|id| id != 2);
+        assert!(grid.query_radius(Point2{x: 5.0, y: 105.0}, 5.0).is_empty());
+        assert_eq!(vec![1], grid.query_radius(Point2{x: 60.0, y: 60.0}, 100.0));
+    }
+
+}
@@ -0,0 +1,311 @@
+use crate::levels::map::{Map, MapIdx};
+use crate::math::Point2;
+use crate::game::consts::point2_to_map_idx;
+use std::collections::HashSet;
+
+/// Tracks which Tiles of a Map can currently be seen, and which have been
+/// seen at some point (explored). Each tick the visible Tiles are cleared and
+/// revealed again from whatever can see, while explored Tiles stay explored.
+///
+/// # Example
+///
+/// ```
+/// extern crate rust_game;
+/// use rust_game::levels::Level;
+/// use rust_game::levels::map::MapIdx;
+/// use rust_game::levels::visibility::Visibility;
+/// use rust_game::math::random::create_seed;
+///
+/// fn main() {
+///     let level = Level::from_ascii(&["#####", "#.#.#", "#####"], create_seed(true));
+///     let mut visibility = Visibility::new();
+///     visibility.reveal(&level.map, MapIdx::new(1, 1), 5);
+///
+///     assert!(visibility.is_visible(&MapIdx::new(2, 1)));
+///     assert!(!visibility.is_visible(&MapIdx::new(3, 1)));
+/// }
+/// ```
+#[derive(Default)]
+pub struct Visibility {
+    visible: HashSet<MapIdx>,
+    explored: HashSet<MapIdx>,
+}
+
+impl Visibility {
+
+    /// Creates a new Visibility where nothing has been seen.
+    pub fn new() -> Self {
+        Self {
+            visible: HashSet::new(),
+            explored: HashSet::new(),
+        }
+    }
+
+    /// Hides every Tile, leaving the explored Tiles explored.
+    pub fn clear_visible(&mut self) {
+        self.visible.clear();
+    }
+
+    /// Makes every Tile in the field_of_view() from origin visible and
+    /// explored.
+    pub fn reveal(&mut self, map: &Map, origin: MapIdx, radius: i32) {
+        let seen = field_of_view(map, origin, radius);
+        self.explored.extend(seen.iter().copied());
+        self.visible.extend(seen);
+    }
+
+    /// Returns true if the Tile at idx can currently be seen.
+    pub fn is_visible(&self, idx: &MapIdx) -> bool {
+        self.visible.contains(idx)
+    }
+
+    /// Returns true if the Tile at idx has ever been seen.
+    pub fn is_explored(&self, idx: &MapIdx) -> bool {
+        self.explored.contains(idx)
+    }
+
+    /// Returns how many Tiles have ever been seen.
+    pub fn explored_count(&self) -> usize {
+        self.explored.len()
+    }
+
+    /// Returns true if the Tile under point can currently be seen.
+    pub fn point_visible(&self, point: Point2) -> bool {
+        self.is_visible(&point2_to_map_idx(point))
+    }
+
+}
+
+/// A slope from the origin of a field of view, stored as an exact fraction so
+/// Tiles lying right on the edge of a shadow are handled consistently.
+#[derive(Clone, Copy, Debug)]
+struct Slope {
+    num: i32,
+    den: i32,
+}
+
+impl Slope {
+
+    /// Returns the slope through the edge of the Tile at col nearest the
+    /// start of the row at depth.
+    fn through(depth: i32, col: i32) -> Self {
+        Self {num: 2 * col - 1, den: 2 * depth}
+    }
+
+    /// Returns the column the slope passes through at depth, rounding ties
+    /// up.
+    fn round_up(self, depth: i32) -> i32 {
+        (2 * depth * self.num + self.den).div_euclid(2 * self.den)
+    }
+
+    /// Returns the column the slope passes through at depth, rounding ties
+    /// down.
+    fn round_down(self, depth: i32) -> i32 {
+        -(self.den - 2 * depth * self.num).div_euclid(2 * self.den)
+    }
+
+    /// Returns true if the slope passes through or before col at depth.
+    fn is_before(self, depth: i32, col: i32) -> bool {
+        depth * self.num <= col * self.den
+    }
+
+    /// Returns true if the slope passes through or after col at depth.
+    fn is_after(self, depth: i32, col: i32) -> bool {
+        depth * self.num >= col * self.den
+    }
+
+}
+
+/// A row of Tiles at some depth from the origin, between two slopes.
+struct Row {
+    depth: i32,
+    start: Slope,
+    end: Slope,
+}
+
+/// Returns the index of every Tile which can be seen from origin, no more
+/// than radius Tiles away. Solid Tiles, and anything outside of the Map,
+/// block vision, but are visible themselves.
+///
+/// Vision is found using symmetric shadowcasting, as described in Albert
+/// Ford's "Symmetric Shadowcasting". The area around the origin is split
+/// into four quadrants, and each is scanned row by row moving away from the
+/// origin. Solid Tiles cast shadows which narrow the slopes the following
+/// rows are scanned between. A Floor Tile is only visible if its center lies
+/// between the slopes, which makes vision symmetric: if a can see b, b can
+/// see a.
+pub fn field_of_view(map: &Map, origin: MapIdx, radius: i32) -> HashSet<MapIdx> {
+    let mut seen: HashSet<MapIdx> = HashSet::new();
+    seen.insert(origin);
+
+    // Each quadrant maps (depth, col) to a MapIdx: north, east, south, west.
+    let quadrants: [fn(MapIdx, i32, i32) -> MapIdx; 4] = [
+        |o, depth, col| MapIdx::new(o.x + col, o.y - depth),
+        |o, depth, col| MapIdx::new(o.x + depth, o.y + col),
+        |o, depth, col| MapIdx::new(o.x + col, o.y + depth),
+        |o, depth, col| MapIdx::new(o.x - depth, o.y + col),
+    ];
+    let blocks = |idx: &MapIdx| map.get(idx).is_none_or(|tile| tile.variant.is_solid());
+    let in_range = |depth: i32, col: i32| depth * depth + col * col <= radius * radius + radius;
+
+    for transform in quadrants.iter() {
+        let mut rows = vec![Row{
+            depth: 1,
+            start: Slope{num: -1, den: 1},
+            end: Slope{num: 1, den: 1},
+        }];
+        while let Some(mut row) = rows.pop() {
+            if row.depth > radius {
+                continue;
+            }
+            // Whether the previous Tile in the row blocked vision, if there
+            // was one.
+            let mut previous: Option<bool> = None;
+            for col in row.start.round_up(row.depth)..=row.end.round_down(row.depth) {
+                let idx = transform(origin, row.depth, col);
+                let wall = blocks(&idx);
+                let symmetric = row.start.is_before(row.depth, col) && row.end.is_after(row.depth, col);
+                if (wall || symmetric) && in_range(row.depth, col) {
+                    seen.insert(idx);
+                }
+                if previous == Some(true) && !wall {
+                    row.start = Slope::through(row.depth, col);
+                }
+                if previous == Some(false) && wall {
+                    rows.push(Row{
+                        depth: row.depth + 1,
+                        start: row.start,
+                        end: Slope::through(row.depth, col),
+                    });
+                }
+                previous = Some(wall);
+            }
+            if previous == Some(false) {
+                rows.push(Row{
+                    depth: row.depth + 1,
+                    start: row.start,
+                    end: row.end,
+                });
+            }
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+mod visibility_tests {
+
+    use super::{field_of_view, Visibility};
+    use crate::levels::Level;
+    use crate::levels::map::{Map, MapIdx};
+    use crate::math::random::create_seed;
+
+    fn setup(rows: &[&str]) -> Map {
+        Level::from_ascii(rows, create_seed(true)).map
+    }
+
+    /// Draws the field of view on top of the rows, marking visible Tiles
+    /// with '*'.
+    fn draw(rows: &[&str], origin: MapIdx, radius: i32) -> Vec<String> {
+        let seen = field_of_view(&setup(rows), origin, radius);
+        rows.iter().enumerate().map(|(y, row)| {
+            row.chars().enumerate().map(|(x, c)| {
+                if seen.contains(&MapIdx::new(x as i32, y as i32)) { '*' } else { c }
+            }).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn test_open_room() {
+        let rows = [
+            "#######",
+            "#.....#",
+            "#.....#",
+            "#.....#",
+            "#######",
+        ];
+        assert_eq!(vec![
+            "*******",
+            "*******",
+            "*******",
+            "*******",
+            "*******",
+        ], draw(&rows, MapIdx::new(3, 2), 10));
+    }
+
+    #[test]
+    fn test_walls_cast_shadows() {
+        let rows = [
+            "#########",
+            "#.......#",
+            "#...#...#",
+            "#.......#",
+            "#########",
+        ];
+        assert_eq!(vec![
+            "*********",
+            "*********",
+            "*****...#",
+            "*********",
+            "*********",
+        ], draw(&rows, MapIdx::new(1, 2), 10));
+    }
+
+    #[test]
+    fn test_doors_block_vision() {
+        let rows = [
+            "##########",
+            "#...D....#",
+            "##########",
+        ];
+        assert_eq!(vec![
+            "*****#####",
+            "*****....#",
+            "*****#####",
+        ], draw(&rows, MapIdx::new(1, 1), 20));
+    }
+
+    #[test]
+    fn test_radius_and_symmetry() {
+        let rows = [
+            "############",
+            "#..........#",
+            "#.#..#.....#",
+            "#....#..#..#",
+            "#..........#",
+            "############",
+        ];
+        let map = setup(&rows);
+        let a = MapIdx::new(1, 1);
+        let seen = field_of_view(&map, a, 4);
+        assert!(seen.contains(&MapIdx::new(5, 1)));
+        assert!(!seen.contains(&MapIdx::new(6, 1)));
+
+        // If a can see b, b can see a.
+        let floor: Vec<MapIdx> = map.iter()
+            .filter(|(_, tile)| !tile.variant.is_solid())
+            .map(|(idx, _)| *idx)
+            .collect();
+        for a in floor.iter() {
+            let from_a = field_of_view(&map, *a, 20);
+            for b in floor.iter() {
+                assert_eq!(from_a.contains(b), field_of_view(&map, *b, 20).contains(a));
+            }
+        }
+    }
+
+    #[test]
+    fn test_explored_tiles_stay_explored() {
+        let map = setup(&["##########", "#........#", "##########"]);
+        let mut visibility = Visibility::new();
+        visibility.reveal(&map, MapIdx::new(1, 1), 2);
+        visibility.clear_visible();
+        visibility.reveal(&map, MapIdx::new(8, 1), 1);
+        assert!(!visibility.is_visible(&MapIdx::new(1, 1)));
+        assert!(visibility.is_explored(&MapIdx::new(1, 1)));
+        assert!(visibility.is_visible(&MapIdx::new(8, 1)));
+        assert!(!visibility.is_explored(&MapIdx::new(5, 1)));
+    }
+
+}
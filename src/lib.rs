@@ -7,4 +7,6 @@ pub mod entity;
 pub mod levels;
 pub mod math;
 pub mod game;
-pub mod traits;
\ No newline at end of file
+pub mod traits;
+pub mod ecs;
+pub mod render;
\ No newline at end of file
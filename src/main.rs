@@ -4,6 +4,8 @@ fn main() {
 
     // Create a new Game object and start the game loop.
     let mut game = Game::new();
-    game.run();
+    if let Some(results) = game.run() {
+        println!("{}", results);
+    }
 
 }
\ No newline at end of file
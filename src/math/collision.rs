@@ -176,6 +176,384 @@ pub fn circle_circle_intersect(c1: Point2, r1: f64, c2: Point2, r2: f64) -> bool
 
 }
 
+/// The contact between two overlapping shapes. normal is a unit vector 
+/// pointing from the first shape towards the second, and depth is how far 
+/// the shapes overlap along it, so moving the second shape by depth along 
+/// normal (or the first shape by depth against it) separates them. points 
+/// are where the shapes touch.
+#[derive(Clone, Debug)]
+pub struct Manifold {
+    pub normal: Vec2,
+    pub depth: f64,
+    pub points: Vec<Point2>,
+}
+
+/// Where a ray first hits a shape. distance is measured along the ray from 
+/// its origin, and normal is the unit normal of the surface which was hit. 
+/// A ray starting inside a shape hits it at distance 0.0 with the normal 
+/// facing back along the ray.
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    pub distance: f64,
+    pub point: Point2,
+    pub normal: Vec2,
+}
+
+/// Dot product of two points treated as vectors.
+fn dot(p: Point2, q: Point2) -> f64 {
+    p.x * q.x + p.y * q.y
+}
+
+/// Exact length of a point treated as a vector.
+fn length(p: Point2) -> f64 {
+    dot(p, p).sqrt()
+}
+
+/// Exact unit vector in the direction of p, or None if p has no length.
+fn unit(p: Point2) -> Option<Point2> {
+    let len = length(p);
+    if len > 0.0 {
+        Some(p * (1.0 / len))
+    } else {
+        None
+    }
+}
+
+/// A rectangle described by a corner, the unit vectors along its two sides
+/// from that corner, and the lengths of the sides. Corners are expected in
+/// the order given by GenericShape::get_corners(): the origin, along the 
+/// width, along the height, then opposite the origin.
+#[derive(Clone, Copy, Debug)]
+struct Frame {
+    origin: Point2,
+    u: Point2,
+    v: Point2,
+    width: f64,
+    height: f64,
+}
+
+impl Frame {
+
+    fn new(corners: &[Point2]) -> Self {
+        let side_u = corners[1] - corners[0];
+        let side_v = corners[2] - corners[0];
+        Frame {
+            origin: corners[0],
+            u: unit(side_u).unwrap_or(Point2{x: 1.0, y: 0.0}),
+            v: unit(side_v).unwrap_or(Point2{x: 0.0, y: 1.0}),
+            width: length(side_u),
+            height: length(side_v),
+        }
+    }
+
+    /// Converts a point in the world to coordinates along the sides.
+    fn to_local(self, p: Point2) -> Point2 {
+        let d = p - self.origin;
+        Point2{x: dot(d, self.u), y: dot(d, self.v)}
+    }
+
+    /// Converts a direction in the world to a direction along the sides.
+    fn to_local_dir(self, d: Point2) -> Point2 {
+        Point2{x: dot(d, self.u), y: dot(d, self.v)}
+    }
+
+    /// Converts coordinates along the sides to a point in the world.
+    fn to_world(self, p: Point2) -> Point2 {
+        self.origin + self.u * p.x + self.v * p.y
+    }
+
+    /// Converts a direction along the sides to a direction in the world.
+    fn to_world_dir(self, d: Point2) -> Point2 {
+        self.u * d.x + self.v * d.y
+    }
+
+    fn center(&self) -> Point2 {
+        self.to_world(Point2{x: self.width / 2.0, y: self.height / 2.0})
+    }
+
+    /// Returns the corners of the rectangle grown by dx along its width and
+    /// dy along its height on every side.
+    fn grown(&self, dx: f64, dy: f64) -> Vec<Point2> {
+        let (x0, x1) = (-dx, self.width + dx);
+        let (y0, y1) = (-dy, self.height + dy);
+        vec![
+            self.to_world(Point2{x: x0, y: y0}),
+            self.to_world(Point2{x: x1, y: y0}),
+            self.to_world(Point2{x: x0, y: y1}),
+            self.to_world(Point2{x: x1, y: y1}),
+        ]
+    }
+
+}
+
+/// Function to see if a point lies inside (or on the edge of) a circle. The
+/// circle is defined by a center point and a radius.
+pub fn point_in_circle(p: Point2, c: Point2, r: f64) -> bool {
+    dot(p - c, p - c) <= r * r
+}
+
+/// Function to see if a point lies inside (or on the edge of) a rectangle. 
+/// The rectangle is defined by a list of four corners.
+pub fn point_in_rect(p: Point2, corners: &[Point2]) -> bool {
+    let frame = Frame::new(corners);
+    let local = frame.to_local(p);
+    within(local.x, 0.0, frame.width) && within(local.y, 0.0, frame.height)
+}
+
+/// Finds the contact between two circles, each defined by a center point and
+/// a radius. Returns None if the circles aren't overlapping. Circles sharing
+/// a center are separated along the x axis.
+pub fn circle_circle_contact(c1: Point2, r1: f64, c2: Point2, r2: f64) -> Option<Manifold> {
+
+    let d = c2 - c1;
+    let dist = length(d);
+    if dist >= r1 + r2 {
+        return None;
+    }
+    let normal = unit(d).unwrap_or(Point2{x: 1.0, y: 0.0});
+
+    Some(Manifold {
+        normal: Vec2::new_from_point(normal),
+        depth: r1 + r2 - dist,
+        points: vec![c1 + normal * r1],
+    })
+
+}
+
+/// Finds the contact between a circle and a rectangle. The circle is defined
+/// by a center point and a radius, and the rectangle by a list of four 
+/// corners. The normal points from the circle towards the rectangle. Returns
+/// None if they aren't overlapping.
+/// 
+/// If the circle's center is inside the rectangle, the circle is pushed out 
+/// through the nearest side.
+pub fn circle_rect_contact(c: Point2, r: f64, corners: &[Point2]) -> Option<Manifold> {
+
+    let frame = Frame::new(corners);
+    let local = frame.to_local(c);
+    let closest = Point2 {
+        x: local.x.clamp(0.0, frame.width),
+        y: local.y.clamp(0.0, frame.height),
+    };
+
+    let outside = closest - local;
+    let dist = length(outside);
+    if dist > 0.0 {
+        if dist >= r {
+            return None;
+        }
+        return Some(Manifold {
+            normal: Vec2::new_from_point(frame.to_world_dir(outside * (1.0 / dist))),
+            depth: r - dist,
+            points: vec![frame.to_world(closest)],
+        });
+    }
+
+    // The center is inside, so find the nearest side. Each side is given as 
+    // the distance to it, the direction out through it, and the point on it
+    // closest to the center.
+    let sides = [
+        (local.x, Point2{x: -1.0, y: 0.0}, Point2{x: 0.0, y: local.y}),
+        (frame.width - local.x, Point2{x: 1.0, y: 0.0}, Point2{x: frame.width, y: local.y}),
+        (local.y, Point2{x: 0.0, y: -1.0}, Point2{x: local.x, y: 0.0}),
+        (frame.height - local.y, Point2{x: 0.0, y: 1.0}, Point2{x: local.x, y: frame.height}),
+    ];
+    let mut nearest = sides[0];
+    for side in sides.iter().skip(1) {
+        if side.0 < nearest.0 {
+            nearest = *side;
+        }
+    }
+    let (dist, out, point) = nearest;
+
+    Some(Manifold {
+        normal: Vec2::new_from_point(frame.to_world_dir(out * -1.0)),
+        depth: r + dist,
+        points: vec![frame.to_world(point)],
+    })
+
+}
+
+/// Finds the contact between two rectangles, each defined by a list of four 
+/// corners, using the separating axis test. The normal is the side normal 
+/// along which the rectangles overlap least, pointing from the first towards
+/// the second. Returns None if they aren't overlapping.
+/// 
+/// The contact points are the corners of each rectangle which lie inside the
+/// other. If no corners are inside (the rectangles cross like a plus sign), 
+/// the point halfway between their centers is used.
+pub fn rect_rect_contact(corners1: &[Point2], corners2: &[Point2]) -> Option<Manifold> {
+
+    let (f1, f2) = (Frame::new(corners1), Frame::new(corners2));
+    let between = f2.center() - f1.center();
+
+    let mut best: Option<(f64, Point2)> = None;
+    for axis in [f1.u, f1.v, f2.u, f2.v].iter() {
+        let span = |corners: &[Point2]| {
+            corners.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                let d = dot(*p, *axis);
+                (lo.min(d), hi.max(d))
+            })
+        };
+        let (lo1, hi1) = span(corners1);
+        let (lo2, hi2) = span(corners2);
+        let overlap = hi1.min(hi2) - lo1.max(lo2);
+        if overlap <= 0.0 {
+            return None;
+        }
+        if best.is_none_or(|(depth, _)| overlap < depth) {
+            let normal = if dot(between, *axis) < 0.0 { *axis * -1.0 } else { *axis };
+            best = Some((overlap, normal));
+        }
+    }
+    let (depth, normal) = best?;
+
+    let mut points: Vec<Point2> = corners2.iter().filter(|p| point_in_rect(**p, corners1)).copied().collect();
+    points.extend(corners1.iter().filter(|p| point_in_rect(**p, corners2)));
+    if points.is_empty() {
+        points.push(f1.center() + between * 0.5);
+    }
+
+    Some(Manifold {
+        normal: Vec2::new_from_point(normal),
+        depth,
+        points,
+    })
+
+}
+
+/// Casts a ray from origin in the input direction against a circle, defined
+/// by a center point and a radius. Returns where the ray first hits the 
+/// circle, or None if it misses or the hit is further than max_distance.
+pub fn ray_circle(origin: Point2, direction: Vec2, max_distance: f64, c: Point2, r: f64) -> Option<RayHit> {
+
+    let dir = unit(Point2{x: direction.x, y: direction.y})?;
+    let m = origin - c;
+    let b = dot(m, dir);
+    let k = dot(m, m) - r * r;
+
+    // Starting outside and pointing away.
+    if k > 0.0 && b > 0.0 {
+        return None;
+    }
+    let discriminant = b * b - k;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let distance = (-b - discriminant.sqrt()).max(0.0);
+    if distance > max_distance {
+        return None;
+    }
+
+    let point = origin + dir * distance;
+    let normal = if k > 0.0 {
+        unit(point - c).unwrap_or(dir * -1.0)
+    } else {
+        dir * -1.0
+    };
+    Some(RayHit {
+        distance,
+        point,
+        normal: Vec2::new_from_point(normal),
+    })
+
+}
+
+/// Casts a ray from origin in the input direction against a rectangle, 
+/// defined by a list of four corners. Returns where the ray first hits the 
+/// rectangle, or None if it misses or the hit is further than max_distance.
+pub fn ray_rect(origin: Point2, direction: Vec2, max_distance: f64, corners: &[Point2]) -> Option<RayHit> {
+
+    let dir = unit(Point2{x: direction.x, y: direction.y})?;
+    let frame = Frame::new(corners);
+    let o = frame.to_local(origin);
+    let d = frame.to_local_dir(dir);
+
+    // Clip the ray against the slab between each pair of opposite sides, 
+    // remembering which side it entered through last.
+    let mut near = 0.0;
+    let mut far = max_distance;
+    let mut normal = dir * -1.0;
+    let slabs = [
+        (o.x, d.x, frame.width, Point2{x: 1.0, y: 0.0}),
+        (o.y, d.y, frame.height, Point2{x: 0.0, y: 1.0}),
+    ];
+    for (start, step, size, axis) in slabs.iter() {
+        if step.abs() < f64::EPSILON {
+            if !within(*start, 0.0, *size) {
+                return None;
+            }
+            continue;
+        }
+        let (mut t0, mut t1) = (-start / step, (size - start) / step);
+        let mut side = *axis * -1.0;
+        if t0 > t1 {
+            std::mem::swap(&mut t0, &mut t1);
+            side = *axis;
+        }
+        if t0 > near {
+            near = t0;
+            normal = frame.to_world_dir(side);
+        }
+        far = far.min(t1);
+        if near > far {
+            return None;
+        }
+    }
+
+    Some(RayHit {
+        distance: near,
+        point: origin + dir * near,
+        normal: Vec2::new_from_point(normal),
+    })
+
+}
+
+/// Finds when a circle moving by delta first touches a stationary circle. 
+/// Each circle is defined by a center point and a radius. Returns the 
+/// fraction of delta travelled before touching, from 0.0 to 1.0, or None if
+/// they don't touch. Circles which already overlap touch at 0.0.
+pub fn sweep_circle_circle(c1: Point2, r1: f64, delta: Vec2, c2: Point2, r2: f64) -> Option<f64> {
+
+    if circle_circle_intersect(c1, r1, c2, r2) {
+        return Some(0.0);
+    }
+    let len = delta.length();
+    ray_circle(c1, delta, len, c2, r1 + r2).map(|hit| hit.distance / len)
+
+}
+
+/// Finds when a circle moving by delta first touches a stationary rectangle.
+/// The circle is defined by a center point and a radius, and the rectangle by
+/// a list of four corners. Returns the fraction of delta travelled before 
+/// touching, from 0.0 to 1.0, or None if they don't touch. Shapes which
+/// already overlap touch at 0.0.
+/// 
+/// The circle's center is cast against the rectangle grown by the radius, 
+/// which has rounded corners. This is the same as casting it against the 
+/// rectangle grown along each side and a circle at each corner.
+pub fn sweep_circle_rect(c: Point2, r: f64, delta: Vec2, corners: &[Point2]) -> Option<f64> {
+
+    if circle_rect_contact(c, r, corners).is_some() {
+        return Some(0.0);
+    }
+    let len = delta.length();
+    let frame = Frame::new(corners);
+    let mut first: Option<f64> = None;
+    let mut hit = |distance: Option<f64>| {
+        if let Some(d) = distance {
+            first = Some(first.map_or(d, |f: f64| f.min(d)));
+        }
+    };
+    hit(ray_rect(c, delta, len, &frame.grown(r, 0.0)).map(|h| h.distance));
+    hit(ray_rect(c, delta, len, &frame.grown(0.0, r)).map(|h| h.distance));
+    for corner in corners {
+        hit(ray_circle(c, delta, len, *corner, r).map(|h| h.distance));
+    }
+    first.map(|distance| distance / len)
+
+}
+
 #[cfg(test)]
 mod collision_tests {
 
@@ -294,4 +672,210 @@ mod collision_tests {
         assert!(extremes[1].x == 4.0 && extremes[1].y == 2.0);
     }
 
-}
\ No newline at end of file
+    // the acceptable amount of error
+    const EPSILON: f64 = 0.0001;
+
+    /// The corners of an axis aligned rectangle, in the order given by 
+    /// GenericShape::get_corners().
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Vec<crate::math::Point2> {
+        use crate::math::Point2;
+        vec![
+            Point2{x, y},
+            Point2{x: x + w, y},
+            Point2{x, y: y + h},
+            Point2{x: x + w, y: y + h},
+        ]
+    }
+
+    #[test]
+    fn test_point_containment() {
+
+        use super::{point_in_circle, point_in_rect};
+        use crate::math::Point2;
+
+        let c = Point2{x: 0.0, y: 0.0};
+        assert!(point_in_circle(Point2{x: 3.0, y: 4.0}, c, 5.0));
+        assert!(!point_in_circle(Point2{x: 3.0, y: 4.1}, c, 5.0));
+
+        let corners = rect(0.0, 0.0, 4.0, 2.0);
+        assert!(point_in_rect(Point2{x: 2.0, y: 1.0}, &corners));
+        assert!(point_in_rect(Point2{x: 4.0, y: 2.0}, &corners));
+        assert!(!point_in_rect(Point2{x: 4.5, y: 1.0}, &corners));
+
+        // A square rotated by 45 degrees around the origin.
+        let h = 2.0_f64.sqrt();
+        let diamond = vec![
+            Point2{x: 0.0, y: 0.0},
+            Point2{x: h, y: h},
+            Point2{x: -h, y: h},
+            Point2{x: 0.0, y: 2.0 * h},
+        ];
+        assert!(point_in_rect(Point2{x: 0.0, y: h}, &diamond));
+        assert!(!point_in_rect(Point2{x: 1.2, y: 0.2}, &diamond));
+
+    }
+
+    #[test]
+    fn test_circle_circle_contact() {
+
+        use super::circle_circle_contact;
+        use crate::math::Point2;
+
+        let contact = circle_circle_contact(Point2{x: 0.0, y: 0.0}, 2.0, Point2{x: 3.0, y: 0.0}, 2.0).unwrap();
+        assert!((contact.normal.x - 1.0).abs() < EPSILON && contact.normal.y.abs() < EPSILON);
+        assert!((contact.depth - 1.0).abs() < EPSILON);
+        assert!((contact.points[0].x - 2.0).abs() < EPSILON);
+
+        assert!(circle_circle_contact(Point2{x: 0.0, y: 0.0}, 2.0, Point2{x: 4.0, y: 0.0}, 2.0).is_none());
+
+    }
+
+    #[test]
+    fn test_circle_rect_contact() {
+
+        use super::circle_rect_contact;
+        use crate::math::Point2;
+
+        let corners = rect(0.0, 0.0, 4.0, 4.0);
+
+        // Touching the left side from outside.
+        let contact = circle_rect_contact(Point2{x: -1.0, y: 2.0}, 2.0, &corners).unwrap();
+        assert!((contact.normal.x - 1.0).abs() < EPSILON && contact.normal.y.abs() < EPSILON);
+        assert!((contact.depth - 1.0).abs() < EPSILON);
+        assert!(contact.points[0].x.abs() < EPSILON && (contact.points[0].y - 2.0).abs() < EPSILON);
+
+        // Near a corner, the normal points at the corner.
+        let contact = circle_rect_contact(Point2{x: 5.0, y: 5.0}, 2.0, &corners).unwrap();
+        let diagonal = -(0.5_f64.sqrt());
+        assert!((contact.normal.x - diagonal).abs() < EPSILON && (contact.normal.y - diagonal).abs() < EPSILON);
+        assert!((contact.depth - (2.0 - 2.0_f64.sqrt())).abs() < EPSILON);
+
+        // Inside, the circle is pushed out through the nearest side.
+        let contact = circle_rect_contact(Point2{x: 2.0, y: 3.5}, 1.0, &corners).unwrap();
+        assert!(contact.normal.x.abs() < EPSILON && (contact.normal.y + 1.0).abs() < EPSILON);
+        assert!((contact.depth - 1.5).abs() < EPSILON);
+
+        assert!(circle_rect_contact(Point2{x: 5.5, y: 5.5}, 2.0, &corners).is_none());
+
+    }
+
+    #[test]
+    fn test_rect_rect_contact() {
+
+        use super::rect_rect_contact;
+
+        let a = rect(0.0, 0.0, 4.0, 4.0);
+        let b = rect(3.0, 1.0, 4.0, 4.0);
+        let contact = rect_rect_contact(&a, &b).unwrap();
+        assert!((contact.normal.x - 1.0).abs() < EPSILON && contact.normal.y.abs() < EPSILON);
+        assert!((contact.depth - 1.0).abs() < EPSILON);
+        assert_eq!(2, contact.points.len());
+
+        // Reversing the rectangles reverses the normal.
+        let contact = rect_rect_contact(&b, &a).unwrap();
+        assert!((contact.normal.x + 1.0).abs() < EPSILON);
+
+        // Crossed like a plus sign, no corners are inside.
+        let contact = rect_rect_contact(&rect(0.0, 2.0, 6.0, 2.0), &rect(2.0, 0.0, 2.0, 6.0)).unwrap();
+        assert_eq!(1, contact.points.len());
+        assert!((contact.depth - 2.0).abs() < EPSILON);
+
+        assert!(rect_rect_contact(&a, &rect(5.0, 0.0, 4.0, 4.0)).is_none());
+
+    }
+
+    #[test]
+    fn test_ray_circle() {
+
+        use super::ray_circle;
+        use crate::math::{Point2, Vec2};
+
+        let c = Point2{x: 10.0, y: 0.0};
+        let hit = ray_circle(Point2{x: 0.0, y: 0.0}, Vec2::new(2.0, 0.0), 100.0, c, 2.0).unwrap();
+        assert!((hit.distance - 8.0).abs() < EPSILON);
+        assert!((hit.point.x - 8.0).abs() < EPSILON);
+        assert!((hit.normal.x + 1.0).abs() < EPSILON);
+
+        assert!(ray_circle(Point2{x: 0.0, y: 0.0}, Vec2::new(1.0, 0.0), 5.0, c, 2.0).is_none());
+        assert!(ray_circle(Point2{x: 0.0, y: 0.0}, Vec2::new(-1.0, 0.0), 100.0, c, 2.0).is_none());
+        assert!(ray_circle(Point2{x: 0.0, y: 3.0}, Vec2::new(1.0, 0.0), 100.0, c, 2.0).is_none());
+
+        let hit = ray_circle(Point2{x: 10.5, y: 0.0}, Vec2::new(1.0, 0.0), 100.0, c, 2.0).unwrap();
+        assert_eq!(0.0, hit.distance);
+
+    }
+
+    #[test]
+    fn test_ray_rect() {
+
+        use super::ray_rect;
+        use crate::math::{Point2, Vec2};
+
+        let corners = rect(2.0, 2.0, 4.0, 4.0);
+        let hit = ray_rect(Point2{x: 0.0, y: 0.0}, Vec2::new(1.0, 1.0), 100.0, &corners).unwrap();
+        assert!((hit.distance - 8.0_f64.sqrt()).abs() < EPSILON);
+        assert!((hit.point.x - 2.0).abs() < EPSILON && (hit.point.y - 2.0).abs() < EPSILON);
+
+        let hit = ray_rect(Point2{x: 0.0, y: 3.0}, Vec2::new(1.0, 0.0), 100.0, &corners).unwrap();
+        assert!((hit.distance - 2.0).abs() < EPSILON);
+        assert!((hit.normal.x + 1.0).abs() < EPSILON && hit.normal.y.abs() < EPSILON);
+
+        let hit = ray_rect(Point2{x: 4.0, y: 10.0}, Vec2::new(0.0, -1.0), 100.0, &corners).unwrap();
+        assert!((hit.distance - 4.0).abs() < EPSILON);
+        assert!((hit.normal.y - 1.0).abs() < EPSILON);
+
+        assert!(ray_rect(Point2{x: 0.0, y: 3.0}, Vec2::new(1.0, 0.0), 1.0, &corners).is_none());
+        assert!(ray_rect(Point2{x: 0.0, y: 7.0}, Vec2::new(1.0, 0.0), 100.0, &corners).is_none());
+        assert!(ray_rect(Point2{x: 0.0, y: 3.0}, Vec2::new(-1.0, 0.0), 100.0, &corners).is_none());
+
+        // A square rotated by 45 degrees, with its left corner at (0, 5).
+        let h = 2.0_f64.sqrt();
+        let diamond = vec![
+            Point2{x: 0.0, y: 5.0},
+            Point2{x: h, y: 5.0 - h},
+            Point2{x: h, y: 5.0 + h},
+            Point2{x: 2.0 * h, y: 5.0},
+        ];
+        let hit = ray_rect(Point2{x: -3.0, y: 5.0}, Vec2::new(1.0, 0.0), 100.0, &diamond).unwrap();
+        assert!((hit.distance - 3.0).abs() < EPSILON);
+
+    }
+
+    #[test]
+    fn test_sweep_circle_circle() {
+
+        use super::sweep_circle_circle;
+        use crate::math::{Point2, Vec2};
+
+        let t = sweep_circle_circle(Point2{x: 0.0, y: 0.0}, 1.0, Vec2::new(10.0, 0.0), Point2{x: 6.0, y: 0.0}, 1.0).unwrap();
+        assert!((t - 0.4).abs() < EPSILON);
+
+        assert!(sweep_circle_circle(Point2{x: 0.0, y: 0.0}, 1.0, Vec2::new(3.0, 0.0), Point2{x: 6.0, y: 0.0}, 1.0).is_none());
+        assert_eq!(Some(0.0), sweep_circle_circle(Point2{x: 0.0, y: 0.0}, 1.0, Vec2::new(0.0, 0.0), Point2{x: 1.0, y: 0.0}, 1.0));
+
+    }
+
+    #[test]
+    fn test_sweep_circle_rect() {
+
+        use super::sweep_circle_rect;
+        use crate::math::{Point2, Vec2};
+
+        let corners = rect(4.0, 0.0, 2.0, 2.0);
+
+        // Moving straight into a side.
+        let t = sweep_circle_rect(Point2{x: 0.0, y: 1.0}, 1.0, Vec2::new(10.0, 0.0), &corners).unwrap();
+        assert!((t - 0.3).abs() < EPSILON);
+
+        // Passing just above the rectangle, the circle touches its corner.
+        let y = -1.0 + 0.5;
+        let t = sweep_circle_rect(Point2{x: 0.0, y}, 1.0, Vec2::new(10.0, 0.0), &corners).unwrap();
+        let touch = 4.0 - (1.0 - 0.25_f64).sqrt();
+        assert!((t - touch / 10.0).abs() < EPSILON);
+
+        assert!(sweep_circle_rect(Point2{x: 0.0, y: -1.5}, 1.0, Vec2::new(10.0, 0.0), &corners).is_none());
+        assert!(sweep_circle_rect(Point2{x: 0.0, y: 1.0}, 1.0, Vec2::new(2.0, 0.0), &corners).is_none());
+
+    }
+
+}
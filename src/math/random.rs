@@ -39,6 +39,18 @@ pub fn create_seed(debug: bool) -> Seed {
     }
 }
 
+/// Returns a Seed made from a number, so a game can be recreated from a 
+/// short seed typed on the command line. The number's bytes are repeated to
+/// fill the Seed.
+pub fn seed_from_u64(n: u64) -> Seed {
+    let bytes = n.to_le_bytes();
+    let mut seed: Seed = [0; 32];
+    for (i, byte) in seed.iter_mut().enumerate() {
+        *byte = bytes[i % bytes.len()];
+    }
+    seed
+}
+
 /// Wrapper function for RNG::from_seed(seed: Seed)
 pub fn from_seed(seed: Seed) -> RNG {
     RNG::from_seed(seed)
@@ -47,4 +59,9 @@ pub fn from_seed(seed: Seed) -> RNG {
 /// Wrapper function for RNG.next_u32().
 pub fn next_u32(rng: &mut RNG) -> u32 {
     rng.next_u32()
-}
\ No newline at end of file
+}
+
+/// Returns a random number from 0 up to, but not including, 1.
+pub fn next_f64(rng: &mut RNG) -> f64 {
+    next_u32(rng) as f64 / (u32::MAX as f64 + 1.0)
+}
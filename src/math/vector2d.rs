@@ -46,6 +46,11 @@ impl Vec2 {
         Vec2 {x: vector.x * ratio, y: vector.y * ratio}
     }
 
+    /// Returns the exact length of the vector
+    pub fn length(&self) -> f64 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
     pub fn dot_product(vec1: Vec2, vec2: Vec2) -> f64 {
         vec1.x * vec2.x + vec1.y * vec2.y
     }
@@ -82,6 +87,13 @@ mod vector2d_tests{
         assert_eq!(val, Vec2::dot_product(v1, v2));
     }
 
+    #[test]
+    fn test_length(){
+        let v = Vec2::new(3.0, -4.0);
+        assert_eq!(5.0, v.length());
+        assert_eq!(0.0, Vec2::new(0.0, 0.0).length());
+    }
+
     #[test]
     fn test_normal_unit(){
         let v = Vec2::new(3.0, 4.0);
@@ -0,0 +1,2 @@
+pub use self::software::{SoftwareGraphics, SoftwareTexture};
+mod software;
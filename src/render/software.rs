@@ -0,0 +1,321 @@
+use graphics::{Context, Graphics, ImageSize};
+use graphics::draw_state::{Blend, DrawState, Stencil};
+use graphics::types::Color;
+use image::RgbaImage;
+use std::io;
+use std::path::Path;
+
+/// An RGBA image which SoftwareGraphics can draw, loaded from a file.
+#[derive(Clone, Debug)]
+pub struct SoftwareTexture {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl SoftwareTexture {
+
+    /// Creates a SoftwareTexture from rows of RGBA pixels, starting at the
+    /// top left. Returns None if there aren't width * height pixels.
+    pub fn new(width: u32, height: u32, pixels: Vec<[u8; 4]>) -> Option<Self> {
+        if pixels.len() == (width * height) as usize {
+            Some(Self { width, height, pixels })
+        } else {
+            None
+        }
+    }
+
+    /// Loads a SoftwareTexture from an image file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let image = image::open(path)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?
+            .to_rgba();
+        Ok(Self::from_image(&image))
+    }
+
+    /// Creates a SoftwareTexture from an image in memory.
+    pub fn from_image(image: &RgbaImage) -> Self {
+        let (width, height) = image.dimensions();
+        let pixels = image.pixels().map(|pixel| pixel.data).collect();
+        Self { width, height, pixels }
+    }
+
+    /// Returns the pixel at x, y.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+}
+
+impl ImageSize for SoftwareTexture {
+    fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+/// How many steps each pixel is split into when placing the corners of a
+/// triangle.
+const SUBPIXELS: f32 = 256.0;
+
+/// A Graphics backend which draws into an RGBA image in memory, so the game
+/// can be drawn without a window or an OpenGL context.
+///
+/// Triangles are filled wherever they cover the center of a pixel. Like a
+/// GPU, the corners of each triangle are snapped to a fraction of a pixel,
+/// so whether a pixel is covered is worked out exactly. Pixels on an edge
+/// shared by two triangles are only filled by one of them (the top left
+/// rule), so shapes built from several triangles have no gaps and don't
+/// blend twice along their seams. There's no antialiasing, and textures are
+/// sampled without smoothing.
+///
+/// Every Blend mode and Stencil test is supported. Scissor rectangles are in
+/// pixels from the top left of the image.
+pub struct SoftwareGraphics {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+    stencil: Vec<u8>,
+}
+
+impl SoftwareGraphics {
+
+    /// Creates a transparent image of width by height pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        let size = (width * height) as usize;
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 4]; size],
+            stencil: vec![0; size],
+        }
+    }
+
+    /// Calls f with a Context covering the whole image, in pixels, like
+    /// GlGraphics::draw() does for a window.
+    pub fn draw<F: FnOnce(Context, &mut Self)>(&mut self, f: F) {
+        let c = Context::new_abs(self.width as f64, self.height as f64);
+        f(c, self);
+    }
+
+    /// Returns the width and height of the image.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the colour of the pixel at x, y.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        to_bytes(self.pixels[(y * self.width + x) as usize])
+    }
+
+    /// Returns the image drawn so far.
+    pub fn to_image(&self) -> RgbaImage {
+        let bytes = self.pixels.iter().flat_map(|pixel| to_bytes(*pixel).to_vec()).collect();
+        RgbaImage::from_raw(self.width, self.height, bytes).unwrap()
+    }
+
+    /// Saves the image drawn so far. The format depends on the path's
+    /// extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.to_image().save(path)
+    }
+
+    /// Fills every pixel covered by the triangles, which are given in the
+    /// normalised device coordinates of the Graphics trait. shade returns
+    /// the colour of a pixel from its weights for each corner of the triangle
+    /// it's in.
+    fn fill<S: Fn(usize, [f32; 3]) -> Color>(&mut self, draw_state: &DrawState, vertices: &[[f32; 2]], shade: S) {
+        let (width, height) = (self.width as f32 * SUBPIXELS, self.height as f32 * SUBPIXELS);
+        let pixel = SUBPIXELS as i64;
+        let (min_x, min_y, max_x, max_y) = match draw_state.scissor {
+            Some([x, y, w, h]) => (x, y, (x + w).min(self.width), (y + h).min(self.height)),
+            None => (0, 0, self.width, self.height),
+        };
+
+        for (t, triangle) in vertices.chunks_exact(3).enumerate() {
+            let mut corners = [[0; 2]; 3];
+            for (corner, vertex) in corners.iter_mut().zip(triangle.iter()) {
+                *corner = [
+                    ((vertex[0] + 1.0) / 2.0 * width).round() as i64,
+                    ((1.0 - vertex[1]) / 2.0 * height).round() as i64,
+                ];
+            }
+            let area = edge(corners[0], corners[1], corners[2]);
+            if area == 0 {
+                continue;
+            }
+            // Wind every triangle the same way, so the edge tests agree.
+            let order = if area > 0 { [0, 1, 2] } else { [0, 2, 1] };
+            let [a, b, c] = [corners[order[0]], corners[order[1]], corners[order[2]]];
+            let area = area.abs() as f32;
+
+            let lo = |i: usize| (a[i].min(b[i]).min(c[i]) / pixel).max(0) as u32;
+            let hi = |i: usize| ((a[i].max(b[i]).max(c[i]) + pixel - 1) / pixel).max(0) as u32;
+            for y in lo(1).max(min_y)..hi(1).min(max_y) {
+                for x in lo(0).max(min_x)..hi(0).min(max_x) {
+                    let p = [x as i64 * pixel + pixel / 2, y as i64 * pixel + pixel / 2];
+                    let weights = [edge(b, c, p), edge(c, a, p), edge(a, b, p)];
+                    let edges = [(b, c), (c, a), (a, b)];
+                    let inside = weights.iter().zip(edges.iter())
+                        .all(|(w, (from, to))| *w > 0 || (*w == 0 && is_top_left(*from, *to)));
+                    if !inside {
+                        continue;
+                    }
+                    let mut unordered = [0.0; 3];
+                    for (i, weight) in order.iter().zip(weights.iter()) {
+                        unordered[*i] = *weight as f32 / area;
+                    }
+                    let color = shade(t * 3, unordered);
+                    self.plot(draw_state, x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Blends color into the pixel at x, y, if it passes the stencil test.
+    fn plot(&mut self, draw_state: &DrawState, x: u32, y: u32, color: Color) {
+        let i = (y * self.width + x) as usize;
+        match draw_state.stencil {
+            Some(Stencil::Clip(value)) => {
+                self.stencil[i] = value;
+                return;
+            },
+            Some(Stencil::Inside(value)) if self.stencil[i] != value => return,
+            Some(Stencil::Outside(value)) if self.stencil[i] == value => return,
+            _ => (),
+        }
+
+        let dest = self.pixels[i];
+        let blend = |f: &dyn Fn(f32, f32) -> f32, alpha: f32| {
+            [f(color[0], dest[0]), f(color[1], dest[1]), f(color[2], dest[2]), alpha]
+        };
+        let alpha = color[3];
+        let result = match draw_state.blend {
+            None => color,
+            Some(Blend::Alpha) => blend(&|src, dest| src * alpha + dest * (1.0 - alpha), alpha + dest[3] * (1.0 - alpha)),
+            Some(Blend::Add) => blend(&|src, dest| src + dest, alpha + dest[3]),
+            Some(Blend::Multiply) => blend(&|src, dest| src * dest, alpha * dest[3]),
+            Some(Blend::Invert) => blend(&|src, dest| src - dest, dest[3]),
+        };
+        self.pixels[i] = [
+            result[0].clamp(0.0, 1.0),
+            result[1].clamp(0.0, 1.0),
+            result[2].clamp(0.0, 1.0),
+            result[3].clamp(0.0, 1.0),
+        ];
+    }
+
+}
+
+impl Graphics for SoftwareGraphics {
+    type Texture = SoftwareTexture;
+
+    fn clear_color(&mut self, color: Color) {
+        for pixel in self.pixels.iter_mut() {
+            *pixel = color;
+        }
+    }
+
+    fn clear_stencil(&mut self, value: u8) {
+        for stencil in self.stencil.iter_mut() {
+            *stencil = value;
+        }
+    }
+
+    fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]]))
+    {
+        let color = *color;
+        f(&mut |vertices| self.fill(draw_state, vertices, |_, _| color));
+    }
+
+    fn tri_list_uv<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], texture: &SoftwareTexture, mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 2]]))
+    {
+        let color = *color;
+        let (width, height) = texture.get_size();
+        f(&mut |vertices, uvs| self.fill(draw_state, vertices, |first, weights| {
+            let uv = |i: usize| (0..3).map(|j| uvs[first + j][i] * weights[j]).sum::<f32>();
+            let x = ((uv(0) * width as f32) as u32).min(width - 1);
+            let y = ((uv(1) * height as f32) as u32).min(height - 1);
+            let texel = texture.pixel(x, y);
+            [
+                color[0] * texel[0] as f32 / 255.0,
+                color[1] * texel[1] as f32 / 255.0,
+                color[2] * texel[2] as f32 / 255.0,
+                color[3] * texel[3] as f32 / 255.0,
+            ]
+        }));
+    }
+}
+
+/// Returns twice the signed area of the triangle a, b, p: positive when p is
+/// to the right of the edge from a to b (in image coordinates, where y points
+/// down).
+fn edge(a: [i64; 2], b: [i64; 2], p: [i64; 2]) -> i64 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Returns true if the edge from a to b is a top or left edge of a triangle
+/// wound the way fill() winds them.
+fn is_top_left(a: [i64; 2], b: [i64; 2]) -> bool {
+    (a[1] == b[1] && b[0] < a[0]) || b[1] < a[1]
+}
+
+/// Converts a colour to bytes.
+fn to_bytes(color: Color) -> [u8; 4] {
+    [
+        (color[0] * 255.0).round() as u8,
+        (color[1] * 255.0).round() as u8,
+        (color[2] * 255.0).round() as u8,
+        (color[3] * 255.0).round() as u8,
+    ]
+}
+
+#[cfg(test)]
+mod software_tests {
+
+    use super::{SoftwareGraphics, SoftwareTexture};
+    use graphics::{clear, Image, Rectangle};
+
+    #[test]
+    fn test_fill_rectangles() {
+        let mut g = SoftwareGraphics::new(8, 8);
+        g.draw(|c, g| {
+            clear([1.0, 1.0, 1.0, 1.0], g);
+            Rectangle::new([1.0, 0.0, 0.0, 1.0]).draw([2.0, 2.0, 4.0, 4.0], &c.draw_state, c.transform, g);
+        });
+        assert_eq!([255, 255, 255, 255], g.pixel(1, 1));
+        assert_eq!([255, 0, 0, 255], g.pixel(2, 2));
+        assert_eq!([255, 0, 0, 255], g.pixel(5, 5));
+        assert_eq!([255, 255, 255, 255], g.pixel(6, 6));
+        let filled = (0..64).filter(|i| g.pixel(i % 8, i / 8) == [255, 0, 0, 255]).count();
+        assert_eq!(16, filled);
+    }
+
+    #[test]
+    fn test_blend_seams_once() {
+        let mut g = SoftwareGraphics::new(8, 8);
+        g.draw(|c, g| {
+            clear([0.0, 0.0, 0.0, 1.0], g);
+            Rectangle::new([1.0, 1.0, 1.0, 0.5]).draw([0.0, 0.0, 8.0, 8.0], &c.draw_state, c.transform, g);
+        });
+        // The diagonal shared by the rectangle's triangles isn't blended
+        // twice.
+        for i in 0..8 {
+            assert_eq!([128, 128, 128, 255], g.pixel(i, i));
+            assert_eq!([128, 128, 128, 255], g.pixel(7 - i, i));
+        }
+    }
+
+    #[test]
+    fn test_draw_textures() {
+        let texture = SoftwareTexture::new(2, 1, vec![[255, 0, 0, 255], [0, 0, 255, 255]]).unwrap();
+        let mut g = SoftwareGraphics::new(4, 2);
+        g.draw(|c, g| {
+            Image::new().rect([0.0, 0.0, 4.0, 2.0]).draw(&texture, &c.draw_state, c.transform, g);
+        });
+        assert_eq!([255, 0, 0, 255], g.pixel(1, 0));
+        assert_eq!([0, 0, 255, 255], g.pixel(2, 1));
+    }
+
+}
@@ -1,4 +1,4 @@
-use crate::math::{Point2, collision};
+use crate::math::{Point2, Vec2, collision};
 use crate::game::consts::PI;
 pub use graphics::{Rectangle, Context, Graphics};
 use graphics::Transformed;
@@ -18,6 +18,10 @@ pub enum ShapeVariant {
 }
 
 /// A generic shape which can be used to draw every shape in the game.
+///
+/// The shape also remembers the position and rotation it had at the start of
+/// the current step of the simulation, so it can be drawn part way between
+/// the two when a frame falls between steps. See interpolate().
 #[derive(Clone, Copy)]
 pub struct GenericShape {
     pub shape: ShapeVariant,
@@ -25,6 +29,8 @@ pub struct GenericShape {
     position: Point2,
     rotation: Option<f64>,
     offset: Option<Point2>,
+    previous_position: Point2,
+    previous_rotation: Option<f64>,
 }
 
 impl GenericShape {
@@ -38,6 +44,8 @@ impl GenericShape {
             position: position,
             rotation: None,
             offset: None,
+            previous_position: position,
+            previous_rotation: None,
         }
     }
 
@@ -68,6 +76,32 @@ impl GenericShape {
         self.color = new_color;
     }
 
+    /// Remembers the current position and rotation as where the shape was at
+    /// the start of a step of the simulation.
+    pub fn save_transform(&mut self) {
+        self.previous_position = self.position;
+        self.previous_rotation = self.rotation;
+    }
+
+    /// Returns a copy of the shape part way between where it was when 
+    /// save_transform() was last called and where it is now. alpha is how
+    /// far, from 0 (where it was) to 1 (where it is). Rotations turn the 
+    /// shortest way round.
+    pub fn interpolate(&self, alpha: f64) -> Self {
+        let mut shape = *self;
+        shape.position = self.previous_position + (self.position - self.previous_position) * alpha;
+        if let (Some(previous), Some(current)) = (self.previous_rotation, self.rotation) {
+            let turn = (current - previous + PI).rem_euclid(2.0 * PI) - PI;
+            shape.rotation = Some(previous + turn * alpha);
+        }
+        shape
+    }
+
+    /// Function to return the private Color field.
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
     /// Function to return the private Position field.
     pub fn get_position(&self) -> Point2 { 
         self.position
@@ -280,6 +314,28 @@ impl GenericShape {
 
     }
 
+    /// Function to find the axis-aligned bounding box of the shape. Returns
+    /// the top left and bottom right corners of the box.
+    pub fn bounds(&self) -> (Point2, Point2) {
+        match self.shape {
+            ShapeVariant::Circle{size: _s, radius: r} => {
+                let extent = Point2{x: r, y: r};
+                let center = self.center_point();
+                (center - extent, center + extent)
+            },
+            ShapeVariant::Rect{width: _w, height: _h} => {
+                let corners = self.get_corners().unwrap_or_default();
+                let mut min = Point2{x: f64::INFINITY, y: f64::INFINITY};
+                let mut max = Point2{x: f64::NEG_INFINITY, y: f64::NEG_INFINITY};
+                for p in corners {
+                    min = Point2{x: min.x.min(p.x), y: min.y.min(p.y)};
+                    max = Point2{x: max.x.max(p.x), y: max.y.max(p.y)};
+                }
+                (min, max)
+            }
+        }
+    }
+
     /// Function to set the private Offset field.
     pub fn set_offset(&mut self, new_offset: Point2){
         self.offset = Some(new_offset);
@@ -381,4 +437,28 @@ pub fn check_collision(s1: GenericShape, s2: GenericShape) -> bool {
 
     }
 
-}
\ No newline at end of file
+}
+/// Function which finds the contact between two generic shapes, or None if 
+/// they aren't colliding. The normal of the contact points from s1 towards 
+/// s2.
+pub fn contact(s1: GenericShape, s2: GenericShape) -> Option<collision::Manifold> {
+
+    match (s1.shape, s1.get_corners(), s2.shape, s2.get_corners()) {
+        (_, Some(c1), _, Some(c2)) => collision::rect_rect_contact(&c1, &c2),
+        (ShapeVariant::Circle{size: _s, radius: r}, None, _, Some(c2)) => {
+            collision::circle_rect_contact(s1.center_point(), r, &c2)
+        },
+        (_, Some(c1), ShapeVariant::Circle{size: _s, radius: r}, None) => {
+            // Found from the circle's side, so the normal is flipped.
+            collision::circle_rect_contact(s2.center_point(), r, &c1).map(|mut manifold| {
+                manifold.normal = Vec2::new(-manifold.normal.x, -manifold.normal.y);
+                manifold
+            })
+        },
+        (ShapeVariant::Circle{size: _s1, radius: r1}, None, ShapeVariant::Circle{size: _s2, radius: r2}, None) => {
+            collision::circle_circle_contact(s1.center_point(), r1, s2.center_point(), r2)
+        },
+        _ => None,
+    }
+
+}
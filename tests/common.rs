@@ -1,10 +1,105 @@
+#![allow(dead_code)]
 extern crate rust_game;
 use rust_game::levels::Level;
+use rust_game::levels::map::{Map, MapIdx};
+use rust_game::ecs::EntityId;
+use rust_game::entity::enemy::spawn_enemy;
+use rust_game::entity::tile::{Tile, TileVariant};
+use rust_game::entity::towers::bullet::{Bullet, spawn_projectile};
+use rust_game::math::{Point2, Vec2};
 use rust_game::math::random::create_seed;
+use rust_game::game::{GameController, GameModel, Rules};
+use piston::input::{Button, ButtonArgs, ButtonState, Event, Input};
+use rust_game::game::consts::{map_idx_to_point2, BEACON_SIZE, BULLET_HEIGHT, BULLET_WIDTH, ENEMY_RADIUS, TILE_SIZE};
 
 pub fn setup_level() -> Level {
 
     let seed = create_seed(true);
     Level::new(seed)
     
-}
\ No newline at end of file
+}
+
+/// The length of a tick in seconds, at the rate the game is updated.
+pub const DT: f64 = 1.0 / 120.0;
+
+/// Creates a Map from rows of characters, where '#' is a Tile::Wall and any
+/// other character is a Tile::Floor.
+pub fn setup_map(rows: &[&str]) -> Map {
+
+    let mut map = Map::new();
+    for (h, row) in rows.iter().enumerate() {
+        for (w, c) in row.chars().enumerate() {
+            let idx = MapIdx::new(w as i32, h as i32);
+            let variant = match c {
+                '#' => TileVariant::Wall,
+                _ => TileVariant::Floor,
+            };
+            map.insert(idx, Tile::new(variant, idx));
+        }
+    }
+    map
+
+}
+
+/// A small walled arena with two Beacons, a Player start and a spawner, for
+/// testing a GameModel.
+pub const ARENA: [&str; 6] = [
+    "##########",
+    "#B......B#",
+    "#........#",
+    "#...P....#",
+    "#S.......#",
+    "##########",
+];
+
+/// Creates a GameModel on the ARENA, played by the input Rules.
+pub fn setup_model(rules: Rules) -> GameModel {
+
+    setup_model_from(&ARENA, rules)
+
+}
+
+/// Creates a GameModel on a Level made from rows of characters (see 
+/// Level::from_ascii()), played by the input Rules.
+pub fn setup_model_from(rows: &[&str], rules: Rules) -> GameModel {
+
+    let seed = create_seed(true);
+    GameModel::with_rules(Level::from_ascii(rows, seed), seed, rules).unwrap()
+
+}
+
+/// Creates a GameController on the ARENA, played by the default Rules.
+pub fn setup_controller() -> GameController {
+
+    let seed = create_seed(true);
+    GameController::with_rules(Level::from_ascii(&ARENA, seed), seed, Rules::default()).unwrap()
+
+}
+
+/// Returns the center of the Tile at idx.
+pub fn tile_center(idx: MapIdx) -> Point2 {
+    map_idx_to_point2(idx) + Point2{x: TILE_SIZE / 2.0, y: TILE_SIZE / 2.0}
+}
+
+/// Returns a point on the edge of the first Beacon. Enemies are caught 
+/// crossing the edge of a Beacon, so this is where they reach it.
+pub fn beacon_edge(model: &GameModel) -> Point2 {
+    model.beacons[0].shape.center_point() + Point2{x: BEACON_SIZE / 2.0, y: 0.0}
+}
+
+/// Creates an Enemy with nowhere to go, centered on center.
+pub fn enemy_at(model: &mut GameModel, center: Point2) -> EntityId {
+    spawn_enemy(&mut model.world, center - Point2{x: ENEMY_RADIUS, y: ENEMY_RADIUS})
+}
+
+/// Creates a projectile which doesn't move, centered on center.
+pub fn projectile_at(model: &mut GameModel, center: Point2) -> EntityId {
+    let position = center - Point2{x: BULLET_WIDTH / 2.0, y: BULLET_HEIGHT / 2.0};
+    spawn_projectile(&mut model.world, Bullet::new(position, Vec2::new(0.0, 0.0)))
+}
+
+/// Presses or releases a Button, as the window would.
+pub fn button(controller: &mut GameController, button: Button, state: ButtonState) {
+    let args = ButtonArgs{state, button, scancode: None};
+    controller.handle_event(&Event::Input(Input::Button(args), None));
+}
@@ -0,0 +1,106 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_enemy_shot_twice_dies_once(){
+    use rust_game::levels::map::MapIdx;
+
+    let mut controller = common::setup_controller();
+    let center = common::tile_center(MapIdx::new(6, 2));
+    let enemy = common::enemy_at(&mut controller.model, center);
+    let first = common::projectile_at(&mut controller.model, center);
+    let second = common::projectile_at(&mut controller.model, center);
+
+    controller.tick(common::DT);
+    let world = &controller.model.world;
+    assert!(!world.is_alive(enemy));
+    assert_eq!(1, controller.model.kills);
+    // Only the first projectile hits, and the second carries on.
+    assert!(!world.is_alive(first));
+    assert!(world.is_alive(second));
+    assert!(world.pickups.len() <= 1);
+
+}
+
+#[test]
+fn test_enemy_reaching_beacon_cant_be_shot(){
+    use rust_game::game::consts::ENEMY_DAMAGE;
+
+    let mut controller = common::setup_controller();
+    let center = common::beacon_edge(&controller.model);
+    let health = controller.model.beacons[0].health.current;
+    let enemy = common::enemy_at(&mut controller.model, center);
+    let projectile = common::projectile_at(&mut controller.model, center);
+
+    controller.tick(common::DT);
+    let world = &controller.model.world;
+    assert!(!world.is_alive(enemy));
+    assert!(world.is_alive(projectile));
+    assert_eq!(0, controller.model.kills);
+    assert_eq!(health - ENEMY_DAMAGE, controller.model.beacons[0].health.current);
+
+}
+
+#[test]
+fn test_enemy_reaching_beacon_doesnt_hurt_player(){
+    use rust_game::game::consts::ENEMY_DAMAGE;
+
+    let mut controller = common::setup_controller();
+    let center = common::beacon_edge(&controller.model);
+    let (beacon, player) = (controller.model.beacons[0].health.current, controller.model.player.health.current);
+    controller.model.player.teleport(center);
+    let enemy = common::enemy_at(&mut controller.model, center);
+
+    controller.tick(common::DT);
+    assert!(!controller.model.world.is_alive(enemy));
+    assert_eq!(beacon - ENEMY_DAMAGE, controller.model.beacons[0].health.current);
+    assert_eq!(player, controller.model.player.health.current);
+
+}
+
+#[test]
+fn test_trapped_enemy_shot_dies_once(){
+    use rust_game::entity::tile::TrapKind;
+    use rust_game::game::consts::SPIKE_TRAP_DAMAGE;
+    use rust_game::levels::map::MapIdx;
+
+    let mut controller = common::setup_controller();
+    let trap = MapIdx::new(4, 3);
+    controller.model.player.resources = 1;
+    controller.model.player.teleport(common::tile_center(trap));
+    assert!(controller.model.build_trap(TrapKind::Spike));
+    controller.model.player.teleport(common::tile_center(MapIdx::new(7, 4)));
+
+    let enemy = common::enemy_at(&mut controller.model, common::tile_center(trap));
+    controller.model.world.healths[enemy].current = SPIKE_TRAP_DAMAGE;
+    let projectile = common::projectile_at(&mut controller.model, common::tile_center(trap));
+
+    controller.tick(common::DT);
+    let world = &controller.model.world;
+    assert!(!world.is_alive(enemy));
+    assert!(world.is_alive(projectile));
+    assert_eq!(1, controller.model.kills);
+
+}
+
+#[test]
+fn test_despawned_ids_stay_despawned(){
+    use rust_game::levels::map::MapIdx;
+
+    let mut controller = common::setup_controller();
+    let center = common::tile_center(MapIdx::new(6, 2));
+    let enemy = common::enemy_at(&mut controller.model, center);
+    common::projectile_at(&mut controller.model, center);
+    controller.tick(common::DT);
+
+    // The new Enemy reuses a slot, but the old id can't reach it.
+    let replacement = common::enemy_at(&mut controller.model, center);
+    assert_eq!(enemy.index(), replacement.index());
+    assert!(controller.model.world.enemies.get(enemy).is_none());
+    assert!(!controller.model.kill_enemy(enemy));
+    assert!(controller.model.kill_enemy(replacement));
+    assert!(!controller.model.kill_enemy(replacement));
+    assert_eq!(2, controller.model.kills);
+
+}
@@ -0,0 +1,47 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_hit_enemy_flashes(){
+    use rust_game::levels::map::MapIdx;
+
+    let mut controller = common::setup_controller();
+    let model = &mut controller.model;
+    let center = common::tile_center(MapIdx::new(6, 2));
+    let enemy = common::enemy_at(model, center);
+    model.world.healths[enemy].current *= 2;
+    common::projectile_at(model, center);
+
+    controller.tick(common::DT);
+    let effects = &controller.view.effects;
+    assert!(controller.model.world.is_alive(enemy));
+    assert!(effects.is_flashing(enemy));
+    assert!(!effects.particles.is_empty());
+
+    // The flash wears off, and so do the sparks.
+    for _ in 0..60 {
+        controller.tick(common::DT);
+    }
+    let effects = &controller.view.effects;
+    assert!(!effects.is_flashing(enemy));
+    assert!(effects.particles.is_empty());
+
+}
+
+#[test]
+fn test_beacon_damage_shakes_screen(){
+
+    let mut controller = common::setup_controller();
+    let model = &mut controller.model;
+    let center = common::beacon_edge(model);
+    common::enemy_at(model, center);
+
+    let offset = controller.view.effects.shake.offset();
+    assert_eq!((0.0, 0.0), (offset.x, offset.y));
+    controller.tick(common::DT);
+    let offset = controller.view.effects.shake.offset();
+    assert!(offset.x != 0.0 || offset.y != 0.0);
+    assert!(!controller.view.effects.particles.is_empty());
+
+}
@@ -0,0 +1,77 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_kills_are_reported_once(){
+    use rust_game::game::GameEvent;
+    use rust_game::levels::map::MapIdx;
+
+    let mut controller = common::setup_controller();
+    let model = &mut controller.model;
+    let center = common::tile_center(MapIdx::new(6, 2));
+    let enemy = common::enemy_at(model, center);
+    common::projectile_at(model, center);
+    common::projectile_at(model, center);
+
+    let events = controller.tick(common::DT);
+    let killed = events.iter().filter(|event| matches!(event, GameEvent::EnemyKilled{id, ..} if *id == enemy)).count();
+    assert_eq!(1, killed);
+
+    // Each tick only returns its own events.
+    let events = controller.tick(common::DT);
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::EnemyKilled{..})));
+
+}
+
+#[test]
+fn test_beacon_damage_is_reported(){
+    use rust_game::entity::health::DamageOutcome;
+    use rust_game::game::GameEvent;
+
+    let mut controller = common::setup_controller();
+    let model = &mut controller.model;
+    let center = common::beacon_edge(model);
+    common::enemy_at(model, center);
+
+    controller.tick(common::DT);
+    let events = controller.events();
+    assert!(events.iter().any(|event| matches!(event, GameEvent::BeaconDamaged{beacon: 0, outcome: DamageOutcome::Damaged})));
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::BeaconFallen{..} | GameEvent::EnemyKilled{..})));
+
+}
+
+#[test]
+fn test_building_is_reported(){
+    use rust_game::entity::tile::TrapKind;
+    use rust_game::game::GameEvent;
+    use rust_game::levels::map::MapIdx;
+
+    let mut controller = common::setup_controller();
+    let model = &mut controller.model;
+    model.player.resources = 2;
+    model.create_tower();
+    model.player.teleport(common::tile_center(MapIdx::new(6, 2)));
+    assert!(model.build_trap(TrapKind::Slow));
+
+    assert!(matches!(model.events[..], [
+        ..,
+        GameEvent::TowerBuilt{..},
+        GameEvent::TrapBuilt{kind: TrapKind::Slow, ..},
+    ]));
+
+}
+
+#[test]
+fn test_game_over_is_reported(){
+    use rust_game::game::{GameEvent, Outcome};
+
+    let mut controller = common::setup_controller();
+    controller.tick(common::DT);
+    assert!(!controller.events().iter().any(|event| matches!(event, GameEvent::GameOver{..})));
+
+    controller.model.player.health.current = 0;
+    let events = controller.tick(common::DT);
+    assert!(matches!(events.last(), Some(GameEvent::GameOver{outcome: Outcome::Lost})));
+
+}
@@ -0,0 +1,131 @@
+extern crate rust_game;
+
+mod common;
+
+use rust_game::game::GameController;
+
+/// Sends an update event, which ticks the game unless it's paused.
+fn update(controller: &mut GameController) {
+    use piston::input::{Event, Loop, UpdateArgs};
+
+    controller.handle_event(&Event::Loop(Loop::Update(UpdateArgs{dt: common::DT})));
+}
+
+#[test]
+fn test_rebound_attack(){
+    use rust_game::entity::player::PlayerState;
+    use rust_game::game::{Action, Binding};
+    use piston::input::{Button, ButtonState, Key, MouseButton};
+
+    let mut controller = common::setup_controller();
+    controller.bindings.rebind(Action::Attack, Binding::Key(Key::J));
+
+    common::button(&mut controller, Button::Keyboard(Key::Space), ButtonState::Press);
+    update(&mut controller);
+    assert!(!matches!(controller.model.player.state, PlayerState::Attacking));
+
+    // Either Binding starts the Swing, but holding them only swings once
+    controller.bindings.bind(Action::Attack, Binding::Mouse(MouseButton::Right));
+    common::button(&mut controller, Button::Keyboard(Key::J), ButtonState::Press);
+    update(&mut controller);
+    assert!(matches!(controller.model.player.state, PlayerState::Attacking));
+    let swing = controller.model.player.attack.swing_id;
+    common::button(&mut controller, Button::Mouse(MouseButton::Right), ButtonState::Press);
+    common::button(&mut controller, Button::Keyboard(Key::J), ButtonState::Release);
+    update(&mut controller);
+    assert_eq!(swing, controller.model.player.attack.swing_id);
+
+}
+
+#[test]
+fn test_pause(){
+    use piston::input::{Button, ButtonState, Key};
+
+    let mut controller = common::setup_controller();
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Press);
+    assert!(controller.is_paused());
+    assert!(controller.check_state());
+
+    // Nothing happens while paused, however long the key is held
+    let time = controller.model.elapsed;
+    update(&mut controller);
+    update(&mut controller);
+    assert!(controller.is_paused());
+    assert_eq!(time, controller.model.elapsed);
+
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Release);
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Press);
+    assert!(!controller.is_paused());
+    update(&mut controller);
+    assert!(controller.model.elapsed > time);
+
+}
+
+#[test]
+fn test_rebind_while_paused(){
+    use rust_game::entity::player::PlayerState;
+    use rust_game::game::{Action, Binding, Bindings};
+    use piston::input::{Button, ButtonState, Key};
+    use std::env;
+
+    let mut controller = common::setup_controller();
+    let path = env::temp_dir().join("rust_game_rebind_test.txt");
+    controller.bindings_path = Some(path.clone());
+
+    // Rebinding only works while paused
+    common::button(&mut controller, Button::Keyboard(Key::F1), ButtonState::Press);
+    common::button(&mut controller, Button::Keyboard(Key::F1), ButtonState::Release);
+    assert_eq!(None, controller.rebinding());
+
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Press);
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Release);
+    // Each press of F1 chooses the next Action, up to Attack
+    for _ in 0..5 {
+        common::button(&mut controller, Button::Keyboard(Key::F1), ButtonState::Press);
+        common::button(&mut controller, Button::Keyboard(Key::F1), ButtonState::Release);
+    }
+    assert_eq!(Some(Action::Attack), controller.rebinding());
+
+    common::button(&mut controller, Button::Keyboard(Key::J), ButtonState::Press);
+    assert_eq!(None, controller.rebinding());
+    assert_eq!(&[Binding::Key(Key::J)], controller.bindings.get(Action::Attack));
+    assert_eq!(controller.bindings, Bindings::load(&path).unwrap());
+
+    // The key bound doesn't attack until it's pressed again
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Press);
+    update(&mut controller);
+    assert!(!matches!(controller.model.player.state, PlayerState::Attacking));
+    common::button(&mut controller, Button::Keyboard(Key::J), ButtonState::Release);
+    common::button(&mut controller, Button::Keyboard(Key::J), ButtonState::Press);
+    update(&mut controller);
+    assert!(matches!(controller.model.player.state, PlayerState::Attacking));
+
+}
+
+#[test]
+fn test_rebind_rebind(){
+    use rust_game::game::{Action, Binding};
+    use piston::input::{Button, ButtonState, Key};
+    use std::env;
+
+    let mut controller = common::setup_controller();
+    controller.bindings_path = Some(env::temp_dir().join("rust_game_rebind_rebind_test.txt"));
+
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Press);
+    common::button(&mut controller, Button::Keyboard(Key::P), ButtonState::Release);
+    // Rebind is the last Action
+    for _ in 0..20 {
+        common::button(&mut controller, Button::Keyboard(Key::F1), ButtonState::Press);
+        common::button(&mut controller, Button::Keyboard(Key::F1), ButtonState::Release);
+    }
+    assert_eq!(Some(Action::Rebind), controller.rebinding());
+
+    // Once Rebind is chosen, the next input is bound to it, F1 included
+    common::button(&mut controller, Button::Keyboard(Key::F2), ButtonState::Press);
+    common::button(&mut controller, Button::Keyboard(Key::F2), ButtonState::Release);
+    assert_eq!(None, controller.rebinding());
+    assert_eq!(&[Binding::Key(Key::F2)], controller.bindings.get(Action::Rebind));
+
+    common::button(&mut controller, Button::Keyboard(Key::F2), ButtonState::Press);
+    assert_eq!(Some(Action::MoveForward), controller.rebinding());
+}
@@ -0,0 +1,83 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_shape_interpolation(){
+    use rust_game::game::consts::PI;
+    use rust_game::math::Point2;
+    use rust_game::traits::draw::{GenericShape, ShapeVariant};
+
+    let mut shape = GenericShape::new(
+        ShapeVariant::Rect{width: 10.0, height: 10.0},
+        [1.0; 4],
+        Point2{x: 0.0, y: 0.0}
+    );
+    shape.set_rotation(2.0 * PI - 0.1);
+    shape.save_transform();
+    shape.set_position(Point2{x: 10.0, y: -4.0});
+    shape.set_rotation(0.1);
+
+    let halfway = shape.interpolate(0.5);
+    let position = halfway.get_position();
+    assert_eq!((5.0, -2.0), (position.x, position.y));
+    // Turning the short way round, through 0
+    let rotation = halfway.get_rotation().unwrap();
+    assert!((rotation - 2.0 * PI).abs() < 1e-9);
+
+    let now = shape.interpolate(1.0).get_position();
+    assert_eq!((10.0, -4.0), (now.x, now.y));
+
+}
+
+#[test]
+fn test_tick_keeps_previous_transform(){
+    use piston::input::{Button, ButtonState, Key};
+
+    let mut controller = common::setup_controller();
+    common::button(&mut controller, Button::Keyboard(Key::W), ButtonState::Press);
+    common::button(&mut controller, Button::Keyboard(Key::D), ButtonState::Press);
+    let start = controller.model.player.shape.get_position();
+
+    controller.tick(common::DT);
+    let shape = controller.model.player.shape;
+    let (end, before) = (shape.get_position(), shape.interpolate(0.0).get_position());
+    assert!(end.x != start.x || end.y != start.y);
+    assert_eq!((start.x, start.y), (before.x, before.y));
+    let halfway = shape.interpolate(0.5).get_position();
+    assert!((halfway.x - (start.x + end.x) / 2.0).abs() < 1e-9);
+    assert!((halfway.y - (start.y + end.y) / 2.0).abs() < 1e-9);
+
+}
+
+#[test]
+fn test_recall_doesnt_interpolate(){
+    use rust_game::game::consts::RECALL_CHANNEL_TIME;
+    use piston::input::{Button, ButtonState, Key};
+
+    let mut controller = common::setup_controller();
+    let start = controller.model.player.shape.get_position();
+    common::button(&mut controller, Button::Keyboard(Key::R), ButtonState::Press);
+    controller.tick(common::DT);
+    common::button(&mut controller, Button::Keyboard(Key::R), ButtonState::Release);
+
+    // Tick until the Recall completes
+    let mut time = common::DT;
+    while time < RECALL_CHANNEL_TIME + common::DT {
+        let position = controller.model.player.shape.get_position();
+        if position.x != start.x || position.y != start.y {
+            break;
+        }
+        controller.tick(common::DT);
+        time += common::DT;
+    }
+    let player = &controller.model.player;
+    let end = player.shape.get_position();
+    assert!(end.x != start.x || end.y != start.y);
+    // The Player appears at the Beacon straight away
+    let before = player.shape.interpolate(0.0).get_position();
+    assert_eq!((end.x, end.y), (before.x, before.y));
+    let (attack, before) = (player.attack.shape.get_position(), player.attack.shape.interpolate(0.0).get_position());
+    assert_eq!((attack.x, attack.y), (before.x, before.y));
+
+}
@@ -9,7 +9,7 @@ fn test_level_generation(){
     
     let lvl = common::setup_level();
     
-    // Check that all tiles are walls, floors, or spawners
+    // Check that all tiles are walls, floors, spawners or terrain
     for h in 0..lvl.height {
         for w in 0..lvl.width {
 
@@ -19,6 +19,9 @@ fn test_level_generation(){
                         TileVariant::Wall => true,
                         TileVariant::Floor => true,
                         TileVariant::Spawner => true,
+                        TileVariant::Mud => true,
+                        TileVariant::Water => true,
+                        TileVariant::Rubble => true,
                         _ => false
                     }
                 } else {
@@ -222,4 +225,147 @@ fn test_pathfinding(){
         panic!("Unable to find path from floor to floor")
     }
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_sweep_circle_rounds_corners(){
+
+    use rust_game::levels::map::sweep_circle;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::{TILE_SIZE, PLAYER_RADIUS};
+
+    let map = common::setup_map(&[
+        "#####",
+        "#...#",
+        "#.#.#",
+        "#...#",
+        "#####",
+    ]);
+
+    // Moving down past the corner of the middle wall, the circle is pushed
+    // aside rather than stopped.
+    let start = Point2{x: TILE_SIZE * 2.0 - PLAYER_RADIUS + 3.0, y: TILE_SIZE * 1.5};
+    let sweep = sweep_circle(&map, start, PLAYER_RADIUS, Point2{x: 0.0, y: TILE_SIZE});
+
+    assert!(sweep.blocked_x);
+    assert!(sweep.normal.is_some());
+    assert!(sweep.delta.x < 0.0);
+    assert!(start.y + sweep.delta.y > TILE_SIZE * 2.0);
+    assert!(start.x + sweep.delta.x + PLAYER_RADIUS <= TILE_SIZE * 2.0);
+
+}
+
+#[test]
+fn test_sweep_circle_doesnt_tunnel(){
+
+    use rust_game::levels::map::sweep_circle;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::{TILE_SIZE, PLAYER_RADIUS};
+
+    let map = common::setup_map(&[
+        "#######",
+        "#..#..#",
+        "#..#..#",
+        "#######",
+    ]);
+
+    let start = Point2{x: TILE_SIZE * 1.5, y: TILE_SIZE * 1.5};
+    let sweep = sweep_circle(&map, start, PLAYER_RADIUS, Point2{x: TILE_SIZE * 10.0, y: 0.0});
+
+    assert!(sweep.blocked_x);
+    assert_eq!(0.0, sweep.delta.y);
+    assert!(start.x + sweep.delta.x + PLAYER_RADIUS <= TILE_SIZE * 3.0);
+    assert!(start.x + sweep.delta.x + PLAYER_RADIUS > TILE_SIZE * 3.0 - 1.0);
+
+}
+
+#[test]
+fn test_level_from_ascii(){
+    use rust_game::levels::Level;
+    use rust_game::levels::map::MapIdx;
+    use rust_game::entity::tile::TileVariant;
+    use rust_game::math::random::create_seed;
+
+    let lvl = Level::from_ascii(&[
+        "#####",
+        "#B.S#",
+        "#P.",
+        "....#",
+    ], create_seed(true));
+    assert_eq!(5, lvl.width);
+    assert_eq!(4, lvl.height);
+    assert_eq!(vec![MapIdx::new(1, 1)], lvl.beacon_spawns);
+    assert_eq!(Some(MapIdx::new(1, 2)), lvl.player_spawn);
+    assert!(matches!(lvl.map[&MapIdx::new(1, 1)].variant, TileVariant::Floor));
+    assert!(matches!(lvl.map[&MapIdx::new(3, 1)].variant, TileVariant::Spawner));
+    // Short rows are padded with walls, and the rim is always walled.
+    assert!(matches!(lvl.map[&MapIdx::new(4, 2)].variant, TileVariant::Wall));
+    assert!(matches!(lvl.map[&MapIdx::new(0, 3)].variant, TileVariant::Wall));
+}
+
+#[test]
+fn test_diagonal_pathfinding(){
+
+    use rust_game::levels::map::{MapIdx, pathfind};
+    use rust_game::game::consts::{PATH_STEP_COST, PATH_DIAGONAL_COST};
+
+    let map = common::setup_map(&[
+        "#####",
+        "#...#",
+        "#...#",
+        "#...#",
+        "#####",
+    ]);
+
+    // Moving diagonally across open floor.
+    let (path, cost) = pathfind(&map, &MapIdx::new(1, 1), &MapIdx::new(3, 3)).unwrap();
+    assert_eq!(3, path.len());
+    assert_eq!(2 * PATH_DIAGONAL_COST, cost);
+
+    // Corners can't be cut, so the path has to go around the wall.
+    let map = common::setup_map(&[
+        "####",
+        "#..#",
+        "##.#",
+        "####",
+    ]);
+    let (path, cost) = pathfind(&map, &MapIdx::new(1, 1), &MapIdx::new(2, 2)).unwrap();
+    assert_eq!(vec![MapIdx::new(1, 1), MapIdx::new(2, 1), MapIdx::new(2, 2)], path);
+    assert_eq!(2 * PATH_STEP_COST, cost);
+
+}
+
+#[test]
+fn test_pathfinding_avoids_costly_terrain(){
+
+    use rust_game::levels::Level;
+    use rust_game::levels::map::{MapIdx, pathfind, smooth_path};
+    use rust_game::entity::tile::TileVariant;
+    use rust_game::math::random::create_seed;
+    use rust_game::game::consts::{map_idx_to_point2, ENEMY_SIZE};
+
+    let lvl = Level::from_ascii(&[
+        "#######",
+        "#.....#",
+        "#~~~~.#",
+        "#.....#",
+        "#######",
+    ], create_seed(true));
+    assert!(matches!(lvl.map[&MapIdx::new(1, 2)].variant, TileVariant::Water));
+
+    // Going around the water is cheaper than wading straight through it, and
+    // smoothing doesn't pull the path back into the water.
+    let (path, _cost) = pathfind(&lvl.map, &MapIdx::new(4, 1), &MapIdx::new(4, 3)).unwrap();
+    assert_eq!(vec![MapIdx::new(4, 1), MapIdx::new(5, 2), MapIdx::new(4, 3)], path);
+    let smoothed = smooth_path(&lvl.map, &path, ENEMY_SIZE);
+    assert_eq!(3, smoothed.len());
+
+    // Across open floor, the turn in the path is smoothed out.
+    let (path, _cost) = pathfind(&lvl.map, &MapIdx::new(1, 1), &MapIdx::new(4, 3)).unwrap();
+    assert!(path.len() > 2);
+    let smoothed = smooth_path(&lvl.map, &path, ENEMY_SIZE);
+    assert!(smoothed.len() < path.len());
+    assert_eq!(map_idx_to_point2(path[0]).x, smoothed[0].x);
+    assert_eq!(map_idx_to_point2(*path.last().unwrap()).x, smoothed.last().unwrap().x);
+
+}
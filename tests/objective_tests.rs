@@ -0,0 +1,173 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_beacons_from_map(){
+    use rust_game::game::Rules;
+    use rust_game::levels::map::MapIdx;
+
+    let model = common::setup_model(Rules::default());
+    assert_eq!(2, model.beacons.len());
+    assert_eq!(MapIdx::new(1, 1), model.beacons[0].idx);
+    assert_eq!(MapIdx::new(8, 1), model.beacons[1].idx);
+    // The spawner from the map is used instead of creating new ones.
+    assert_eq!(vec![MapIdx::new(1, 4)], model.spawners.iter().map(|spawner| spawner.idx).collect::<Vec<MapIdx>>());
+}
+
+#[test]
+fn test_enemies_target_nearest_beacon(){
+    use rust_game::game::{Rules, Targeting};
+    use rust_game::game::consts::BEACON_STARTING_HEALTH;
+
+    let mut model = common::setup_model(Rules {targeting: Targeting::Nearest, ..Rules::default()});
+    for _ in 0..10000 {
+        if !model.world.enemies.is_empty() {
+            break;
+        }
+        model.spawn_enemies();
+    }
+    let enemy = model.world.enemies.ids()[0];
+    assert_eq!(0, model.world.enemies[enemy].target);
+
+    // Once the Beacon falls, its enemies head for the other Beacon.
+    model.damage_beacon(0, BEACON_STARTING_HEALTH);
+    assert!(model.beacons[0].is_fallen());
+    assert_eq!(1, model.world.enemies[enemy].target);
+}
+
+#[test]
+fn test_lost_when_beacons_fall(){
+    use rust_game::game::{Outcome, Rules};
+    use rust_game::game::consts::BEACON_STARTING_HEALTH;
+
+    // By default every Beacon has to fall.
+    let mut model = common::setup_model(Rules::default());
+    model.damage_beacon(0, BEACON_STARTING_HEALTH);
+    assert_eq!(None, model.check_objective());
+    model.damage_beacon(1, BEACON_STARTING_HEALTH);
+    assert_eq!(Some(Outcome::Lost), model.check_objective());
+
+    let mut model = common::setup_model(Rules {beacon_losses: Some(1), ..Rules::default()});
+    model.damage_beacon(1, BEACON_STARTING_HEALTH);
+    assert_eq!(Some(Outcome::Lost), model.check_objective());
+}
+
+#[test]
+fn test_survive_and_hold_objectives(){
+    use rust_game::game::{Objective, Outcome, Rules};
+    use rust_game::game::consts::{WAVE_DURATION, WAVE_BREAK_DURATION};
+
+    let mut model = common::setup_model(Rules {objective: Objective::Hold(10.0), ..Rules::default()});
+    model.tick_beacons(5.0);
+    assert_eq!(None, model.check_objective());
+    model.tick_beacons(5.0);
+    assert_eq!(Some(Outcome::Won), model.check_objective());
+
+    let mut model = common::setup_model(Rules {objective: Objective::SurviveWaves(2), ..Rules::default()});
+    model.tick_beacons(WAVE_DURATION);
+    model.tick_beacons(WAVE_BREAK_DURATION);
+    assert_eq!(None, model.check_objective());
+    model.tick_beacons(WAVE_DURATION);
+    assert_eq!(Some(Outcome::Won), model.check_objective());
+}
+
+#[test]
+fn test_escort_follows_player(){
+    use rust_game::game::{Objective, Outcome, Rules};
+    use rust_game::math::Point2;
+
+    let mut model = common::setup_model(Rules {objective: Objective::Escort, ..Rules::default()});
+    assert!(!model.escort_path.is_empty());
+
+    // The Escort waits while the Player is far away.
+    let start = model.beacons[0].shape.center_point();
+    model.player.teleport(Point2{x: 1000.0, y: 1000.0});
+    model.tick_beacons(1.0);
+    assert_eq!(start.x, model.beacons[0].shape.center_point().x);
+    assert_eq!(start.y, model.beacons[0].shape.center_point().y);
+
+    // The Escort reaches its destination while the Player stays with it.
+    for _ in 0..1000 {
+        let beacon = model.beacons[0].shape.center_point();
+        model.player.teleport(beacon);
+        model.tick_beacons(0.1);
+        if model.check_objective().is_some() {
+            break;
+        }
+    }
+    assert_eq!(Some(Outcome::Won), model.check_objective());
+}
+
+#[test]
+fn test_results_are_deterministic(){
+    use rust_game::game::{GameController, Objective, Rules};
+    use rust_game::levels::Level;
+    use rust_game::math::random::create_seed;
+
+    let play = || {
+        let seed = create_seed(true);
+        let rules = Rules {objective: Objective::Hold(60.0), ..Rules::default()};
+        let mut controller = GameController::with_rules(Level::from_ascii(&common::ARENA, seed), seed, rules).unwrap();
+        for _ in 0..60 * 120 {
+            if !controller.check_state() {
+                break;
+            }
+            controller.tick(1.0 / 120.0);
+        }
+        controller.results
+    };
+
+    let results = play();
+    assert!(results.is_some());
+    assert_eq!(results, play());
+}
+
+#[test]
+fn test_destroying_spawners_clears_level(){
+    use rust_game::game::{Difficulty, Objective, Outcome, Rules};
+    use rust_game::entity::tile::TileVariant;
+    use rust_game::levels::map::MapIdx;
+
+    let rules = Rules {objective: Objective::ClearSpawners, difficulty: Difficulty::Easy, ..Rules::default()};
+    let mut model = common::setup_model(rules);
+    let health = Difficulty::Easy.spawner_health();
+    assert!(!model.damage_spawner(0, health - 1));
+    assert_eq!(None, model.check_objective());
+
+    // A destroyed spawner collapses into rubble and drops rewards.
+    assert!(model.damage_spawner(0, 1));
+    assert!(model.spawners.is_empty());
+    assert!(matches!(model.level.map[&MapIdx::new(1, 4)].variant, TileVariant::Rubble));
+    assert!(!model.world.pickups.is_empty());
+    assert_eq!(1, model.score().spawners);
+    assert_eq!(Some(Outcome::Won), model.check_objective());
+
+    // No new spawners are created while clearing them.
+    model.chanced_create_spawner(1000.0);
+    assert!(model.spawners.is_empty());
+}
+
+#[test]
+fn test_weighted_targeting_on_long_paths(){
+    use rust_game::game::{GameModel, Rules, Targeting};
+    use rust_game::levels::Level;
+    use rust_game::math::random::create_seed;
+
+    // Every path costs more than ten thousand, yet a Beacon is still picked.
+    let corridor = format!("#B{}S#", ".".repeat(1050));
+    let wall = "#".repeat(corridor.len());
+    let rows = [wall.as_str(), corridor.as_str(), wall.as_str()];
+    let seed = create_seed(true);
+    let rules = Rules {targeting: Targeting::Weighted, beacons: 1, ..Rules::default()};
+    let mut model = GameModel::with_rules(Level::from_ascii(&rows, seed), seed, rules).unwrap();
+    for _ in 0..10000 {
+        if !model.world.enemies.is_empty() {
+            break;
+        }
+        model.spawn_enemies();
+    }
+    assert!(!model.world.enemies.is_empty());
+    let enemy = model.world.enemies.ids()[0];
+    assert_eq!(0, model.world.enemies[enemy].target);
+}
@@ -0,0 +1,171 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_dash_is_invulnerable(){
+    use rust_game::entity::player::{Player, PlayerState};
+    use rust_game::entity::abilities::AbilityKind;
+    use rust_game::traits::entity::Entity;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::{DASH_DURATION, PLAYER_STARTING_HEALTH};
+
+    let mut player = Player::new(Point2{x: 0.0, y: 0.0});
+    assert!(player.use_ability(AbilityKind::Dash));
+    assert!(player.is_invulnerable());
+    player.damage(1);
+    assert_eq!(PLAYER_STARTING_HEALTH, player.health.current);
+
+    // The dash can't be used again until it's cooled down.
+    player.tick(DASH_DURATION + 0.01);
+    assert!(!matches!(player.state, PlayerState::Dashing));
+    assert!(!player.use_ability(AbilityKind::Dash));
+    player.damage(1);
+    assert_eq!(PLAYER_STARTING_HEALTH - 1, player.health.current);
+}
+
+#[test]
+fn test_shield_absorbs_hits(){
+    use rust_game::entity::player::Player;
+    use rust_game::entity::abilities::AbilityKind;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::{SHIELD_HITS, PLAYER_STARTING_HEALTH};
+
+    let mut player = Player::new(Point2{x: 0.0, y: 0.0});
+    assert!(player.use_ability(AbilityKind::Shield));
+    for _ in 0..SHIELD_HITS {
+        player.damage(1);
+    }
+    assert_eq!(PLAYER_STARTING_HEALTH, player.health.current);
+    assert!(!player.is_shielded());
+    player.damage(1);
+    assert_eq!(PLAYER_STARTING_HEALTH - 1, player.health.current);
+}
+
+#[test]
+fn test_recall_channel(){
+    use rust_game::entity::player::Player;
+    use rust_game::entity::abilities::AbilityKind;
+    use rust_game::traits::entity::Entity;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::{RECALL_CHANNEL_TIME, RECALL_COOLDOWN};
+
+    // Taking damage interrupts the channel.
+    let mut player = Player::new(Point2{x: 0.0, y: 0.0});
+    assert!(player.use_ability(AbilityKind::Recall));
+    player.damage(1);
+    player.tick(RECALL_CHANNEL_TIME);
+    assert!(!player.take_recall());
+
+    // An uninterrupted channel completes once.
+    player.tick(RECALL_COOLDOWN);
+    assert!(player.use_ability(AbilityKind::Recall));
+    player.tick(RECALL_CHANNEL_TIME + 0.01);
+    assert!(player.take_recall());
+    assert!(!player.take_recall());
+}
+
+#[test]
+fn test_invulnerability_frames(){
+    use rust_game::entity::player::Player;
+    use rust_game::entity::health::DamageOutcome;
+    use rust_game::traits::entity::Entity;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::{PLAYER_INVULNERABILITY, PLAYER_STARTING_HEALTH};
+
+    let mut player = Player::new(Point2{x: 0.0, y: 0.0});
+    assert_eq!(DamageOutcome::Damaged, player.damage(2));
+    assert_eq!(DamageOutcome::Ignored, player.damage(2));
+    assert_eq!(PLAYER_STARTING_HEALTH - 2, player.health.current);
+
+    player.tick(PLAYER_INVULNERABILITY);
+    assert_eq!(DamageOutcome::Damaged, player.damage(2));
+    assert_eq!(PLAYER_STARTING_HEALTH - 4, player.health.current);
+    assert_eq!(player.health.fraction(), player.health_bar.fraction());
+}
+
+#[test]
+fn test_fire_uses_ammo(){
+    use rust_game::entity::player::Player;
+    use rust_game::traits::entity::Entity;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::{PLAYER_FIRE_COOLDOWN, PLAYER_STARTING_AMMO};
+
+    let mut player = Player::new(Point2{x: 0.0, y: 0.0});
+    assert!(player.fire().is_some());
+    assert_eq!(PLAYER_STARTING_AMMO - 1, player.ammo);
+
+    // The next shot has to wait for the cooldown.
+    assert!(player.fire().is_none());
+    player.tick(PLAYER_FIRE_COOLDOWN + 0.01);
+    assert!(player.fire().is_some());
+    assert_eq!(PLAYER_STARTING_AMMO - 2, player.ammo);
+
+    // Without ammo, nothing is fired.
+    player.ammo = 0;
+    player.tick(PLAYER_FIRE_COOLDOWN + 0.01);
+    assert!(player.fire().is_none());
+    assert_eq!(0, player.ammo);
+}
+
+#[test]
+fn test_cant_fire_while_attacking(){
+    use rust_game::entity::player::Player;
+    use rust_game::math::Point2;
+    use rust_game::game::consts::PLAYER_STARTING_AMMO;
+
+    let mut player = Player::new(Point2{x: 0.0, y: 0.0});
+    player.attack();
+    assert!(player.fire().is_none());
+    assert_eq!(PLAYER_STARTING_AMMO, player.ammo);
+}
+
+#[test]
+fn test_ammo_pickup(){
+    use rust_game::entity::drops::{spawn_resource, ResourceVariant};
+    use rust_game::game::consts::AMMO_PER_PICKUP;
+
+    let mut controller = common::setup_controller();
+    let ammo = controller.model.player.ammo;
+    let position = controller.model.player.shape.center_point();
+    spawn_resource(&mut controller.model.world, position, ResourceVariant::Ammo);
+    controller.tick(common::DT);
+    assert_eq!(ammo + AMMO_PER_PICKUP, controller.model.player.ammo);
+    assert!(controller.model.world.pickups.is_empty());
+}
+
+#[test]
+fn test_bullets_despawn_on_walls(){
+    use rust_game::math::Vec2;
+
+    // Fire at the left wall, which is a few Tiles away.
+    let mut controller = common::setup_controller();
+    controller.model.player.direction = Vec2::new(-1.0, 0.0);
+    controller.model.fire_projectile();
+    assert_eq!(1, controller.model.world.projectiles.len());
+    let mut last = controller.model.player.shape.center_point();
+    for _ in 0..60 {
+        controller.tick(common::DT);
+        let world = &controller.model.world;
+        for id in world.projectiles.ids() {
+            last = world.shapes[id].center_point();
+        }
+    }
+    assert!(controller.model.world.projectiles.is_empty());
+    // It was destroyed in the wall, not by leaving the Level.
+    assert!(last.x > 0.0);
+}
+
+#[test]
+fn test_cant_attack_while_dashing(){
+    use rust_game::entity::player::{Player, PlayerState};
+    use rust_game::entity::attack::AttackPhase;
+    use rust_game::entity::abilities::AbilityKind;
+    use rust_game::math::Point2;
+
+    let mut player = Player::new(Point2{x: 0.0, y: 0.0});
+    assert!(player.use_ability(AbilityKind::Dash));
+    player.attack();
+    assert!(matches!(player.state, PlayerState::Dashing));
+    assert_eq!(AttackPhase::Ready, player.attack.phase);
+}
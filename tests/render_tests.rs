@@ -0,0 +1,77 @@
+extern crate rust_game;
+
+mod common;
+
+use rust_game::game::{GameController, TextureAtlas};
+use rust_game::render::{SoftwareGraphics, SoftwareTexture};
+
+/// The width and height of the ARENA, in pixels.
+const ARENA_SIZE: (u32, u32) = (200, 120);
+
+/// How far apart the channels of a pixel can be before it counts as changed.
+const TOLERANCE: u8 = 2;
+
+/// Draws the GameController's view of the ARENA, with or without sprites.
+fn render(controller: &mut GameController, sprites: Option<TextureAtlas<SoftwareTexture>>) -> SoftwareGraphics {
+    use rust_game::game::ViewTextures;
+
+    let mut textures = ViewTextures::new(sprites);
+    let mut image = SoftwareGraphics::new(ARENA_SIZE.0, ARENA_SIZE.1);
+    image.draw(|c, g| {
+        graphics::clear([1.0; 4], g);
+        controller.view.draw(&controller.model, &mut textures, 1.0, &c, g);
+    });
+    image
+
+}
+
+/// Checks an image against the golden image of the same name in
+/// tests/golden. Run with UPDATE_GOLDEN set to save the image as the new
+/// golden image instead, after a change to how the game looks.
+fn assert_golden(image: &SoftwareGraphics, name: &str) {
+    use graphics::ImageSize;
+    use std::env;
+    use std::path::Path;
+
+    let path = Path::new("tests/golden").join(name);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        image.save(&path).unwrap();
+        return;
+    }
+
+    let golden = SoftwareTexture::from_path(&path)
+        .unwrap_or_else(|error| panic!("Couldn't load {}: {}", path.display(), error));
+    assert_eq!(image.size(), (golden.get_width(), golden.get_height()));
+    let (width, height) = image.size();
+    let changed = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| {
+            let (a, b) = (image.pixel(x, y), golden.pixel(x, y));
+            a.iter().zip(b.iter()).any(|(a, b)| (*a as i16 - *b as i16).abs() > TOLERANCE as i16)
+        })
+        .count();
+    assert_eq!(0, changed, "{} pixels differ from {}", changed, path.display());
+
+}
+
+#[test]
+fn test_render_shapes(){
+
+    let mut controller = common::setup_controller();
+    controller.model.fog = false;
+    let image = render(&mut controller, None);
+    assert_golden(&image, "arena_shapes.png");
+
+}
+
+#[test]
+fn test_render_sprites(){
+    use rust_game::game::consts::SPRITE_SHEET_PATH;
+
+    let mut controller = common::setup_controller();
+    controller.model.fog = false;
+    let sprites = TextureAtlas::<SoftwareTexture>::load(SPRITE_SHEET_PATH).unwrap();
+    let image = render(&mut controller, Some(sprites));
+    assert_golden(&image, "arena_sprites.png");
+
+}
@@ -0,0 +1,94 @@
+extern crate rust_game;
+
+mod common;
+
+use rust_game::ecs::EntityId;
+use rust_game::game::GameModel;
+use rust_game::levels::map::MapIdx;
+use rust_game::math::Point2;
+
+/// Steers and moves every Enemy in the model for a number of ticks.
+fn run(model: &mut GameModel, ticks: usize) {
+    for _ in 0..ticks {
+        model.steer_enemies();
+        model.move_enemies(0.02);
+    }
+}
+
+/// Creates an Enemy at start which heads for goal.
+fn heading_for(model: &mut GameModel, start: Point2, goal: MapIdx) -> EntityId {
+    use rust_game::entity::enemy::spawn_enemy;
+    use rust_game::game::consts::map_idx_to_point2;
+
+    let enemy = spawn_enemy(&mut model.world, start);
+    model.world.enemies[enemy].path = vec![map_idx_to_point2(goal)];
+    enemy
+}
+
+/// Returns how far apart the centers of Enemies a and b are.
+fn distance(model: &GameModel, a: EntityId, b: EntityId) -> f64 {
+    let apart = model.world.shapes[a].center_point() - model.world.shapes[b].center_point();
+    apart.x.abs() + apart.y.abs()
+}
+
+#[test]
+fn test_enemies_spread_apart(){
+    use rust_game::game::Rules;
+    use rust_game::game::consts::{map_idx_to_point2, ENEMY_RADIUS};
+
+    let start = map_idx_to_point2(MapIdx::new(2, 2));
+    let goal = MapIdx::new(8, 2);
+
+    let mut model = common::setup_model(Rules::default());
+    let (a, b) = (heading_for(&mut model, start, goal), heading_for(&mut model, start, goal));
+    run(&mut model, 10);
+    assert!(distance(&model, a, b) > ENEMY_RADIUS);
+
+    // Without steering, the Enemies stay stacked on top of each other.
+    let mut model = common::setup_model(Rules::default());
+    model.steering = false;
+    let (a, b) = (heading_for(&mut model, start, goal), heading_for(&mut model, start, goal));
+    run(&mut model, 10);
+    assert_eq!(0.0, distance(&model, a, b));
+    assert!(model.world.enemies.iter().all(|(_, ai)| ai.steering.is_none()));
+
+}
+
+#[test]
+fn test_enemies_steer_off_walls(){
+    use rust_game::game::Rules;
+    use rust_game::game::consts::{ENEMY_RADIUS, TILE_SIZE};
+
+    // Start overlapping the left wall and head down alongside it.
+    let start = Point2{x: TILE_SIZE - 4.0, y: TILE_SIZE * 2.0};
+    let mut model = common::setup_model(Rules::default());
+    let enemy = heading_for(&mut model, start, MapIdx::new(1, 4));
+    run(&mut model, 10);
+
+    let center = model.world.shapes[enemy].center_point();
+    assert!(center.x - ENEMY_RADIUS > TILE_SIZE - 1.0);
+    assert!(center.y > start.y + ENEMY_RADIUS);
+
+}
+
+#[test]
+fn test_knockback_stops_at_walls(){
+    use rust_game::ecs::systems;
+    use rust_game::entity::enemy::hit_enemy;
+    use rust_game::game::Rules;
+    use rust_game::game::consts::{ENEMY_RADIUS, TILE_SIZE};
+    use rust_game::math::Vec2;
+
+    // Knock an Enemy beside the left wall hard into it.
+    let mut model = common::setup_model(Rules::default());
+    let enemy = common::enemy_at(&mut model, common::tile_center(MapIdx::new(1, 2)));
+    assert!(model.level.map[&MapIdx::new(0, 2)].variant.is_solid());
+    hit_enemy(&mut model.world, enemy, 0, Vec2::new(-5000.0, 0.0));
+    systems::movement(&mut model.world, &model.level.map, 0.02);
+
+    let center = model.world.shapes[enemy].center_point();
+    assert!(center.x - ENEMY_RADIUS > TILE_SIZE - 1.0);
+    // The knockback into the wall is spent.
+    assert_eq!(0.0, model.world.velocities[enemy].knockback.x);
+
+}
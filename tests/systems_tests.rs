@@ -0,0 +1,57 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_projectile_hits_one_enemy(){
+    use rust_game::ecs::systems::{self, Hit};
+    use rust_game::game::Rules;
+    use rust_game::levels::map::MapIdx;
+
+    let mut model = common::setup_model(Rules::default());
+    let center = common::tile_center(MapIdx::new(6, 2));
+    let a = common::enemy_at(&mut model, center);
+    let b = common::enemy_at(&mut model, center);
+    let projectile = common::projectile_at(&mut model, center);
+    let full = model.world.healths[b].current;
+
+    model.index_enemies();
+    let hits = systems::collide(&mut model.world, &model.enemy_grid, &model.level.map, &model.spawners);
+    assert_eq!(1, hits.len());
+    // Killing the Enemy is left to the caller
+    assert!(matches!(hits[0], Hit::Enemy{id, killed: true, by_tower: false, ..} if id == a));
+    assert!(!model.world.is_despawning(a));
+    assert_eq!(full, model.world.healths[b].current);
+    assert!(model.world.is_despawning(projectile));
+
+}
+
+#[test]
+fn test_tower_shoots_enemy(){
+    use rust_game::ecs::systems::{self, Hit};
+    use rust_game::entity::towers::tower::{TowerState, spawn_tower};
+    use rust_game::game::Rules;
+    use rust_game::game::consts::map_idx_to_point2;
+    use rust_game::levels::map::MapIdx;
+
+    let mut model = common::setup_model(Rules::default());
+    let tower = spawn_tower(&mut model.world, map_idx_to_point2(MapIdx::new(5, 2)));
+    let enemy = common::enemy_at(&mut model, common::tile_center(MapIdx::new(8, 2)));
+    model.index_enemies();
+
+    systems::target(&mut model.world, &model.enemy_grid, &model.level.map, &model.spawners, common::DT);
+    assert!(matches!(model.world.towers[tower].state, TowerState::Attacking));
+
+    // The bullet reaches the Enemy, and the Tower is Ready to fire again
+    let mut hit = None;
+    for _ in 0..120 {
+        systems::target(&mut model.world, &model.enemy_grid, &model.level.map, &model.spawners, common::DT);
+        hit = systems::collide(&mut model.world, &model.enemy_grid, &model.level.map, &model.spawners).pop();
+        if hit.is_some() {
+            break;
+        }
+    }
+    assert!(matches!(hit, Some(Hit::Enemy{id, by_tower: true, ..}) if id == enemy));
+    assert!(matches!(model.world.towers[tower].state, TowerState::Ready));
+
+}
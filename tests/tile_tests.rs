@@ -0,0 +1,155 @@
+extern crate rust_game;
+
+mod common;
+
+#[test]
+fn test_doors_block_pathing(){
+    use rust_game::entity::enemy::spawn_enemy;
+    use rust_game::entity::tile::TileVariant;
+    use rust_game::game::Rules;
+    use rust_game::game::consts::map_idx_to_point2;
+    use rust_game::levels::map::{MapIdx, pathfind, pathfind_breaching};
+
+    let mut model = common::setup_model_from(&[
+        "#########",
+        "#B..D..S#",
+        "#...#...#",
+        "#P..#...#",
+        "#########",
+    ], Rules::default());
+    let (start, beacon) = (MapIdx::new(6, 2), MapIdx::new(1, 1));
+    assert!(pathfind(&model.level.map, &start, &beacon).is_none());
+    // Doors can't be broken through.
+    assert!(pathfind_breaching(&model.level.map, &start, &beacon).is_none());
+
+    let enemy = spawn_enemy(&mut model.world, map_idx_to_point2(start));
+    model.world.enemies[enemy].path = vec![map_idx_to_point2(MapIdx::new(6, 3))];
+
+    // Out of range of the Player, the Door stays shut.
+    assert!(!model.toggle_door());
+    model.player.teleport(common::tile_center(MapIdx::new(3, 1)));
+    assert!(model.toggle_door());
+    assert!(matches!(model.level.map[&MapIdx::new(4, 1)].variant, TileVariant::Door{open: true}));
+    assert!(pathfind(&model.level.map, &start, &beacon).is_some());
+    assert!(!model.world.enemies[enemy].path.is_empty());
+
+    // Closing the Door again leaves the Enemy with nowhere to go, but it
+    // can't be closed on anyone.
+    model.player.teleport(common::tile_center(MapIdx::new(4, 1)));
+    assert!(!model.toggle_door());
+    model.player.teleport(common::tile_center(MapIdx::new(3, 1)));
+    assert!(model.toggle_door());
+    assert!(model.world.enemies[enemy].path.is_empty());
+
+}
+
+#[test]
+fn test_enemies_breach_walls(){
+    use rust_game::entity::tile::TileVariant;
+    use rust_game::game::Rules;
+    use rust_game::game::consts::DESTRUCTIBLE_WALL_HEALTH;
+    use rust_game::levels::map::MapIdx;
+
+    let mut model = common::setup_model_from(&[
+        "#########",
+        "#B..+..S#",
+        "#...#...#",
+        "#P..#...#",
+        "#########",
+    ], Rules::default());
+    let wall = MapIdx::new(4, 1);
+    for _ in 0..10000 {
+        if !model.world.enemies.is_empty() {
+            break;
+        }
+        model.spawn_enemies();
+    }
+    let enemy = model.world.enemies.ids()[0];
+    assert_eq!(Some(wall), model.world.enemies[enemy].breach);
+
+    for _ in 0..1000 {
+        model.steer_enemies();
+        model.move_enemies(0.02);
+        model.tick_breaches(0.02);
+        if matches!(model.level.map[&wall].variant, TileVariant::Rubble) {
+            break;
+        }
+    }
+    assert!(matches!(model.level.map[&wall].variant, TileVariant::Rubble));
+    // Once through, the Enemy heads for the Beacon.
+    assert_eq!(None, model.world.enemies[enemy].breach);
+    assert!(!model.world.enemies[enemy].path.is_empty());
+
+    // Damage short of the wall's health doesn't bring it down.
+    let mut model = common::setup_model_from(&["######", "#B+.S#", "######"], Rules::default());
+    assert!(!model.damage_wall(MapIdx::new(2, 1), DESTRUCTIBLE_WALL_HEALTH - 1));
+    assert!(model.damage_wall(MapIdx::new(2, 1), 1));
+
+}
+
+#[test]
+fn test_traps(){
+    use rust_game::entity::enemy::spawn_enemy;
+    use rust_game::entity::tile::TrapKind;
+    use rust_game::game::Rules;
+    use rust_game::game::consts::{map_idx_to_point2, ENEMY_STARTING_HEALTH, SPIKE_TRAP_DAMAGE};
+    use rust_game::levels::map::MapIdx;
+
+    let mut model = common::setup_model_from(&[
+        "#########",
+        "#B......#",
+        "#.P....S#",
+        "#########",
+    ], Rules::default());
+    let trap = MapIdx::new(2, 2);
+
+    // Traps cost resources.
+    model.player.resources = 0;
+    assert!(!model.build_trap(TrapKind::Spike));
+    model.player.resources = 2;
+    assert!(model.build_trap(TrapKind::Spike));
+    assert_eq!(vec![trap], model.traps);
+    // Only one trap fits on a Tile.
+    assert!(!model.build_trap(TrapKind::Slow));
+
+    let enemy = spawn_enemy(&mut model.world, map_idx_to_point2(trap));
+    model.index_enemies();
+    model.tick_traps(0.02);
+    assert_eq!(ENEMY_STARTING_HEALTH - SPIKE_TRAP_DAMAGE, model.world.healths[enemy].current);
+    // The trap has to cool down before it strikes again.
+    model.tick_traps(0.02);
+    assert_eq!(ENEMY_STARTING_HEALTH - SPIKE_TRAP_DAMAGE, model.world.healths[enemy].current);
+
+    // Slow traps slow down anything crossing them, but don't change paths.
+    model.player.teleport(common::tile_center(MapIdx::new(4, 1)));
+    assert!(model.build_trap(TrapKind::Slow));
+    let slow = &model.level.map[&MapIdx::new(4, 1)].variant;
+    assert!(slow.speed() < 1.0);
+    assert_eq!(model.level.map[&MapIdx::new(5, 1)].variant.cost(false), slow.cost(false));
+
+}
+
+#[test]
+fn test_wall_masks(){
+    use rust_game::levels::Level;
+    use rust_game::levels::map::{MapIdx, wall_mask};
+    use rust_game::math::random::create_seed;
+
+    let level = Level::from_ascii(&[
+        "#####",
+        "#...#",
+        "#.#.#",
+        "#.#+#",
+        "#####",
+    ], create_seed(true));
+
+    // A lone wall only joins the wall below it.
+    assert_eq!(4, wall_mask(&level.map, MapIdx::new(2, 2)));
+    // Walls on the edge join up with the outside of the Map.
+    assert_eq!(1 | 4 | 8, wall_mask(&level.map, MapIdx::new(0, 2)));
+    assert_eq!(1 | 2 | 4 | 8, wall_mask(&level.map, MapIdx::new(0, 0)));
+    // DestructibleWalls aren't joined to.
+    assert_eq!(1 | 4, wall_mask(&level.map, MapIdx::new(2, 3)));
+    assert_eq!(2 | 4 | 8, wall_mask(&level.map, MapIdx::new(3, 4)));
+
+}